@@ -1,6 +1,9 @@
 use crate::assets::Assets;
 use crate::data::game_data::GameData;
+use crate::game::station::generate_stations;
 use crate::game::world::World;
+use crate::savefile::WorldError;
+use crate::scenes::data_errors::DataErrors;
 use crate::scenes::main_menu::MainMenu;
 use crate::scenes::{GameScene, Scene, Transition};
 use crate::settings::Settings;
@@ -14,6 +17,8 @@ pub struct App {
     pub assets: Rc<Assets>,
     pub data: Rc<GameData>,
     pub world: Option<Rc<RefCell<World>>>,
+    /// Mods for new worlds, every installed one
+    default_mods: Vec<String>,
     default_title: String,
     current_fps: u8,
 }
@@ -22,9 +27,18 @@ impl App {
     pub fn new(ctx: &mut Context) -> Result<Self> {
         let assets = Rc::new(Assets::load(ctx)?);
         let data = Rc::new(GameData::load());
+        for error in data.errors.iter() {
+            println!("{}", error);
+        }
+        let first_scene: Box<dyn Scene> = if data.has_fatal_errors() {
+            Box::new(DataErrors::new(&assets, &data.errors, Transition::Quit))
+        } else {
+            Box::new(MainMenu::new(&assets))
+        };
         let mut game = Self {
-            scenes: vec![Box::new(MainMenu::new(&assets))],
+            scenes: vec![first_scene],
             assets,
+            default_mods: data.mod_ids(),
             data,
             default_title: window::get_title(ctx).to_string(),
             current_fps: 60,
@@ -78,6 +92,43 @@ impl App {
         self.on_open(ctx);
     }
 
+    /// Reloads game data if mods are different, false if they can't be loaded
+    fn use_mods(&mut self, ctx: &mut Context, mods: &[String]) -> bool {
+        if self.data.mod_ids() == mods {
+            return true;
+        }
+        let data = GameData::load_with(mods);
+        for error in data.errors.iter() {
+            println!("{}", error);
+        }
+        if data.has_fatal_errors() {
+            self.push_scene(ctx, GameScene::DataErrors(data.errors));
+            return false;
+        }
+        self.data = Rc::new(data);
+        true
+    }
+
+    /// Clears the stack of scenes, new worlds are created with every installed mod
+    fn to_main_menu(&mut self, ctx: &mut Context) {
+        self.scenes.clear();
+        self.push_scene(ctx, GameScene::MainMenu);
+        let default_mods = self.default_mods.clone();
+        self.use_mods(ctx, &default_mods);
+    }
+
+    /// World which can't be saved stays loaded and the error scene goes over the game,
+    /// otherwise the world is left as it is on the disk and the scene is closed to main menu
+    fn show_error(&mut self, ctx: &mut Context, error: WorldError) {
+        if matches!(error, WorldError::Save(..)) && self.world.is_some() {
+            self.push_scene(ctx, GameScene::WorldError(error));
+            return;
+        }
+        self.world = None;
+        self.to_main_menu(ctx);
+        self.push_scene(ctx, GameScene::WorldError(error));
+    }
+
     fn current_scene(&mut self) -> Option<&mut Box<dyn Scene>> {
         self.scenes.last_mut()
     }
@@ -85,27 +136,61 @@ impl App {
     fn transit(&mut self, ctx: &mut Context, transition: Transition) {
         match transition {
             Transition::DoNothing => {}
-            Transition::CreateWorld(savefile) => {
-                let world = World::create(&savefile, &self.data);
-                world.save();
-                self.world = Some(Rc::new(RefCell::new(world)));
-                self.replace_scene(ctx, GameScene::ShipWalk);
-            }
+            Transition::CreateWorld(savefile) => match World::create(&savefile, &self.data) {
+                Ok(world) => {
+                    let world = world.with_autosave(Settings::instance().game.autosave_interval);
+                    match world.save() {
+                        Ok(_) => {
+                            self.world = Some(Rc::new(RefCell::new(world)));
+                            self.replace_scene(ctx, GameScene::ShipWalk);
+                        }
+                        Err(e) => self.show_error(ctx, WorldError::Save(world.path, e)),
+                    }
+                }
+                Err(e) => self.show_error(ctx, WorldError::Load(savefile.path, e)),
+            },
             Transition::LoadWorld(savefile) => {
-                if let Ok(world) = savefile.load_world() {
-                    self.world = Some(Rc::new(RefCell::new(world)));
-                    self.replace_scene(ctx, GameScene::ShipWalk);
-                } else {
-                    // TODO: show message and go to main menu
-                    panic!("Can't load world: {:?}", savefile.path)
+                if !self.use_mods(ctx, &savefile.mods) {
+                    return;
+                }
+                match savefile.load_world() {
+                    Ok(mut world) => {
+                        // saves made before factions and stations were introduced
+                        if world.galaxy.factions.is_empty() {
+                            world.galaxy.generate_factions(&self.data.names);
+                        }
+                        if world.stations.is_empty() {
+                            world.stations =
+                                generate_stations(&world.galaxy, &self.data, world.current_tick);
+                        }
+                        world.refit_legacy_ship(&self.data);
+                        let world =
+                            world.with_autosave(Settings::instance().game.autosave_interval);
+                        self.world = Some(Rc::new(RefCell::new(world)));
+                        self.replace_scene(ctx, GameScene::ShipWalk);
+                    }
+                    Err(e) => self.show_error(ctx, WorldError::Load(savefile.path, e)),
                 }
             }
             Transition::UnloadWorld => {
-                if let Some(world) = &self.world {
-                    world.borrow().save();
+                let saved = self.world.as_ref().map(|world| {
+                    let world = world.borrow();
+                    world.save().map_err(|e| (world.path.clone(), e))
+                });
+                match saved {
+                    Some(Err((path, e))) => self.show_error(ctx, WorldError::Save(path, e)),
+                    _ => {
+                        self.world = None;
+                        self.to_main_menu(ctx);
+                    }
                 }
-                self.scenes.clear();
-                self.push_scene(ctx, GameScene::MainMenu);
+            }
+            Transition::DropWorld => {
+                self.world = None;
+                self.to_main_menu(ctx);
+            }
+            Transition::WorldError(error) => {
+                self.show_error(ctx, error);
             }
             Transition::Push(s) => {
                 self.push_scene(ctx, s);
@@ -214,7 +299,10 @@ impl Drop for App {
     fn drop(&mut self) {
         Settings::instance().save();
         if let Some(world) = &self.world {
-            world.borrow().save();
+            if let Err(e) = world.borrow().save() {
+                println!("Can't save world to {:?}: {}", world.borrow().path, e);
+                // TODO: log
+            }
         }
     }
 }
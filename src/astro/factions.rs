@@ -0,0 +1,364 @@
+use crate::astro::galaxy::Galaxy;
+use crate::astro::galaxy_generator::CORE_MAX_STARS;
+use crate::data::names_pack::NamesPack;
+use geometry::{Point, DIR8};
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Factions use their own random sequence, so adding them doesn't change anything else
+const SEED_SALT: u64 = 0xFAC7_1055;
+/// How far faction can reach, in quadrants of average density per galaxy size
+const REACH: f32 = 1.0;
+/// Second claim is as strong as the first one if it costs no more than this much more
+const CONTESTED_MARGIN: f32 = 0.15;
+const SUFFIXES: [&str; 10] = [
+    "Union",
+    "Republic",
+    "Dominion",
+    "Confederacy",
+    "Syndicate",
+    "Hegemony",
+    "Commonwealth",
+    "League",
+    "Directorate",
+    "Compact",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Faction {
+    pub id: u8,
+    pub name: String,
+    /// Id of the `NamesPack` used for people of this faction
+    pub names_pack: String,
+    pub home: Point,
+    /// Stronger factions expand farther and win contested quadrants
+    pub strength: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Territory {
+    #[default]
+    Unclaimed,
+    Owned(u8),
+    /// Two factions claim the quadrant, first one has the stronger claim
+    Contested(u8, u8),
+}
+
+impl Territory {
+    pub fn is_unclaimed(&self) -> bool {
+        matches!(self, Territory::Unclaimed)
+    }
+
+    pub fn is_contested(&self) -> bool {
+        matches!(self, Territory::Contested(..))
+    }
+
+    /// Faction with the strongest claim
+    pub fn owner(&self) -> Option<u8> {
+        match self {
+            Territory::Unclaimed => None,
+            Territory::Owned(id) | Territory::Contested(id, _) => Some(*id),
+        }
+    }
+
+    pub fn claimed_by(&self, faction: u8) -> bool {
+        match self {
+            Territory::Unclaimed => false,
+            Territory::Owned(id) => *id == faction,
+            Territory::Contested(a, b) => *a == faction || *b == faction,
+        }
+    }
+}
+
+pub fn factions_count(size: usize) -> usize {
+    ((size as f32).sqrt() / 2.0) as usize
+}
+
+/// Crossing dense quadrants is cheap, crossing the void is expensive
+fn step_cost(stars_count: u32) -> f32 {
+    1.0 / (stars_count as f32 / CORE_MAX_STARS as f32 + 0.05)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Claim {
+    cost: f32,
+    index: usize,
+    faction: u8,
+}
+
+impl Eq for Claim {}
+
+impl Ord for Claim {
+    // reversed, BinaryHeap should pop the cheapest claim first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.faction.cmp(&self.faction))
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Claim {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn faction_name(rng: &mut StdRng, pack: &NamesPack, taken: &[Faction]) -> String {
+    let mut name = String::new();
+    for _ in 0..100 {
+        name = format!(
+            "{} {}",
+            pack.last_names_male[rng.gen_range(0..pack.last_names_male.len())],
+            SUFFIXES[rng.gen_range(0..SUFFIXES.len())]
+        );
+        if taken.iter().all(|f| f.name != name) {
+            break;
+        }
+    }
+    name
+}
+
+fn choose_homes(rng: &mut StdRng, size: usize, stars: &[u32], count: usize) -> Vec<Point> {
+    let mut homes: Vec<Point> = Vec::with_capacity(count);
+    let weights = match WeightedIndex::new(stars) {
+        Ok(weights) => weights,
+        Err(_) => return homes,
+    };
+    let mut min_distance = size as f32 / (count as f32 + 1.0);
+    while homes.len() < count && min_distance >= 1.0 {
+        for _ in 0..100 {
+            let i = weights.sample(rng);
+            let point = Point::new((i / size) as i32, (i % size) as i32);
+            if homes
+                .iter()
+                .all(|h| f32::hypot((h.x - point.x) as f32, (h.y - point.y) as f32) >= min_distance)
+            {
+                homes.push(point);
+                break;
+            }
+        }
+        // galaxy is too small or too sparse for such a distance
+        min_distance *= 0.9;
+    }
+    homes
+}
+
+/// Dijkstra from every home at once, every quadrant keeps two cheapest claims of different factions
+fn claim_territories(size: usize, stars: &[u32], factions: &[Faction]) -> Vec<Territory> {
+    let budget = size as f32 * REACH;
+    let mut first: Vec<Option<(u8, f32)>> = vec![None; stars.len()];
+    let mut second: Vec<Option<(u8, f32)>> = vec![None; stars.len()];
+    let mut heap = BinaryHeap::new();
+    for faction in factions.iter() {
+        heap.push(Claim {
+            cost: 0.0,
+            index: faction.home.x as usize * size + faction.home.y as usize,
+            faction: faction.id,
+        });
+    }
+    while let Some(Claim {
+        cost,
+        index,
+        faction,
+    }) = heap.pop()
+    {
+        match (first[index], second[index]) {
+            (None, _) => first[index] = Some((faction, cost)),
+            (Some((f, _)), None) if f != faction => second[index] = Some((faction, cost)),
+            _ => continue,
+        }
+        let strength = factions[faction as usize].strength;
+        let point = Point::new((index / size) as i32, (index % size) as i32);
+        for dir in DIR8 {
+            let neighbour = point + dir;
+            if neighbour.x < 0
+                || neighbour.y < 0
+                || neighbour.x >= size as i32
+                || neighbour.y >= size as i32
+            {
+                continue;
+            }
+            let n = neighbour.x as usize * size + neighbour.y as usize;
+            if matches!(first[n], Some((f, _)) if f == faction) || second[n].is_some() {
+                continue;
+            }
+            let distance = if dir.dx() != 0 && dir.dy() != 0 {
+                std::f32::consts::SQRT_2
+            } else {
+                1.0
+            };
+            let cost = cost + step_cost(stars[n]) * distance / strength;
+            if cost <= budget {
+                heap.push(Claim {
+                    cost,
+                    index: n,
+                    faction,
+                });
+            }
+        }
+    }
+    first
+        .into_iter()
+        .zip(second)
+        .map(|claims| match claims {
+            (Some((a, cost_a)), Some((b, cost_b)))
+                if cost_b <= cost_a * (1.0 + CONTESTED_MARGIN) =>
+            {
+                Territory::Contested(a, b)
+            }
+            (Some((a, _)), _) => Territory::Owned(a),
+            (None, _) => Territory::Unclaimed,
+        })
+        .collect()
+}
+
+impl Galaxy {
+    /// Places factions and their territories, the same for every galaxy with this seed.
+    /// Does nothing if there are no names to call the factions.
+    pub fn generate_factions(&mut self, packs: &[NamesPack]) {
+        let packs: Vec<&NamesPack> = packs
+            .iter()
+            .filter(|p| !p.last_names_male.is_empty())
+            .collect();
+        if packs.is_empty() {
+            return;
+        }
+        let size = self.size();
+        let mut rng = StdRng::seed_from_u64(self.meta.seed ^ SEED_SALT);
+        let stars: Vec<u32> = self.quadrants.iter().map(|q| q.stars_count).collect();
+        let homes = choose_homes(&mut rng, size, &stars, factions_count(size));
+        self.factions = Vec::with_capacity(homes.len());
+        for (id, home) in homes.into_iter().enumerate() {
+            let pack = packs[rng.gen_range(0..packs.len())];
+            let faction = Faction {
+                id: id as u8,
+                name: faction_name(&mut rng, pack, &self.factions),
+                names_pack: pack.id.clone(),
+                home,
+                strength: rng.gen_range(0.6..1.4),
+            };
+            self.factions.push(faction);
+        }
+        self.claim_territories();
+    }
+
+    /// Territories only depend on stars and factions, so they aren't saved
+    pub fn claim_territories(&mut self) {
+        let size = self.size();
+        let stars: Vec<u32> = self.quadrants.iter().map(|q| q.stars_count).collect();
+        for (quadrant, territory) in
+            self.quadrants
+                .iter_mut()
+                .zip(claim_territories(size, &stars, &self.factions))
+        {
+            quadrant.territory = territory;
+        }
+    }
+
+    pub fn faction(&self, id: u8) -> Option<&Faction> {
+        self.factions.get(id as usize)
+    }
+
+    pub fn territory(&self, point: Point) -> Territory {
+        self.quadrant(point)
+            .map_or(Territory::Unclaimed, |q| q.territory)
+    }
+
+    /// Faction with the strongest claim to the quadrant
+    pub fn owner(&self, point: Point) -> Option<&Faction> {
+        self.territory(point)
+            .owner()
+            .and_then(|id| self.faction(id))
+    }
+
+    /// Claimed quadrant next to a quadrant with another owner
+    pub fn is_border(&self, point: Point) -> bool {
+        let owner = self.territory(point).owner();
+        owner.is_some()
+            && DIR8.into_iter().any(|dir| {
+                let neighbour = point + dir;
+                self.quadrant(neighbour).is_some() && self.territory(neighbour).owner() != owner
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{factions_count, Territory};
+    use crate::astro::galaxy::{Galaxy, GalaxyMeta};
+    use crate::astro::galaxy_class::GalaxyClass;
+    use crate::astro::galaxy_size::GalaxySize;
+    use crate::astro::spiral_arms::SpiralArms;
+    use crate::data::names_pack::NamesPack;
+    use geometry::Point;
+
+    fn packs() -> Vec<NamesPack> {
+        vec![NamesPack {
+            id: "test".to_string(),
+            first_names_male: vec!["Ilya".to_string()],
+            first_names_female: vec!["Ashley".to_string()],
+            last_names_male: vec!["Afganov".to_string(), "Smith".to_string()],
+            last_names_female: vec![],
+        }]
+    }
+
+    fn galaxy(seed: &str) -> Galaxy {
+        let mut galaxy = Galaxy::from(GalaxyMeta::new(
+            "Test".to_string(),
+            seed.to_string(),
+            GalaxySize::Small,
+            GalaxyClass::Spiral,
+            SpiralArms::default(),
+        ));
+        galaxy.generate_factions(&packs());
+        galaxy
+    }
+
+    #[test]
+    fn test_same_for_seed() {
+        let a = galaxy("42");
+        let b = galaxy("42");
+        assert_eq!(a.factions.len(), factions_count(a.size()));
+        for (fa, fb) in a.factions.iter().zip(b.factions.iter()) {
+            assert_eq!(fa.name, fb.name);
+            assert_eq!(fa.home, fb.home);
+        }
+        assert!(a
+            .quadrants
+            .iter()
+            .zip(b.quadrants.iter())
+            .all(|(qa, qb)| qa.territory == qb.territory));
+    }
+
+    #[test]
+    fn test_territories() {
+        let galaxy = galaxy("Andromeda");
+        for faction in galaxy.factions.iter() {
+            assert_eq!(galaxy.territory(faction.home), Territory::Owned(faction.id));
+            assert_eq!(galaxy.owner(faction.home).unwrap().name, faction.name);
+            assert_eq!(faction.names_pack, "test");
+        }
+        assert!(galaxy.quadrants.iter().any(|q| q.territory.is_unclaimed()));
+        assert!(galaxy.quadrants.iter().any(|q| q.territory.is_contested()));
+        let size = galaxy.size() as i32;
+        assert!((0..size)
+            .flat_map(|x| (0..size).map(move |y| Point::new(x, y)))
+            .any(|p| galaxy.is_border(p)));
+        assert_eq!(galaxy.territory(Point::new(-1, 0)), Territory::Unclaimed);
+    }
+
+    #[test]
+    fn test_no_names() {
+        let mut galaxy = galaxy("42");
+        galaxy.factions.clear();
+        galaxy.generate_factions(&[]);
+        assert!(galaxy.factions.is_empty());
+    }
+}
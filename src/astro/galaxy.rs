@@ -1,7 +1,12 @@
 use super::galaxy_class::GalaxyClass;
 use super::galaxy_size::GalaxySize;
+use super::spiral_arms::SpiralArms;
+use crate::astro::factions::Faction;
 use crate::astro::galaxy_generator::generate_quadrants;
-use crate::astro::quadrant::Quadrant;
+use crate::astro::quadrant::{Chunk, Quadrant};
+use geometry::Point;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -12,10 +17,18 @@ pub struct GalaxyMeta {
     pub seed: u64,
     pub size: GalaxySize,
     pub class: GalaxyClass,
+    #[serde(default)]
+    pub arms: SpiralArms,
 }
 
 impl GalaxyMeta {
-    pub fn new(name: String, seed: String, size: GalaxySize, class: GalaxyClass) -> Self {
+    pub fn new(
+        name: String,
+        seed: String,
+        size: GalaxySize,
+        class: GalaxyClass,
+        arms: SpiralArms,
+    ) -> Self {
         let name = name.trim().replace(['\n', '/', '\\'], "");
         let mut hasher = DefaultHasher::new();
         seed.hash(&mut hasher);
@@ -25,6 +38,7 @@ impl GalaxyMeta {
             seed,
             size,
             class,
+            arms,
         }
     }
 }
@@ -33,16 +47,183 @@ impl GalaxyMeta {
 pub struct Galaxy {
     pub meta: GalaxyMeta,
     pub quadrants: Vec<Quadrant>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub factions: Vec<Faction>,
+    /// Made by an older generator, star counts are saved instead of regenerated
+    #[serde(skip)]
+    pub stored_stars: bool,
 }
 
 impl From<GalaxyMeta> for Galaxy {
     fn from(meta: GalaxyMeta) -> Self {
         Self {
-            quadrants: generate_quadrants(meta.seed, meta.size.into(), meta.class)
+            quadrants: generate_quadrants(meta.seed, meta.size.into(), meta.class, &meta.arms)
                 .into_iter()
                 .map(Quadrant::new)
                 .collect(),
+            factions: Vec::new(),
+            stored_stars: false,
             meta,
         }
     }
 }
+
+/// Galaxy as it's stored in savefiles, quadrants are regenerated from the meta
+/// and only changes made in the game are kept
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GalaxyChanges {
+    pub meta: GalaxyMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub factions: Vec<Faction>,
+    /// Indexes of visited quadrants
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visited: Vec<usize>,
+    /// Generated chunks by indexes of quadrants
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<(usize, Vec<Option<Chunk>>)>,
+    /// Stars of every quadrant for galaxies from saves that stored all quadrants,
+    /// the generator has changed since and gives other stars for the same seed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stars: Vec<u32>,
+}
+
+impl From<GalaxyMeta> for GalaxyChanges {
+    fn from(meta: GalaxyMeta) -> Self {
+        Self {
+            meta,
+            factions: Vec::new(),
+            visited: Vec::new(),
+            chunks: Vec::new(),
+            stars: Vec::new(),
+        }
+    }
+}
+
+impl From<&Galaxy> for GalaxyChanges {
+    fn from(galaxy: &Galaxy) -> Self {
+        let quadrants = galaxy.quadrants.iter().enumerate();
+        Self {
+            meta: galaxy.meta.clone(),
+            factions: galaxy.factions.clone(),
+            visited: quadrants
+                .clone()
+                .filter(|(_, q)| q.visited)
+                .map(|(i, _)| i)
+                .collect(),
+            chunks: quadrants
+                .filter(|(_, q)| !q.chunks.is_empty())
+                .map(|(i, q)| (i, q.chunks.clone()))
+                .collect(),
+            stars: if galaxy.stored_stars {
+                galaxy.quadrants.iter().map(|q| q.stars_count).collect()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+}
+
+impl From<GalaxyChanges> for Galaxy {
+    fn from(changes: GalaxyChanges) -> Self {
+        let mut galaxy = Galaxy::from(changes.meta);
+        if changes.stars.len() == galaxy.quadrants.len() {
+            for (quadrant, stars) in galaxy.quadrants.iter_mut().zip(changes.stars) {
+                quadrant.stars_count = stars;
+            }
+            galaxy.stored_stars = true;
+        }
+        galaxy.factions = changes.factions;
+        galaxy.claim_territories();
+        for i in changes.visited {
+            if let Some(quadrant) = galaxy.quadrants.get_mut(i) {
+                quadrant.visited = true;
+            }
+        }
+        for (i, chunks) in changes.chunks {
+            if let Some(quadrant) = galaxy.quadrants.get_mut(i) {
+                quadrant.chunks = chunks;
+            }
+        }
+        galaxy
+    }
+}
+
+impl Galaxy {
+    pub fn size(&self) -> usize {
+        self.meta.size.into()
+    }
+
+    /// Quadrants are stored by columns, same as `generate_quadrants()` returns them
+    pub fn quadrant(&self, point: Point) -> Option<&Quadrant> {
+        let size = self.size() as i32;
+        if point.x < 0 || point.y < 0 || point.x >= size || point.y >= size {
+            return None;
+        }
+        self.quadrants.get((point.x * size + point.y) as usize)
+    }
+
+    pub fn quadrant_mut(&mut self, point: Point) -> Option<&mut Quadrant> {
+        let size = self.size() as i32;
+        if point.x < 0 || point.y < 0 || point.x >= size || point.y >= size {
+            return None;
+        }
+        self.quadrants.get_mut((point.x * size + point.y) as usize)
+    }
+
+    /// Random quadrant with some stars in it, the same for every galaxy with this seed
+    pub fn start_quadrant(&self) -> Point {
+        let size = self.size() as i32;
+        let mut rng = StdRng::seed_from_u64(self.meta.seed);
+        for _ in 0..1000 {
+            let point = Point::new(rng.gen_range(0..size), rng.gen_range(0..size));
+            if self.quadrant(point).map_or(0, |q| q.stars_count) > 0 {
+                return point;
+            }
+        }
+        Point::new(size / 2, size / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Galaxy, GalaxyChanges, GalaxyMeta};
+    use crate::astro::galaxy_class::GalaxyClass;
+    use crate::astro::galaxy_size::GalaxySize;
+    use crate::astro::spiral_arms::SpiralArms;
+    use crate::data::game_data::GameData;
+
+    fn meta() -> GalaxyMeta {
+        GalaxyMeta::new(
+            "Test".to_string(),
+            "42".to_string(),
+            GalaxySize::Small,
+            GalaxyClass::Spiral,
+            SpiralArms::default(),
+        )
+    }
+
+    #[test]
+    fn test_start_quadrant() {
+        let galaxy = Galaxy::from(meta());
+        let start = galaxy.start_quadrant();
+        assert_eq!(start, galaxy.start_quadrant());
+        assert!(galaxy.quadrant(start).unwrap().stars_count > 0);
+    }
+
+    #[test]
+    fn test_changes() {
+        let mut galaxy = Galaxy::from(meta());
+        galaxy.generate_factions(&GameData::load().names);
+        let start = galaxy.start_quadrant();
+        galaxy.quadrant_mut(start).unwrap().visited = true;
+
+        let changes = GalaxyChanges::from(&galaxy);
+        assert_eq!(changes.visited.len(), 1);
+        let full = serde_json::to_string(&galaxy).unwrap();
+        let compact = serde_json::to_string(&changes).unwrap();
+        assert!(compact.len() * 10 < full.len());
+
+        let restored = Galaxy::from(serde_json::from_str::<GalaxyChanges>(&compact).unwrap());
+        assert_eq!(serde_json::to_string(&restored).unwrap(), full);
+    }
+}
@@ -10,6 +10,10 @@ pub enum GalaxyClass {
     Elliptical,
     Circular,
     Irregular,
+    Lenticular,
+    Ring,
+    Dwarf,
+    Interacting,
 }
 
 impl From<GalaxyClass> for &str {
@@ -20,6 +24,10 @@ impl From<GalaxyClass> for &str {
             GalaxyClass::Elliptical => "Elliptical",
             GalaxyClass::Circular => "Circular",
             GalaxyClass::Irregular => "Irregular",
+            GalaxyClass::Lenticular => "Lenticular",
+            GalaxyClass::Ring => "Ring",
+            GalaxyClass::Dwarf => "Dwarf",
+            GalaxyClass::Interacting => "Interacting Pair",
         }
     }
 }
@@ -36,16 +44,25 @@ impl GalaxyClass {
     pub fn prev(self) -> Self {
         previous_cycle(&self)
     }
+
+    /// Only these classes use `SpiralArms` settings
+    pub fn has_arms(&self) -> bool {
+        matches!(self, GalaxyClass::Spiral | GalaxyClass::BaredSpiral)
+    }
 }
 
 impl Distribution<GalaxyClass> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> GalaxyClass {
-        match rng.gen_range(0..5) {
+        match rng.gen_range(0..9) {
             0 => GalaxyClass::Spiral,
             1 => GalaxyClass::BaredSpiral,
             2 => GalaxyClass::Elliptical,
             3 => GalaxyClass::Circular,
             4 => GalaxyClass::Irregular,
+            5 => GalaxyClass::Lenticular,
+            6 => GalaxyClass::Ring,
+            7 => GalaxyClass::Dwarf,
+            8 => GalaxyClass::Interacting,
             _ => unreachable!(),
         }
     }
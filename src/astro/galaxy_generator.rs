@@ -1,24 +1,81 @@
 #![allow(dead_code)]
 
 use crate::astro::galaxy_class::GalaxyClass;
+use crate::astro::spiral_arms::SpiralArms;
 use geometry::DIR8;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
 use std::f32::consts::PI;
+use std::ops::RangeInclusive;
 
-pub fn generate_quadrants(seed: u64, size: usize, class: GalaxyClass) -> Vec<u32> {
+pub fn generate_quadrants(
+    seed: u64,
+    size: usize,
+    class: GalaxyClass,
+    arms: &SpiralArms,
+) -> Vec<u32> {
     let mut rng = StdRng::seed_from_u64(seed);
     let mut values = match class {
-        GalaxyClass::Spiral => fill_spiral(&mut rng, size, true),
-        GalaxyClass::BaredSpiral => fill_bared_spiral(&mut rng, size),
+        GalaxyClass::Spiral => fill_spiral(&mut rng, size, arms),
+        GalaxyClass::BaredSpiral => fill_bared_spiral(&mut rng, size, arms),
         GalaxyClass::Elliptical => fill_elliptical(&mut rng, size),
         GalaxyClass::Circular => fill_circular(&mut rng, size),
         GalaxyClass::Irregular => fill_irregular(&mut rng, size),
+        GalaxyClass::Lenticular => fill_lenticular(&mut rng, size),
+        GalaxyClass::Ring => fill_ring(&mut rng, size),
+        GalaxyClass::Dwarf => fill_dwarf(&mut rng, size),
+        GalaxyClass::Interacting => fill_interacting(&mut rng, size),
     };
     smooth(size, &mut values, 2);
     values
 }
 
+/// Takes a random value for every quadrant where `draws` is true, serially and in the same
+/// order as always, so seeds keep their galaxies; other quadrants get 0.0.
+/// Which quadrants draw depends only on the geometry, so the rest of the pass can run
+/// in parallel with `map_parallel()` reading these values.
+fn draw_noise<R, D>(rng: &mut R, size: usize, range: RangeInclusive<f32>, draws: D) -> Vec<f32>
+where
+    R: Rng + ?Sized,
+    D: Fn(usize, usize) -> bool,
+{
+    let mut noise = vec![0.0; size * size];
+    for x in 0..size {
+        for y in 0..size {
+            if draws(x, y) {
+                noise[x * size + y] = rng.gen_range(range.clone());
+            }
+        }
+    }
+    noise
+}
+
+/// Recalculates every quadrant in parallel. Only for passes without rng: random values
+/// are taken by `draw_noise()` before it
+fn map_parallel<F>(size: usize, values: &mut [u32], f: F)
+where
+    F: Fn(usize, usize, u32) -> u32 + Sync,
+{
+    values
+        .par_chunks_mut(size)
+        .enumerate()
+        .for_each(|(x, column)| {
+            for (y, value) in column.iter_mut().enumerate() {
+                *value = f(x, y, *value);
+            }
+        });
+}
+
+fn clamp_stars(values: &mut [u32]) {
+    values
+        .par_iter_mut()
+        .for_each(|value| *value = (*value).min(CORE_MAX_STARS));
+}
+
+/// Runs in place, every quadrant sees already smoothed neighbours before it,
+/// so it can't be split between threads without changing the galaxies of existing worlds.
+/// It's a few additions per quadrant, unlike the fill passes which run in parallel.
 fn smooth(size: usize, values: &mut Vec<u32>, count: usize) {
     let slice = values.as_mut_slice();
     for _ in 0..count {
@@ -42,66 +99,95 @@ fn smooth(size: usize, values: &mut Vec<u32>, count: usize) {
             }
         }
     }
-    for item in slice.iter_mut().take(size * size) {
-        if *item > CORE_MAX_STARS {
-            *item = CORE_MAX_STARS;
-        }
-    }
+    clamp_stars(slice);
 }
 
-// some magic numbers for logarithmic spiral
-const SAMPLES_PER_SIZE: usize = 100;
-const SPIRAL_A: f32 = 1.3;
-const SPIRAL_B: f32 = 0.15;
-const SPIRAL_WINDINGS: f32 = 3.6;
-const SPIRAL_T_MAX: f32 = 2.0 * PI * SPIRAL_WINDINGS;
-const SPIRAL_DRIFT: f32 = 0.3;
-
-fn fill_spiral<R: Rng + ?Sized>(rng: &mut R, size: usize, with_core: bool) -> Vec<u32> {
-    // TODO: this looks terrible, especially on big sizes
-    // need to be more than 2 arms
+const SPIRAL_INNER_RADIUS: f32 = 0.04;
+const SPIRAL_OUTER_RADIUS: f32 = 0.45;
+const MAX_STARS_SPIRAL: u32 = 190_000;
+// faint disk between arms
+const SPIRAL_DISK_K: f32 = 0.1;
+const SPIRAL_BULGE_K: f32 = 1.0;
+
+fn fill_spiral<R: Rng + ?Sized>(rng: &mut R, size: usize, arms: &SpiralArms) -> Vec<u32> {
+    let rotation = rng.gen_range(0.0..2.0 * PI);
+    let mut values = fill_arms(rng, size, arms, rotation, SPIRAL_INNER_RADIUS);
+    fill_core(rng, size, &mut values);
+    values
+}
+
+/// Logarithmic spiral arms starting at `inner_radius` (relative to size)
+/// and making `arms.windings` turns until the edge of the disk
+fn fill_arms<R: Rng + ?Sized>(
+    rng: &mut R,
+    size: usize,
+    arms: &SpiralArms,
+    rotation: f32,
+    inner_radius: f32,
+) -> Vec<u32> {
     let mut values = vec![0; size * size];
-    let slice = values.as_mut_slice();
-    let samples = SAMPLES_PER_SIZE * size;
-    let center = size / 2;
-    for _ in 0..samples {
-        let t = rng.gen_range(0.0..=1.0) * SPIRAL_T_MAX;
-        let mut x = SPIRAL_A * (t * SPIRAL_B).exp() * t.cos() * size as f32 / 96.0;
-        x += SPIRAL_DRIFT * x * rng.gen_range(0.0..=1.0)
-            - SPIRAL_DRIFT * x * rng.gen_range(0.0..=1.0);
-        let mut y = SPIRAL_A * (t * SPIRAL_B).exp() * t.sin() * size as f32 / 96.0;
-        y += SPIRAL_DRIFT * y * rng.gen_range(0.0..=1.0)
-            - SPIRAL_DRIFT * y * rng.gen_range(0.0..=1.0);
-        let (x, y) = if rng.gen_bool(0.5) {
-            (
-                (x + center as f32).round() as i32,
-                (y + center as f32).round() as i32,
-            )
-        } else {
-            (
-                (-x + center as f32).round() as i32,
-                (-y + center as f32).round() as i32,
-            )
-        };
-        if x < 0 || y < 0 || x > size as i32 - 1 || y > size as i32 - 1 {
-            continue;
+    let center = size as f32 / 2.0;
+    let arm_step = 2.0 * PI / arms.count as f32;
+    let edge = SPIRAL_OUTER_RADIUS + arms.width;
+    let log_span = (SPIRAL_OUTER_RADIUS / inner_radius).ln();
+    let offset = |x: usize, y: usize| (x as f32 + 0.5 - center, y as f32 + 0.5 - center);
+    let radius = |(dx, dy): (f32, f32)| f32::hypot(dx, dy) / size as f32;
+    let noise = draw_noise(rng, size, 0.5..=1.5, |x, y| radius(offset(x, y)) <= edge);
+    map_parallel(size, &mut values, |x, y, _| {
+        let (dx, dy) = offset(x, y);
+        let r = radius((dx, dy));
+        if r > edge {
+            return 0;
         }
-        let i = x as usize * size + y as usize;
-        slice[i] += rng.gen_range(1..150_000);
+        let phase = 2.0 * PI * arms.windings * (r.max(inner_radius) / inner_radius).ln() / log_span;
+        let angle = (dy.atan2(dx) - rotation - phase).rem_euclid(arm_step);
+        // arc length to the nearest arm
+        let distance = angle.min(arm_step - angle) * r;
+        let width = arms.width * (0.5 + r / SPIRAL_OUTER_RADIUS);
+        let density = (-(distance / width).powi(2)).exp()
+            + SPIRAL_DISK_K
+            + SPIRAL_BULGE_K * (-r / SPIRAL_INNER_RADIUS).exp();
+        let val = density * (1.0 - r / edge) * 2.0 * noise[x * size + y];
+        (val * MAX_STARS_SPIRAL as f32).round() as u32
+    });
+    let slice = values.as_mut_slice();
+    // star forming regions along the arms
+    let knots = arms.count as usize * size / 8;
+    let knot_radius = (arms.width * size as f32 / 2.0).max(1.0);
+    for _ in 0..knots {
+        let r = rng.gen_range(inner_radius..SPIRAL_OUTER_RADIUS);
+        let arm = rng.gen_range(0..arms.count) as f32;
+        let phase = 2.0 * PI * arms.windings * (r / inner_radius).ln() / log_span;
+        let angle = rotation + phase + arm * arm_step;
+        let knot_x = center + angle.cos() * r * size as f32;
+        let knot_y = center + angle.sin() * r * size as f32;
+        let brightness = rng.gen_range(0.2..=0.6) * MAX_STARS_SPIRAL as f32;
+        add_blob(slice, size, (knot_x, knot_y), knot_radius, brightness);
     }
-    for item in slice.iter_mut().take(size * size) {
-        if *item > CORE_MAX_STARS {
-            *item = CORE_MAX_STARS;
-        }
+    clamp_stars(slice);
+    values
+}
+
+/// Adds gaussian blob of stars, `center` is in quadrants
+fn add_blob(slice: &mut [u32], size: usize, center: (f32, f32), radius: f32, brightness: f32) {
+    let (center_x, center_y) = center;
+    if center_x + radius * 2.0 < 0.0 || center_y + radius * 2.0 < 0.0 {
+        return;
     }
-    if with_core {
-        fill_core(rng, size, &mut values);
+    let from_x = (center_x - radius * 2.0).max(0.0) as usize;
+    let to_x = ((center_x + radius * 2.0) as usize).min(size - 1);
+    let from_y = (center_y - radius * 2.0).max(0.0) as usize;
+    let to_y = ((center_y + radius * 2.0) as usize).min(size - 1);
+    for x in from_x..=to_x {
+        for y in from_y..=to_y {
+            let d = f32::hypot(x as f32 + 0.5 - center_x, y as f32 + 0.5 - center_y) / radius;
+            slice[x * size + y] += (brightness * (-d * d).exp()).round() as u32;
+        }
     }
-    values
 }
 
 const CORE_RADIUS_K: f32 = 0.02;
-const CORE_MAX_STARS: u32 = 262_144;
+pub const CORE_MAX_STARS: u32 = 262_144;
 const CORE_MIN_STARS: u32 = 50_000;
 
 fn fill_core<R: Rng + ?Sized>(rng: &mut R, size: usize, values: &mut Vec<u32>) {
@@ -123,9 +209,7 @@ fn fill_core<R: Rng + ?Sized>(rng: &mut R, size: usize, values: &mut Vec<u32>) {
     }
 }
 
-fn fill_bared_spiral<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
-    let mut values = fill_spiral(rng, size, false);
-    let slice = values.as_mut_slice();
+fn fill_bared_spiral<R: Rng + ?Sized>(rng: &mut R, size: usize, arms: &SpiralArms) -> Vec<u32> {
     let core_radius = match size {
         64 => 5,
         128 => 12,
@@ -133,6 +217,9 @@ fn fill_bared_spiral<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
         512 => 17,
         _ => ((size as f32) * 0.05).round() as usize,
     };
+    // the bar lies along the x == y diagonal, arms should start from its ends
+    let mut values = fill_arms(rng, size, arms, PI / 4.0, core_radius as f32 / size as f32);
+    let slice = values.as_mut_slice();
     let center = size / 2;
     for x in center - core_radius..=center + core_radius {
         for y in center - core_radius..=center + core_radius {
@@ -167,11 +254,7 @@ fn fill_bared_spiral<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
             slice[i] += val;
         }
     }
-    for item in slice.iter_mut().take(size * size) {
-        if *item > CORE_MAX_STARS {
-            *item = CORE_MAX_STARS;
-        }
-    }
+    clamp_stars(slice);
     values
 }
 
@@ -179,24 +262,20 @@ const MAX_STARS_ELLIPTICAL: u32 = 190_000;
 
 fn fill_elliptical<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
     let mut values = vec![0; size * size];
-    let slice = values.as_mut_slice();
     let center = size / 2;
-    for x in 0..size {
-        for y in 0..size {
-            let d = f32::hypot(x as f32 - center as f32, y as f32 - center as f32) / size as f32;
-            if d > 0.6 {
-                continue;
-            }
-            let val =
-                ((d * 2.0 * PI).cos() + rng.gen_range(0.0..=0.5)) * MAX_STARS_ELLIPTICAL as f32;
-            let mut val = if val < 0.0 { 0 } else { val.round() as u32 };
-            if val > CORE_MAX_STARS {
-                val = CORE_MAX_STARS;
-            }
-            let i = x * size + y;
-            slice[i] = val;
+    let distance = |x: usize, y: usize| {
+        f32::hypot(x as f32 - center as f32, y as f32 - center as f32) / size as f32
+    };
+    let noise = draw_noise(rng, size, 0.0..=0.5, |x, y| distance(x, y) <= 0.6);
+    map_parallel(size, &mut values, |x, y, _| {
+        let d = distance(x, y);
+        if d > 0.6 {
+            return 0;
         }
-    }
+        let val = ((d * 2.0 * PI).cos() + noise[x * size + y]) * MAX_STARS_ELLIPTICAL as f32;
+        let val = if val < 0.0 { 0 } else { val.round() as u32 };
+        val.min(CORE_MAX_STARS)
+    });
     values
 }
 
@@ -205,22 +284,16 @@ const MAX_STARS_CIRCULAR: u32 = 200_000;
 
 fn fill_circular<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
     let mut values = vec![0; size * size];
-    let slice = values.as_mut_slice();
     let center = size / 2;
-    for x in 0..size {
-        for y in 0..size {
-            let d = f32::hypot(x as f32 - center as f32, y as f32 - center as f32) / size as f32;
-            let d_to_circle = (d - CIRCULAR_RADIUS).abs();
-            let val = ((CIRCULAR_RADIUS / 4.0 - d_to_circle) * 10.0 + rng.gen_range(0.0..=0.5))
-                * MAX_STARS_CIRCULAR as f32;
-            let mut val = if val < 0.0 { 0 } else { val.round() as u32 };
-            if val > CORE_MAX_STARS {
-                val = CORE_MAX_STARS;
-            }
-            let i = x * size + y;
-            slice[i] = val;
-        }
-    }
+    let noise = draw_noise(rng, size, 0.0..=0.5, |_, _| true);
+    map_parallel(size, &mut values, |x, y, _| {
+        let d = f32::hypot(x as f32 - center as f32, y as f32 - center as f32) / size as f32;
+        let d_to_circle = (d - CIRCULAR_RADIUS).abs();
+        let val = ((CIRCULAR_RADIUS / 4.0 - d_to_circle) * 10.0 + noise[x * size + y])
+            * MAX_STARS_CIRCULAR as f32;
+        let val = if val < 0.0 { 0 } else { val.round() as u32 };
+        val.min(CORE_MAX_STARS)
+    });
     values
 }
 
@@ -238,9 +311,13 @@ fn fill_irregular<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
             rng.gen_range(clamps.0..=clamps.1),
         ));
     }
+    // quadrants further than 0.3 from the center are skipped without rng calls,
+    // so only the square around it is visited
+    let reach = (size as f32 * 0.3).ceil() as usize;
     for (center_x, center_y) in centers {
-        for x in 0..size {
-            for y in 0..size {
+        let xs = center_x.saturating_sub(reach)..=(center_x + reach).min(size - 1);
+        for x in xs {
+            for y in center_y.saturating_sub(reach)..=(center_y + reach).min(size - 1) {
                 let d = f32::hypot(x as f32 - center_x as f32, y as f32 - center_y as f32)
                     / size as f32;
                 if d > 0.3 {
@@ -254,22 +331,206 @@ fn fill_irregular<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
         }
     }
     let center = size / 2;
-    for x in 0..size {
-        for y in 0..size {
-            let i = x * size + y;
-            let d = f32::hypot(x as f32 - center as f32, y as f32 - center as f32) / size as f32;
-            if d > 0.5 {
-                slice[i] = 0
-            } else {
-                slice[i] = (slice[i] as f32 * (0.5 - d) * 10.0).round() as u32
-            }
+    map_parallel(size, slice, |x, y, value| {
+        let d = f32::hypot(x as f32 - center as f32, y as f32 - center as f32) / size as f32;
+        if d > 0.5 {
+            0
+        } else {
+            (value as f32 * (0.5 - d) * 10.0).round() as u32
+        }
+    });
+    clamp_stars(slice);
+    values
+}
+
+const LENTICULAR_RADIUS: f32 = 0.45;
+const LENTICULAR_SCALE: f32 = 0.14;
+const MAX_STARS_LENTICULAR: u32 = 220_000;
+
+/// Smooth featureless disk seen at random inclination with a bright bulge
+fn fill_lenticular<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
+    let mut values = vec![0; size * size];
+    let center = size as f32 / 2.0;
+    let rotation = rng.gen_range(0.0..PI);
+    let (sin, cos) = rotation.sin_cos();
+    // ratio of the minor axis to the major one
+    let flattening = rng.gen_range(0.35..=0.8);
+    let radius = |x: usize, y: usize| {
+        let (dx, dy) = (x as f32 + 0.5 - center, y as f32 + 0.5 - center);
+        let major = (dx * cos + dy * sin) / size as f32;
+        let minor = (dy * cos - dx * sin) / size as f32 / flattening;
+        f32::hypot(major, minor)
+    };
+    let noise = draw_noise(rng, size, 0.8..=1.2, |x, y| {
+        radius(x, y) <= LENTICULAR_RADIUS
+    });
+    map_parallel(size, &mut values, |x, y, _| {
+        let r = radius(x, y);
+        if r > LENTICULAR_RADIUS {
+            return 0;
         }
+        let disk = (-r / LENTICULAR_SCALE).exp();
+        let bulge = 2.0 * (-(r / CORE_RADIUS_K).powi(2)).exp();
+        let val = (disk + bulge)
+            * (1.0 - r / LENTICULAR_RADIUS)
+            * noise[x * size + y]
+            * MAX_STARS_LENTICULAR as f32;
+        (val.round() as u32).min(CORE_MAX_STARS)
+    });
+    values
+}
+
+const RING_RADIUS: f32 = 0.3;
+const RING_NUCLEUS_RADIUS: f32 = 0.05;
+const MAX_STARS_RING: u32 = 160_000;
+
+/// Bright nucleus surrounded by an empty gap and a clumpy ring of young stars
+fn fill_ring<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
+    let mut values = vec![0; size * size];
+    let slice = values.as_mut_slice();
+    let center = size as f32 / 2.0;
+    let ring_radius = RING_RADIUS * rng.gen_range(0.85..=1.1);
+    let ring_width = rng.gen_range(0.02..=0.04);
+    let noise = draw_noise(rng, size, 0.6..=1.4, |_, _| true);
+    map_parallel(size, slice, |x, y, _| {
+        let (dx, dy) = (x as f32 + 0.5 - center, y as f32 + 0.5 - center);
+        let r = f32::hypot(dx, dy) / size as f32;
+        let nucleus = 1.5 * (-(r / RING_NUCLEUS_RADIUS).powi(2)).exp();
+        let ring = (-((r - ring_radius) / ring_width).powi(2)).exp();
+        let val = (nucleus + ring) * noise[x * size + y] * MAX_STARS_RING as f32;
+        val.round() as u32
+    });
+    let clumps = size / 4;
+    let clump_radius = (ring_width * size as f32 / 2.0).max(1.0);
+    for _ in 0..clumps {
+        let angle = rng.gen_range(0.0..2.0 * PI);
+        let r = (ring_radius + rng.gen_range(-ring_width..=ring_width)) * size as f32;
+        let brightness = rng.gen_range(0.2..=0.5) * MAX_STARS_RING as f32;
+        add_blob(
+            slice,
+            size,
+            (center + angle.cos() * r, center + angle.sin() * r),
+            clump_radius,
+            brightness,
+        );
+    }
+    clamp_stars(slice);
+    values
+}
+
+const DWARF_MAX_RADIUS: f32 = 0.18;
+const MAX_STARS_DWARF: u32 = 60_000;
+
+/// Small dim galaxy made of a few loose clumps
+fn fill_dwarf<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
+    let mut values = vec![0; size * size];
+    let slice = values.as_mut_slice();
+    let center = size as f32 / 2.0;
+    let radius = rng.gen_range(0.1..=DWARF_MAX_RADIUS) * size as f32;
+    let clumps = rng.gen_range(4..=8);
+    for i in 0..clumps {
+        // the first clump always sits in the middle
+        let (x, y) = if i == 0 {
+            (center, center)
+        } else {
+            let angle = rng.gen_range(0.0..2.0 * PI);
+            let r = rng.gen_range(0.0..=0.5) * radius;
+            (center + angle.cos() * r, center + angle.sin() * r)
+        };
+        let clump_radius = rng.gen_range(0.25..=0.5) * radius;
+        let brightness = rng.gen_range(0.5..=1.0) * MAX_STARS_DWARF as f32;
+        add_blob(slice, size, (x, y), clump_radius, brightness);
     }
-    for item in slice.iter_mut().take(size * size) {
-        if *item > CORE_MAX_STARS {
-            *item = CORE_MAX_STARS;
+    let distance =
+        |x: usize, y: usize| f32::hypot(x as f32 + 0.5 - center, y as f32 + 0.5 - center) / radius;
+    let noise = draw_noise(rng, size, 0.7..=1.3, |x, y| distance(x, y) <= 1.0);
+    map_parallel(size, slice, |x, y, value| {
+        if distance(x, y) > 1.0 {
+            0
+        } else {
+            (value as f32 * noise[x * size + y]).round() as u32
+        }
+    });
+    values
+}
+
+const INTERACTING_DISTANCE: f32 = 0.16;
+const INTERACTING_RADIUS: f32 = 0.08;
+const MAX_STARS_INTERACTING: u32 = 200_000;
+
+/// Two merging galaxies with a bridge between them and long curved tidal tails
+fn fill_interacting<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
+    let mut values = vec![0; size * size];
+    let slice = values.as_mut_slice();
+    let center = size as f32 / 2.0;
+    let axis = rng.gen_range(0.0..2.0 * PI);
+    let distance = INTERACTING_DISTANCE * rng.gen_range(0.8..=1.2) * size as f32;
+    let scale = size as f32;
+    // the second galaxy is smaller
+    let galaxies = [
+        (
+            center + axis.cos() * distance * 0.8,
+            center + axis.sin() * distance * 0.8,
+            INTERACTING_RADIUS,
+        ),
+        (
+            center - axis.cos() * distance * 1.2,
+            center - axis.sin() * distance * 1.2,
+            INTERACTING_RADIUS * 0.7,
+        ),
+    ];
+    let noise = draw_noise(rng, size, 0.7..=1.3, |_, _| true);
+    map_parallel(size, slice, |x, y, _| {
+        let val: f32 = galaxies
+            .iter()
+            .map(|&(gx, gy, radius)| {
+                let r = f32::hypot(x as f32 + 0.5 - gx, y as f32 + 0.5 - gy) / scale;
+                (-r / (radius / 1.5)).exp() + 1.5 * (-(r / CORE_RADIUS_K).powi(2)).exp()
+            })
+            .sum();
+        (val * noise[x * size + y] * MAX_STARS_INTERACTING as f32).round() as u32
+    });
+    // both galaxies rotate in the same direction, so the tails curl the same way
+    let curl = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let tail_radius = (size as f32 * 0.015).max(1.0);
+    for (i, &(gx, gy, radius)) in galaxies.iter().enumerate() {
+        // the tail goes away from the companion
+        let start = if i == 0 { axis } else { axis + PI };
+        let length = rng.gen_range(0.25..=0.35) * radius / INTERACTING_RADIUS;
+        let steps = (length * size as f32) as usize;
+        for step in 0..steps {
+            let t = step as f32 / steps as f32;
+            let angle = start + curl * t * PI * 0.8;
+            let r = (radius * 0.8 + t * length) * scale;
+            let brightness = (1.0 - t) * rng.gen_range(0.05..=0.15) * MAX_STARS_INTERACTING as f32;
+            add_blob(
+                slice,
+                size,
+                (gx + angle.cos() * r, gy + angle.sin() * r),
+                tail_radius * (1.0 + t),
+                brightness,
+            );
         }
     }
+    // bridge of stars pulled between the galaxies
+    let (from, to) = (galaxies[0], galaxies[1]);
+    let steps = (f32::hypot(to.0 - from.0, to.1 - from.1) as usize).max(1);
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let bend = (t * PI).sin() * curl * 0.1 * scale;
+        let x = from.0 + (to.0 - from.0) * t - axis.sin() * bend;
+        let y = from.1 + (to.1 - from.1) * t + axis.cos() * bend;
+        let brightness = rng.gen_range(0.05..=0.1) * MAX_STARS_INTERACTING as f32;
+        add_blob(slice, size, (x, y), tail_radius, brightness);
+    }
+    map_parallel(size, slice, |x, y, value| {
+        let r = f32::hypot(x as f32 + 0.5 - center, y as f32 + 0.5 - center) / scale;
+        if r > 0.5 {
+            0
+        } else {
+            value.min(CORE_MAX_STARS)
+        }
+    });
     values
 }
 
@@ -277,19 +538,62 @@ fn fill_irregular<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Vec<u32> {
 mod tests {
     use crate::astro::galaxy_class::GalaxyClass;
     use crate::astro::galaxy_generator::{generate_quadrants, CORE_MAX_STARS};
+    use crate::astro::spiral_arms::SpiralArms;
 
     #[test]
     fn test_spiral() {
-        let spiral = generate_quadrants(42, 128, GalaxyClass::Spiral);
+        let spiral = generate_quadrants(42, 128, GalaxyClass::Spiral, &SpiralArms::default());
         assert_eq!(spiral.as_slice()[0], 0);
         assert!(spiral.as_slice()[64 * 128 + 64] > 200_000);
         assert_eq!(spiral.as_slice()[127 * 128 + 127], 0);
         assert!(*spiral.iter().max().unwrap() <= CORE_MAX_STARS);
     }
 
+    /// Counts bright segments on a circle around the center
+    fn count_arms(values: &[u32], size: usize, radius: f32) -> usize {
+        let center = size as f32 / 2.0;
+        let ring: Vec<u32> = (0..720)
+            .map(|i| {
+                let angle = i as f32 / 720.0 * 2.0 * std::f32::consts::PI;
+                let x = (center + angle.cos() * radius) as usize;
+                let y = (center + angle.sin() * radius) as usize;
+                values[x * size + y]
+            })
+            .collect();
+        let max = *ring.iter().max().unwrap();
+        let min = *ring.iter().min().unwrap();
+        let (high, low) = (min + (max - min) / 3, min + (max - min) / 6);
+        // start from the darkest point so no segment is split in two
+        let start = ring.iter().position(|v| *v == min).unwrap();
+        let mut count = 0;
+        let mut bright = false;
+        for i in 0..ring.len() {
+            let v = ring[(start + i) % ring.len()];
+            if !bright && v > high {
+                bright = true;
+                count += 1;
+            } else if bright && v < low {
+                bright = false;
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_spiral_arms() {
+        for count in [2, 3, 4] {
+            let arms = SpiralArms {
+                count,
+                ..SpiralArms::default()
+            };
+            let spiral = generate_quadrants(42, 256, GalaxyClass::Spiral, &arms);
+            assert_eq!(count_arms(&spiral, 256, 256.0 * 0.3), count as usize);
+        }
+    }
+
     #[test]
     fn test_bared_spiral() {
-        let bared = generate_quadrants(42, 128, GalaxyClass::BaredSpiral);
+        let bared = generate_quadrants(42, 128, GalaxyClass::BaredSpiral, &SpiralArms::default());
         assert_eq!(bared.as_slice()[0], 0);
         assert!(bared.as_slice()[64 * 128 + 64] > 200_000);
         assert_eq!(bared.as_slice()[127 * 128 + 127], 0);
@@ -298,7 +602,7 @@ mod tests {
 
     #[test]
     fn test_elliptic() {
-        let elliptic = generate_quadrants(42, 128, GalaxyClass::Elliptical);
+        let elliptic = generate_quadrants(42, 128, GalaxyClass::Elliptical, &SpiralArms::default());
         assert_eq!(elliptic.as_slice()[0], 0);
         assert!(elliptic.as_slice()[64 * 128 + 64] > 200_000);
         assert_eq!(elliptic.as_slice()[127 * 128 + 127], 0);
@@ -307,7 +611,7 @@ mod tests {
 
     #[test]
     fn test_circular() {
-        let circular = generate_quadrants(42, 128, GalaxyClass::Circular);
+        let circular = generate_quadrants(42, 128, GalaxyClass::Circular, &SpiralArms::default());
         assert_eq!(circular.as_slice()[0], 0);
         assert!(circular.as_slice()[32 * 128 + 64] > 60_000);
         assert_eq!(circular.as_slice()[64 * 128 + 64], 0);
@@ -317,10 +621,89 @@ mod tests {
 
     #[test]
     fn test_irregular() {
-        let irregular = generate_quadrants(42, 128, GalaxyClass::Irregular);
+        let irregular = generate_quadrants(42, 128, GalaxyClass::Irregular, &SpiralArms::default());
         assert_eq!(irregular.as_slice()[0], 0);
         assert!(irregular.as_slice()[64 * 128 + 64] > 100_000);
         assert_eq!(irregular.as_slice()[127 * 128 + 127], 0);
         assert!(*irregular.iter().max().unwrap() <= CORE_MAX_STARS);
     }
+
+    #[test]
+    fn test_lenticular() {
+        let lenticular =
+            generate_quadrants(42, 128, GalaxyClass::Lenticular, &SpiralArms::default());
+        assert_eq!(lenticular.as_slice()[0], 0);
+        assert!(lenticular.as_slice()[64 * 128 + 64] > 200_000);
+        assert_eq!(lenticular.as_slice()[127 * 128 + 127], 0);
+        assert!(*lenticular.iter().max().unwrap() <= CORE_MAX_STARS);
+    }
+
+    #[test]
+    fn test_ring() {
+        let ring = generate_quadrants(42, 128, GalaxyClass::Ring, &SpiralArms::default());
+        assert_eq!(ring.as_slice()[0], 0);
+        assert!(ring.as_slice()[64 * 128 + 64] > 100_000);
+        // gap between the nucleus and the ring
+        assert!(ring.as_slice()[64 * 128 + 84] < 10_000);
+        assert!(ring.as_slice()[64 * 128 + 98] > 60_000);
+        assert_eq!(ring.as_slice()[127 * 128 + 127], 0);
+        assert!(*ring.iter().max().unwrap() <= CORE_MAX_STARS);
+    }
+
+    #[test]
+    fn test_dwarf() {
+        let dwarf = generate_quadrants(42, 128, GalaxyClass::Dwarf, &SpiralArms::default());
+        assert_eq!(dwarf.as_slice()[0], 0);
+        assert!(dwarf.as_slice()[64 * 128 + 64] > 20_000);
+        assert_eq!(dwarf.as_slice()[32 * 128 + 64], 0);
+        assert_eq!(dwarf.as_slice()[127 * 128 + 127], 0);
+        assert!(*dwarf.iter().max().unwrap() <= CORE_MAX_STARS);
+        // small galaxy takes only a tiny part of the map
+        assert!(dwarf.iter().filter(|&&v| v > 0).count() < 128 * 128 / 10);
+    }
+
+    #[test]
+    fn test_interacting() {
+        let pair = generate_quadrants(42, 128, GalaxyClass::Interacting, &SpiralArms::default());
+        assert_eq!(pair.as_slice()[0], 0);
+        assert_eq!(pair.as_slice()[127 * 128 + 127], 0);
+        assert!(*pair.iter().max().unwrap() <= CORE_MAX_STARS);
+        // both cores are far from the center
+        let brightest: Vec<(i32, i32)> = (0..128 * 128)
+            .filter(|&i| pair[i] > 200_000)
+            .map(|i| (i as i32 / 128 - 64, i as i32 % 128 - 64))
+            .collect();
+        assert!(!brightest.is_empty());
+        assert!(brightest.iter().all(|&(x, y)| x * x + y * y > 10 * 10));
+        assert!(brightest.iter().any(|&(x1, y1)| brightest
+            .iter()
+            .any(|&(x2, y2)| (x1 - x2).pow(2) + (y1 - y2).pow(2) > 30 * 30)));
+    }
+
+    /// FNV-1a of every quadrant, galaxies are too big to keep whole
+    fn fingerprint(values: &[u32]) -> u64 {
+        values.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &value| {
+            (hash ^ value as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    #[test]
+    fn test_same_galaxies_for_same_seeds() {
+        // output of the serial generator, existing worlds must keep their galaxies
+        let golden = [
+            (GalaxyClass::Spiral, 0x387b04edb17d2466),
+            (GalaxyClass::BaredSpiral, 0xb937877d69fe1980),
+            (GalaxyClass::Elliptical, 0x3f41e9218a3c397a),
+            (GalaxyClass::Circular, 0xa0e2d192497c0ccf),
+            (GalaxyClass::Irregular, 0x313befc790d7065b),
+            (GalaxyClass::Lenticular, 0x76553f84a8a82c3b),
+            (GalaxyClass::Ring, 0x56e0744685d00d9d),
+            (GalaxyClass::Dwarf, 0x7becd05126e767bc),
+            (GalaxyClass::Interacting, 0x9d7f350e1b6be4d3),
+        ];
+        for (class, hash) in golden {
+            let values = generate_quadrants(42, 128, class, &SpiralArms::default());
+            assert_eq!(fingerprint(&values), hash, "{:?}", class);
+        }
+    }
 }
@@ -0,0 +1,357 @@
+use super::galaxy::Galaxy;
+use super::galaxy_generator::CORE_MAX_STARS;
+use std::f32::consts::PI;
+
+/// Number of concentric rings of equal width between the center and the edge of the map
+pub const RINGS: usize = 8;
+pub const HISTOGRAM_BINS: usize = 16;
+/// Map is divided into `REGIONS x REGIONS` square regions
+pub const REGIONS: usize = 8;
+/// Part of all stars inside `GalaxyStats.radius`
+const RADIUS_STARS_PART: f64 = 0.95;
+/// Distances from the center where arms are counted, relative to size
+const ARMS_RADII: [f32; 9] = [0.15, 0.175, 0.2, 0.225, 0.25, 0.275, 0.3, 0.325, 0.35];
+const ARMS_SECTORS: usize = 72;
+const ARMS_MIN_CONTRAST: f32 = 3.0;
+/// Circles where even the brightest sector is dimmer are outside of the galaxy
+const ARMS_MIN_STARS: f32 = CORE_MAX_STARS as f32 / 20.0;
+/// Minimal rotation of arms between the first and the last of `ARMS_RADII`, in radians,
+/// bright sectors that don't rotate are a bar or an inclined disk
+const ARMS_MIN_TWIST: f32 = 0.1;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Region {
+    /// left top quadrant
+    pub x: usize,
+    pub y: usize,
+    /// width and height in quadrants
+    pub size: usize,
+    pub stars: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct GalaxyStats {
+    pub size: usize,
+    pub total_stars: u64,
+    /// quadrants with at least one star
+    pub populated: usize,
+    /// stars in rings around the center, quadrants outside of the inscribed circle aren't counted
+    pub rings: [u64; RINGS],
+    /// count of quadrants by stars count, bin width is `CORE_MAX_STARS / HISTOGRAM_BINS`
+    pub histogram: [usize; HISTOGRAM_BINS],
+    /// distance from the center where density falls to a half of central one, in quadrants
+    pub core_radius: usize,
+    /// distance from the center containing 95% of all stars, in quadrants
+    pub radius: usize,
+    /// spiral arms visible around the center, 0 for galaxies without them
+    pub arms: usize,
+    pub densest: Region,
+    /// emptiest region inside `radius`
+    pub emptiest: Region,
+}
+
+impl GalaxyStats {
+    /// `quadrants` are stored by columns, same as `generate_quadrants()` returns them
+    pub fn new(size: usize, quadrants: &[u32]) -> Self {
+        let center = size as f32 / 2.0;
+        let distance =
+            |x: usize, y: usize| f32::hypot(x as f32 + 0.5 - center, y as f32 + 0.5 - center);
+        let mut total_stars = 0;
+        let mut populated = 0;
+        let mut rings = [0; RINGS];
+        let mut histogram = [0; HISTOGRAM_BINS];
+        // stars and quadrants count for every integer distance from the center
+        let max_distance = (center * std::f32::consts::SQRT_2).ceil() as usize + 1;
+        let mut profile = vec![(0u64, 0u64); max_distance];
+        for x in 0..size {
+            for y in 0..size {
+                let stars = quadrants[x * size + y];
+                total_stars += stars as u64;
+                if stars > 0 {
+                    populated += 1;
+                }
+                let bin = stars as usize * HISTOGRAM_BINS / (CORE_MAX_STARS as usize + 1);
+                histogram[bin.min(HISTOGRAM_BINS - 1)] += 1;
+                let d = distance(x, y);
+                if d < center {
+                    rings[(d / center * RINGS as f32) as usize] += stars as u64;
+                }
+                let d = d as usize;
+                profile[d].0 += stars as u64;
+                profile[d].1 += 1;
+            }
+        }
+
+        let density = |(stars, count): (u64, u64)| {
+            if count > 0 {
+                stars as f32 / count as f32
+            } else {
+                0.0
+            }
+        };
+        let central = density(profile[0]);
+        let core_radius = profile
+            .iter()
+            .position(|&p| density(p) < central / 2.0)
+            .unwrap_or(0);
+        let mut radius = 0;
+        let mut inside = 0;
+        for (d, (stars, _)) in profile.iter().enumerate() {
+            inside += stars;
+            radius = d + 1;
+            if inside as f64 >= total_stars as f64 * RADIUS_STARS_PART {
+                break;
+            }
+        }
+
+        let regions = regions(size, quadrants);
+        let densest = *regions.iter().max_by_key(|r| r.stars).unwrap();
+        let region_center = |r: &Region| {
+            let half = r.size as f32 / 2.0;
+            f32::hypot(r.x as f32 + half - center, r.y as f32 + half - center)
+        };
+        let emptiest = regions
+            .iter()
+            .filter(|r| region_center(r) <= radius as f32)
+            .min_by_key(|r| r.stars)
+            .copied()
+            .unwrap_or_else(|| {
+                *regions
+                    .iter()
+                    .min_by(|a, b| region_center(a).total_cmp(&region_center(b)))
+                    .unwrap()
+            });
+
+        Self {
+            size,
+            total_stars,
+            populated,
+            rings,
+            histogram,
+            core_radius,
+            radius,
+            arms: detect_arms(size, quadrants),
+            densest,
+            emptiest,
+        }
+    }
+}
+
+fn regions(size: usize, quadrants: &[u32]) -> Vec<Region> {
+    let region_size = (size / REGIONS).max(1);
+    let per_side = size / region_size;
+    let mut regions = Vec::with_capacity(per_side * per_side);
+    for rx in 0..per_side {
+        for ry in 0..per_side {
+            let (x, y) = (rx * region_size, ry * region_size);
+            let stars = (x..x + region_size)
+                .flat_map(|qx| (y..y + region_size).map(move |qy| (qx, qy)))
+                .map(|(qx, qy)| quadrants[qx * size + qy] as u64)
+                .sum();
+            regions.push(Region {
+                x,
+                y,
+                size: region_size,
+                stars,
+            });
+        }
+    }
+    regions
+}
+
+/// Counts bright sectors on a few circles around the center and takes the most common answer
+fn detect_arms(size: usize, quadrants: &[u32]) -> usize {
+    let circles: Vec<Vec<f32>> = ARMS_RADII
+        .iter()
+        .map(|k| sectors(size, quadrants, k * size as f32))
+        .collect();
+    let mut votes = [0; 16];
+    for sectors in circles.iter() {
+        votes[count_arms(sectors).min(votes.len() - 1)] += 1;
+    }
+    // on equal votes more arms wins, inner circles often merge arms together
+    let arms = votes
+        .iter()
+        .enumerate()
+        .max_by_key(|&(arms, votes)| (*votes, arms))
+        .map(|(arms, _)| arms)
+        .unwrap();
+    if arms == 0 {
+        return 0;
+    }
+    // sum of phase shifts between neighbour circles, each one is wrapped to (-PI/arms, PI/arms]
+    let sector_angle = 2.0 * PI / arms as f32;
+    let twist: f32 = circles
+        .windows(2)
+        .map(|pair| {
+            let shift = phase(&pair[1], arms) - phase(&pair[0], arms);
+            (shift + sector_angle / 2.0).rem_euclid(sector_angle) - sector_angle / 2.0
+        })
+        .sum();
+    if twist.abs() < ARMS_MIN_TWIST {
+        0
+    } else {
+        arms
+    }
+}
+
+/// Angle of the brightest direction of `m`-fold symmetric pattern
+fn phase(sectors: &[f32], m: usize) -> f32 {
+    let (sin, cos) = sectors
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(sin, cos), (i, v)| {
+            let angle = (i as f32 + 0.5) / sectors.len() as f32 * 2.0 * PI * m as f32;
+            (sin + v * angle.sin(), cos + v * angle.cos())
+        });
+    sin.atan2(cos) / m as f32
+}
+
+/// Average stars count in `ARMS_SECTORS` sectors of a circle
+fn sectors(size: usize, quadrants: &[u32], radius: f32) -> Vec<f32> {
+    let center = size as f32 / 2.0;
+    let samples = ((2.0 * PI * radius) as usize / ARMS_SECTORS).max(4);
+    (0..ARMS_SECTORS)
+        .map(|sector| {
+            let mut sum = 0.0;
+            let mut count = 0;
+            for i in 0..samples {
+                let angle = (sector * samples + i) as f32 / (ARMS_SECTORS * samples) as f32;
+                let angle = angle * 2.0 * PI;
+                let x = center + angle.cos() * radius;
+                let y = center + angle.sin() * radius;
+                if x < 0.0 || y < 0.0 || x >= size as f32 || y >= size as f32 {
+                    continue;
+                }
+                sum += quadrants[x as usize * size + y as usize] as f32;
+                count += 1;
+            }
+            if count > 0 {
+                sum / count as f32
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+fn count_arms(sectors: &[f32]) -> usize {
+    let max = sectors.iter().copied().fold(0.0, f32::max);
+    let min = sectors.iter().copied().fold(f32::MAX, f32::min);
+    if max < ARMS_MIN_STARS || max < min * ARMS_MIN_CONTRAST {
+        return 0;
+    }
+    // hysteresis, so noise on the edge of an arm doesn't split it in two
+    let (high, low) = (min + (max - min) / 3.0, min + (max - min) / 6.0);
+    // start from the darkest sector so no arm is counted twice
+    let start = sectors.iter().position(|&v| v == min).unwrap();
+    let mut count = 0;
+    let mut bright = false;
+    for i in 0..sectors.len() {
+        let v = sectors[(start + i) % sectors.len()];
+        if !bright && v > high {
+            bright = true;
+            count += 1;
+        } else if bright && v < low {
+            bright = false;
+        }
+    }
+    count
+}
+
+impl Galaxy {
+    pub fn stats(&self) -> GalaxyStats {
+        let quadrants: Vec<u32> = self.quadrants.iter().map(|q| q.stars_count).collect();
+        GalaxyStats::new(self.size(), &quadrants)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GalaxyStats, RINGS};
+    use crate::astro::galaxy_class::GalaxyClass;
+    use crate::astro::galaxy_generator::generate_quadrants;
+    use crate::astro::spiral_arms::SpiralArms;
+
+    fn stats(class: GalaxyClass, arms: SpiralArms) -> GalaxyStats {
+        GalaxyStats::new(256, &generate_quadrants(42, 256, class, &arms))
+    }
+
+    #[test]
+    fn test_totals() {
+        let quadrants = vec![0, 10, 262_144, 0];
+        let stats = GalaxyStats::new(2, &quadrants);
+        assert_eq!(stats.total_stars, 262_154);
+        assert_eq!(stats.populated, 2);
+        assert_eq!(stats.histogram.iter().sum::<usize>(), 4);
+        assert_eq!(stats.histogram[0], 3);
+        assert_eq!(stats.densest.stars, 262_144);
+        assert_eq!(stats.densest.x, 1);
+        assert_eq!(stats.densest.y, 0);
+    }
+
+    #[test]
+    fn test_spiral() {
+        for count in [2, 3, 4] {
+            let arms = SpiralArms {
+                count,
+                ..SpiralArms::default()
+            };
+            let stats = stats(GalaxyClass::Spiral, arms);
+            assert_eq!(stats.arms, count as usize);
+            assert!(stats.core_radius < 256 / 8);
+            // the bulge is the densest part
+            assert!(stats.densest.x <= 128 && stats.densest.x + stats.densest.size >= 128);
+        }
+        let stats = stats(GalaxyClass::BaredSpiral, SpiralArms::default());
+        assert_eq!(stats.arms, 2);
+    }
+
+    #[test]
+    fn test_no_arms() {
+        for class in [
+            GalaxyClass::Elliptical,
+            GalaxyClass::Lenticular,
+            GalaxyClass::Ring,
+            GalaxyClass::Circular,
+            GalaxyClass::Dwarf,
+        ] {
+            assert_eq!(stats(class, SpiralArms::default()).arms, 0, "{:?}", class);
+        }
+    }
+
+    #[test]
+    fn test_ring() {
+        let ring = stats(GalaxyClass::Ring, SpiralArms::default());
+        // bright nucleus, empty gap and the ring
+        assert!(ring.core_radius > 0);
+        assert!(ring.rings[2] < ring.rings[0]);
+        assert!(ring.rings[2] * 5 < ring.rings[4] + ring.rings[5]);
+        assert!(ring.emptiest.stars * 2 < ring.densest.stars);
+
+        let circular = stats(GalaxyClass::Circular, SpiralArms::default());
+        // no nucleus at all
+        assert_eq!(circular.core_radius, 0);
+        assert!(circular.rings[0] * 10 < circular.rings[RINGS / 2]);
+    }
+
+    #[test]
+    fn test_dwarf() {
+        let dwarf = stats(GalaxyClass::Dwarf, SpiralArms::default());
+        let elliptical = stats(GalaxyClass::Elliptical, SpiralArms::default());
+        assert!(dwarf.total_stars * 10 < elliptical.total_stars);
+        assert!(dwarf.radius < 256 / 4);
+        assert!(dwarf.populated < elliptical.populated / 4);
+    }
+
+    #[test]
+    fn test_interacting() {
+        let pair = stats(GalaxyClass::Interacting, SpiralArms::default());
+        // the biggest galaxy of the pair is not in the center
+        let (x, y) = (
+            pair.densest.x + pair.densest.size / 2,
+            pair.densest.y + pair.densest.size / 2,
+        );
+        assert!(f32::hypot(x as f32 - 128.0, y as f32 - 128.0) > 256.0 * 0.08);
+    }
+}
@@ -1,5 +1,8 @@
+pub mod factions;
 pub mod galaxy;
 pub mod galaxy_class;
 pub mod galaxy_generator;
 pub mod galaxy_size;
+pub mod galaxy_stats;
 pub mod quadrant;
+pub mod spiral_arms;
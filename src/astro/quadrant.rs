@@ -1,4 +1,4 @@
-use arrayvec::ArrayVec;
+use crate::astro::factions::Territory;
 use serde::{Deserialize, Serialize};
 
 pub const QUADRANT_SIZE: usize = 64;
@@ -8,17 +8,32 @@ pub const CHUNKS_IN_QUADRANT: usize = QUADRANT_SIZE * QUADRANT_SIZE;
 pub struct Quadrant {
     #[serde(rename = "s")]
     pub stars_count: u32,
-    #[serde(rename = "c")]
-    pub chunks: ArrayVec<Option<Chunk>, CHUNKS_IN_QUADRANT>,
+    /// Empty until somebody visits the quadrant, up to `CHUNKS_IN_QUADRANT` items
+    #[serde(rename = "c", default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<Option<Chunk>>,
+    #[serde(rename = "v", default, skip_serializing_if = "is_false")]
+    pub visited: bool,
+    #[serde(rename = "t", default, skip_serializing_if = "Territory::is_unclaimed")]
+    pub territory: Territory,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
 }
 
 impl Quadrant {
     pub fn new(stars_count: u32) -> Self {
         Self {
             stars_count,
-            chunks: ArrayVec::new(),
+            chunks: Vec::new(),
+            visited: false,
+            territory: Territory::Unclaimed,
         }
     }
+
+    pub fn generated_chunks(&self) -> usize {
+        self.chunks.iter().filter(|c| c.is_some()).count()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
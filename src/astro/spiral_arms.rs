@@ -0,0 +1,47 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+pub const MIN_ARMS: u8 = 1;
+pub const MAX_ARMS: u8 = 6;
+pub const MIN_WINDINGS: f32 = 0.25;
+pub const MAX_WINDINGS: f32 = 3.0;
+pub const MIN_WIDTH: f32 = 0.01;
+pub const MAX_WIDTH: f32 = 0.08;
+
+/// Shape of spiral arms, used only by spiral and bared spiral galaxies
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub struct SpiralArms {
+    pub count: u8,
+    /// how many turns every arm makes from the core to the edge
+    pub windings: f32,
+    /// arm width at the middle of the disk, relative to the galaxy size
+    pub width: f32,
+}
+
+impl Default for SpiralArms {
+    fn default() -> Self {
+        Self {
+            count: 2,
+            windings: 1.25,
+            width: 0.03,
+        }
+    }
+}
+
+impl SpiralArms {
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut arms = Self {
+            count: rng.gen_range(MIN_ARMS..=MAX_ARMS),
+            windings: rng.gen_range(2..=8) as f32 * 0.25,
+            width: rng.gen_range(4..=10) as f32 * 0.005,
+        };
+        arms.validate();
+        arms
+    }
+
+    pub fn validate(&mut self) {
+        self.count = self.count.clamp(MIN_ARMS, MAX_ARMS);
+        self.windings = self.windings.clamp(MIN_WINDINGS, MAX_WINDINGS);
+        self.width = self.width.clamp(MIN_WIDTH, MAX_WIDTH);
+    }
+}
@@ -0,0 +1,18 @@
+use crate::human::skills::Skill;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// What the character did before the game starts, gives skills and the first ship
+#[derive(Deserialize, Debug, Clone)]
+pub struct Background {
+    pub id: String,
+    pub name: String,
+    /// Starting levels, points-buy can only raise them
+    #[serde(default)]
+    pub skills: HashMap<Skill, u8>,
+    /// Id of commodity or item and amount loaded to the starting ship
+    #[serde(default)]
+    pub items: HashMap<String, u32>,
+    /// Id of `ShipClass`
+    pub ship: String,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TradeCategory {
+    Food,
+    Medicine,
+    Minerals,
+    Metals,
+    Fuel,
+    Machinery,
+    Electronics,
+    Luxury,
+    Contraband,
+}
+
+impl TradeCategory {
+    pub fn name(&self) -> &str {
+        match self {
+            TradeCategory::Food => "Food",
+            TradeCategory::Medicine => "Medicine",
+            TradeCategory::Minerals => "Minerals",
+            TradeCategory::Metals => "Metals",
+            TradeCategory::Fuel => "Fuel",
+            TradeCategory::Machinery => "Machinery",
+            TradeCategory::Electronics => "Electronics",
+            TradeCategory::Luxury => "Luxury",
+            TradeCategory::Contraband => "Contraband",
+        }
+    }
+}
+
+fn default_volatility() -> f32 {
+    0.1
+}
+
+/// Bulk goods traded on stations, unlike items they have no individual state
+#[derive(Deserialize, Debug)]
+pub struct Commodity {
+    pub id: String,
+    pub name: String,
+    pub category: TradeCategory,
+    /// Price in credits per unit when stock of the station is balanced
+    pub base_price: u32,
+    /// How much price drifts day to day, 0.1 is ±10%
+    #[serde(default = "default_volatility")]
+    pub volatility: f32,
+}
@@ -0,0 +1,124 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DataErrorKind {
+    /// Directory or file can't be read
+    Io(String),
+    /// File is not a valid list of entities
+    Malformed(String),
+    /// Entity of the same type with the same id is already loaded
+    DuplicateId,
+    /// `tiles.len()` doesn't equal `bounds.0 * bounds.1 * decks`
+    BoundsMismatch {
+        tiles: usize,
+        bounds: (i32, i32),
+        decks: i32,
+    },
+    UnknownLegend(String),
+    UnknownGlyph(String),
+    /// Glyph uses a `ShipComponent` which isn't loaded
+    UnknownComponent(String),
+    /// Background starts on a ship class which isn't loaded
+    UnknownShipClass(String),
+    /// Neither commodity nor item has this id
+    UnknownItem(String),
+    /// Species uses a name pack which isn't loaded
+    UnknownNamesPack(String),
+    /// Species has nothing to choose from in this list
+    EmptyOptions(&'static str),
+    /// Name pack has no names in this list
+    EmptyNames(&'static str),
+    /// Mod depends on a mod which isn't installed or can't be loaded
+    MissingDependency(String),
+    /// World was created with a mod which isn't installed
+    UnknownMod(String),
+    /// Only some types of entities can be extended by later packs
+    NotExtendable(&'static str),
+    /// Entity extends nothing, there is no entity with this id in earlier packs
+    NothingToExtend,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DataError {
+    pub path: PathBuf,
+    /// `None` if the error is about the whole file
+    pub id: Option<String>,
+    pub kind: DataErrorKind,
+}
+
+impl DataError {
+    pub fn new(path: PathBuf, id: Option<&str>, kind: DataErrorKind) -> Self {
+        Self {
+            path,
+            id: id.map(String::from),
+            kind,
+        }
+    }
+
+    /// Game can't start with fatal errors, other problems are fixed by skipping broken parts
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self.kind,
+            DataErrorKind::Io(..)
+                | DataErrorKind::Malformed(..)
+                | DataErrorKind::BoundsMismatch { .. }
+                | DataErrorKind::UnknownMod(..)
+        )
+    }
+}
+
+impl Display for DataErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataErrorKind::Io(e) => write!(f, "can't read: {}", e),
+            DataErrorKind::Malformed(e) => write!(f, "malformed JSON: {}", e),
+            DataErrorKind::DuplicateId => write!(f, "duplicate id, previous one is replaced"),
+            DataErrorKind::BoundsMismatch {
+                tiles,
+                bounds,
+                decks,
+            } => write!(
+                f,
+                "{} tiles don't fit bounds {}x{} on {} decks",
+                tiles, bounds.0, bounds.1, decks
+            ),
+            DataErrorKind::UnknownLegend(legend) => write!(f, "unknown glyph legend '{}'", legend),
+            DataErrorKind::UnknownGlyph(glyph) => write!(f, "'{}' is not a valid tile", glyph),
+            DataErrorKind::UnknownComponent(id) => {
+                write!(f, "unknown ship component '{}', part is skipped", id)
+            }
+            DataErrorKind::UnknownShipClass(id) => {
+                write!(f, "unknown ship class '{}', background is skipped", id)
+            }
+            DataErrorKind::UnknownItem(id) => write!(f, "unknown item '{}', it's skipped", id),
+            DataErrorKind::UnknownNamesPack(id) => {
+                write!(f, "unknown names pack '{}', it's skipped", id)
+            }
+            DataErrorKind::EmptyOptions(list) => write!(f, "{} is empty, species is skipped", list),
+            DataErrorKind::EmptyNames(list) => write!(f, "{} is empty, pack is skipped", list),
+            DataErrorKind::MissingDependency(id) => {
+                write!(
+                    f,
+                    "depends on '{}' which can't be loaded, mod is skipped",
+                    id
+                )
+            }
+            DataErrorKind::UnknownMod(id) => write!(f, "mod '{}' is not installed", id),
+            DataErrorKind::NotExtendable(type_name) => {
+                write!(f, "{} can't be extended, entity is skipped", type_name)
+            }
+            DataErrorKind::NothingToExtend => write!(f, "nothing to extend, entity is skipped"),
+        }
+    }
+}
+
+impl Display for DataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(id) = &self.id {
+            write!(f, " [{}]", id)?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
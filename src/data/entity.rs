@@ -1,6 +1,12 @@
+use crate::data::background::Background;
+use crate::data::commodity::Commodity;
+use crate::data::glyph_legend::GlyphLegend;
 use crate::data::item::Item;
 use crate::data::names_pack::NamesPack;
 use crate::data::ship_class::ShipClass;
+use crate::data::ship_component::ShipComponent;
+use crate::data::species::Species;
+use crate::data::station_class::StationClass;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -10,12 +16,61 @@ pub enum DataEntity {
     Item(Item),
     ShipClass(ShipClass),
     NamesPack(NamesPack),
+    Commodity(Commodity),
+    StationClass(StationClass),
+    GlyphLegend(GlyphLegend),
+    ShipComponent(ShipComponent),
+    Background(Background),
+    Species(Species),
+}
+
+/// Entity as it's written in data files
+#[derive(Deserialize, Debug)]
+pub struct DataEntry {
+    /// Add to the entity with the same id from earlier packs instead of replacing it
+    #[serde(default)]
+    pub extend: bool,
+    #[serde(flatten)]
+    pub entity: DataEntity,
+}
+
+impl DataEntity {
+    pub fn id(&self) -> &str {
+        match self {
+            DataEntity::Item(item) => item.id.as_str(),
+            DataEntity::ShipClass(ship) => ship.id.as_str(),
+            DataEntity::NamesPack(pack) => pack.id.as_str(),
+            DataEntity::Commodity(commodity) => commodity.id.as_str(),
+            DataEntity::StationClass(station) => station.id.as_str(),
+            DataEntity::GlyphLegend(legend) => legend.id.as_str(),
+            DataEntity::ShipComponent(component) => component.id.as_str(),
+            DataEntity::Background(background) => background.id.as_str(),
+            DataEntity::Species(species) => species.id.as_str(),
+        }
+    }
+
+    /// Same as `type` field in JSON
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            DataEntity::Item(..) => "item",
+            DataEntity::ShipClass(..) => "ship_class",
+            DataEntity::NamesPack(..) => "names_pack",
+            DataEntity::Commodity(..) => "commodity",
+            DataEntity::StationClass(..) => "station_class",
+            DataEntity::GlyphLegend(..) => "glyph_legend",
+            DataEntity::ShipComponent(..) => "ship_component",
+            DataEntity::Background(..) => "background",
+            DataEntity::Species(..) => "species",
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::data::commodity::TradeCategory;
     use crate::data::entity::DataEntity;
     use crate::data::item::ItemTag;
+    use crate::game::station::StationKind;
 
     #[test]
     fn test_deserialize() {
@@ -45,6 +100,28 @@ mod tests {
             "first_names_male": [ "Ilya", "Victor" ],
             "first_names_female": [ "Ashley" ],
             "last_names_male": [ "Agafonov", "Nikolayev" ]
+          },
+          {
+            "type": "commodity",
+            "id": "ore",
+            "name": "Ore",
+            "category": "MINERALS",
+            "base_price": 20
+          },
+          {
+            "type": "station_class",
+            "id": "outpost",
+            "name": "Outpost",
+            "tiles": [ "║", "=", "║" ],
+            "bounds": [3, 1],
+            "kinds": [ "Outpost" ]
+          },
+          {
+            "type": "glyph_legend",
+            "id": "default",
+            "glyphs": {
+              "=": [ { "part": "frame" }, { "part": "door", "locked": true, "airlock": true } ]
+            }
           }
         ]
         "#;
@@ -74,5 +151,27 @@ mod tests {
         } else {
             unreachable!("Third DataEntity is not NamePack!");
         }
+        if let DataEntity::Commodity(commodity) = &slice[3] {
+            assert_eq!(commodity.id, "ore");
+            assert_eq!(commodity.category, TradeCategory::Minerals);
+            assert_eq!(commodity.base_price, 20);
+            assert_eq!(commodity.volatility, 0.1);
+        } else {
+            unreachable!("Fourth DataEntity is not Commodity!");
+        }
+        if let DataEntity::StationClass(station) = &slice[4] {
+            assert_eq!(station.id, "outpost");
+            assert_eq!(station.tiles.len(), 3);
+            assert!(station.suits(StationKind::Outpost));
+            assert!(!station.suits(StationKind::Mining));
+        } else {
+            unreachable!("Fifth DataEntity is not StationClass!");
+        }
+        if let DataEntity::GlyphLegend(legend) = &slice[5] {
+            assert_eq!(legend.id, "default");
+            assert_eq!(legend.glyphs.get("=").unwrap().len(), 2);
+        } else {
+            unreachable!("Sixth DataEntity is not GlyphLegend!");
+        }
     }
 }
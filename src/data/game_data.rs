@@ -1,8 +1,16 @@
-use crate::data::entity::DataEntity;
+use crate::data::background::Background;
+use crate::data::commodity::Commodity;
+use crate::data::data_error::{DataError, DataErrorKind};
+use crate::data::entity::{DataEntity, DataEntry};
+use crate::data::glyph_legend::{unknown_components, unknown_glyphs, GlyphLegend, Glyphs};
 use crate::data::item::Item;
+use crate::data::mod_manifest::{installed_mods, resolve_load_order, ModManifest, CORE, MANIFEST};
 use crate::data::names_pack::NamesPack;
 use crate::data::ship_class::ShipClass;
-use std::collections::HashMap;
+use crate::data::ship_component::Components;
+use crate::data::species::Species;
+use crate::data::station_class::StationClass;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
@@ -11,43 +19,278 @@ fn make_str(s: &str) -> &'static str {
     Box::leak(s.to_owned().into_boxed_str())
 }
 
+fn with_core(mods: Vec<ModManifest>) -> Vec<ModManifest> {
+    let mut packs = Vec::with_capacity(mods.len() + 1);
+    packs.push(ModManifest::core());
+    packs.extend(mods);
+    packs
+}
+
+type Entries = Vec<(PathBuf, DataEntry)>;
+/// File where every entity was defined last, to report problems found after merging all packs
+type Origins = HashMap<(&'static str, String), PathBuf>;
+
 #[derive(Debug)]
 pub struct GameData {
     pub items: HashMap<&'static str, Item>,
     pub ships: HashMap<&'static str, ShipClass>,
     pub names: Vec<NamesPack>,
+    pub commodities: HashMap<&'static str, Commodity>,
+    pub stations: HashMap<&'static str, StationClass>,
+    pub legends: HashMap<&'static str, GlyphLegend>,
+    pub components: Components,
+    pub backgrounds: HashMap<&'static str, Background>,
+    pub species: HashMap<&'static str, Species>,
+    /// Active mods in load order, core isn't here
+    pub mods: Vec<ModManifest>,
+    /// Every problem found while loading, game can't start if some of them are fatal
+    pub errors: Vec<DataError>,
 }
 
 impl GameData {
+    /// Core and every installed mod, for new worlds
     pub fn load() -> GameData {
+        let mut errors = Vec::new();
+        let mods = resolve_load_order(installed_mods(&mut errors), &mut errors);
+        Self::load_packs(with_core(mods), errors)
+    }
+
+    /// Core and the same mods the world was created with
+    pub fn load_with(ids: &[String]) -> GameData {
+        let mut errors = Vec::new();
+        let mut installed = installed_mods(&mut errors);
+        let mut mods = Vec::with_capacity(ids.len());
+        for id in ids {
+            match installed.iter().position(|m| m.id == *id) {
+                Some(i) => mods.push(installed.remove(i)),
+                None => errors.push(DataError::new(
+                    ["data", "mods"].iter().collect(),
+                    Some(id.as_str()),
+                    DataErrorKind::UnknownMod(id.clone()),
+                )),
+            }
+        }
+        Self::load_packs(with_core(resolve_load_order(mods, &mut errors)), errors)
+    }
+
+    fn load_packs(packs: Vec<ModManifest>, errors: Vec<DataError>) -> GameData {
         let mut data = GameData {
             // TODO: adjust amount of entities
             items: HashMap::with_capacity(1),
             ships: HashMap::with_capacity(1),
             names: Vec::with_capacity(3),
+            commodities: HashMap::with_capacity(12),
+            stations: HashMap::with_capacity(2),
+            legends: HashMap::with_capacity(1),
+            components: HashMap::with_capacity(3),
+            backgrounds: HashMap::with_capacity(4),
+            species: HashMap::with_capacity(2),
+            mods: Vec::with_capacity(packs.len()),
+            errors,
         };
-        let path: PathBuf = ["data", "core"].iter().collect();
-        data.load_dir(&path);
+        let mut origins = Origins::new();
+        for manifest in packs {
+            data.load_pack(&manifest.path, &mut origins);
+            if manifest.id != CORE {
+                data.mods.push(manifest);
+            }
+        }
+        data.validate(&origins);
         data
     }
 
-    fn load_dir(&mut self, path: &Path) {
-        for entry in path.read_dir().unwrap() {
-            let path = entry.unwrap().path();
-            if path.is_dir() {
-                self.load_dir(&path);
-            } else if let Ok(file) = File::open(path) {
-                if let Ok(entities) =
-                    serde_json::from_reader::<_, Vec<DataEntity>>(BufReader::new(file))
-                {
-                    for entity in entities {
-                        self.add_entity(entity);
-                    }
+    pub fn has_fatal_errors(&self) -> bool {
+        self.errors.iter().any(DataError::is_fatal)
+    }
+
+    /// Ids of active mods, to store them in the save
+    pub fn mod_ids(&self) -> Vec<String> {
+        self.mods.iter().map(|m| m.id.clone()).collect()
+    }
+
+    fn load_pack(&mut self, path: &Path, origins: &mut Origins) {
+        let mut entries = Vec::new();
+        self.load_dir(path, &mut entries);
+        let mut ids = HashSet::with_capacity(entries.len());
+        for (path, DataEntry { extend, entity }) in entries {
+            let key = (entity.type_name(), entity.id().to_string());
+            // overriding entities of earlier packs is fine, but not in the same pack
+            if !ids.insert(key.clone()) {
+                self.error(&path, Some(key.1.as_str()), DataErrorKind::DuplicateId);
+            }
+            if extend {
+                if let Err(kind) = self.extend_entity(entity) {
+                    self.error(&path, Some(key.1.as_str()), kind);
+                    continue;
                 }
+            } else {
+                self.add_entity(entity);
             }
+            origins.insert(key, path);
         }
     }
 
+    fn load_dir(&mut self, path: &Path, entries: &mut Entries) {
+        let mut paths: Vec<PathBuf> = match path.read_dir() {
+            Ok(dir) => dir.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+            Err(e) => {
+                self.error(path, None, DataErrorKind::Io(e.to_string()));
+                return;
+            }
+        };
+        // read_dir order depends on filesystem, but the last duplicate should always win
+        paths.sort();
+        for path in paths {
+            if path.is_dir() {
+                self.load_dir(&path, entries);
+            } else if path.file_name().and_then(|name| name.to_str()) != Some(MANIFEST) {
+                self.load_file(path, entries);
+            }
+        }
+    }
+
+    fn load_file(&mut self, path: PathBuf, entries: &mut Entries) {
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                self.error(&path, None, DataErrorKind::Io(e.to_string()));
+                return;
+            }
+        };
+        match serde_json::from_reader::<_, Vec<DataEntry>>(BufReader::new(file)) {
+            Ok(list) => {
+                entries.extend(list.into_iter().map(|entry| (path.clone(), entry)));
+            }
+            Err(e) => self.error(&path, None, DataErrorKind::Malformed(e.to_string())),
+        }
+    }
+
+    fn error(&mut self, path: &Path, id: Option<&str>, kind: DataErrorKind) {
+        self.errors
+            .push(DataError::new(path.to_path_buf(), id, kind));
+    }
+
+    /// Checks entities as they are after all overrides and extensions
+    fn validate(&mut self, origins: &Origins) {
+        let origin = |type_name: &'static str, id: &str| {
+            origins
+                .get(&(type_name, id.to_string()))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let mut problems = Vec::new();
+        let mut legends: Vec<&GlyphLegend> = self.legends.values().collect();
+        legends.sort_by(|a, b| a.id.cmp(&b.id));
+        for legend in legends {
+            for component in unknown_components(&legend.glyphs, &self.components) {
+                problems.push(DataError::new(
+                    origin("glyph_legend", legend.id.as_str()),
+                    Some(legend.id.as_str()),
+                    DataErrorKind::UnknownComponent(component.to_string()),
+                ));
+            }
+        }
+        let mut schemes: Vec<(&'static str, &str, Scheme)> = self
+            .ships
+            .values()
+            .map(|ship| ("ship_class", ship.id.as_str(), Scheme::from(ship)))
+            .chain(
+                self.stations
+                    .values()
+                    .map(|station| ("station_class", station.id.as_str(), Scheme::from(station))),
+            )
+            .collect();
+        // HashMap order is random, but reports should be the same every time
+        schemes.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        for (type_name, id, scheme) in schemes {
+            for kind in scheme.check(&self.legends, &self.components) {
+                problems.push(DataError::new(origin(type_name, id), Some(id), kind));
+            }
+        }
+        for pack in self.names.iter() {
+            if let Some(list) = pack.empty_list() {
+                problems.push(DataError::new(
+                    origin("names_pack", pack.id.as_str()),
+                    Some(pack.id.as_str()),
+                    DataErrorKind::EmptyNames(list),
+                ));
+            }
+        }
+        // NamesPack::random_name panics on empty lists
+        self.names.retain(|pack| pack.empty_list().is_none());
+        let mut ids: Vec<&'static str> = self.species.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let path = origin("species", id);
+            let species = self.species.get_mut(id).unwrap();
+            let names = &self.names;
+            let unknown: Vec<String> = species
+                .names
+                .iter()
+                .filter(|n| !names.iter().any(|p| p.id == **n))
+                .cloned()
+                .collect();
+            species.names.retain(|n| !unknown.contains(n));
+            for pack in unknown {
+                problems.push(DataError::new(
+                    path.clone(),
+                    Some(id),
+                    DataErrorKind::UnknownNamesPack(pack),
+                ));
+            }
+            let empty = if species.genders.is_empty() {
+                Some("genders")
+            } else if species.skin_tones.is_empty() {
+                Some("skin_tones")
+            } else if species.names.is_empty() {
+                Some("names")
+            } else {
+                None
+            };
+            if let Some(list) = empty {
+                problems.push(DataError::new(
+                    path,
+                    Some(id),
+                    DataErrorKind::EmptyOptions(list),
+                ));
+                self.species.remove(id);
+            }
+        }
+        let mut ids: Vec<&'static str> = self.backgrounds.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let path = origin("background", id);
+            let background = self.backgrounds.get_mut(id).unwrap();
+            let mut unknown: Vec<String> = background
+                .items
+                .keys()
+                .filter(|i| {
+                    !self.commodities.contains_key(i.as_str())
+                        && !self.items.contains_key(i.as_str())
+                })
+                .cloned()
+                .collect();
+            unknown.sort();
+            for item in unknown {
+                background.items.remove(&item);
+                problems.push(DataError::new(
+                    path.clone(),
+                    Some(id),
+                    DataErrorKind::UnknownItem(item),
+                ));
+            }
+            if !self.ships.contains_key(background.ship.as_str()) {
+                problems.push(DataError::new(
+                    path,
+                    Some(id),
+                    DataErrorKind::UnknownShipClass(background.ship.clone()),
+                ));
+                self.backgrounds.remove(id);
+            }
+        }
+        self.errors.append(&mut problems);
+    }
+
     fn add_entity(&mut self, entity: DataEntity) {
         match entity {
             DataEntity::Item(item) => {
@@ -56,15 +299,129 @@ impl GameData {
             DataEntity::ShipClass(ship) => {
                 self.ships.insert(make_str(ship.id.as_str()), ship);
             }
-            DataEntity::NamesPack(name_pack) => self.names.push(name_pack),
+            DataEntity::NamesPack(name_pack) => {
+                self.names.retain(|p| p.id != name_pack.id);
+                self.names.push(name_pack);
+            }
+            DataEntity::Commodity(commodity) => {
+                self.commodities
+                    .insert(make_str(commodity.id.as_str()), commodity);
+            }
+            DataEntity::StationClass(station) => {
+                self.stations.insert(make_str(station.id.as_str()), station);
+            }
+            DataEntity::GlyphLegend(legend) => {
+                self.legends.insert(make_str(legend.id.as_str()), legend);
+            }
+            DataEntity::ShipComponent(component) => {
+                self.components
+                    .insert(make_str(component.id.as_str()), component);
+            }
+            DataEntity::Background(background) => {
+                self.backgrounds
+                    .insert(make_str(background.id.as_str()), background);
+            }
+            DataEntity::Species(species) => {
+                self.species.insert(make_str(species.id.as_str()), species);
+            }
+        }
+    }
+
+    fn extend_entity(&mut self, entity: DataEntity) -> Result<(), DataErrorKind> {
+        match entity {
+            DataEntity::NamesPack(name_pack) => self
+                .names
+                .iter_mut()
+                .find(|p| p.id == name_pack.id)
+                .ok_or(DataErrorKind::NothingToExtend)?
+                .extend(name_pack),
+            DataEntity::GlyphLegend(legend) => self
+                .legends
+                .get_mut(legend.id.as_str())
+                .ok_or(DataErrorKind::NothingToExtend)?
+                .extend(legend),
+            entity => return Err(DataErrorKind::NotExtendable(entity.type_name())),
+        }
+        Ok(())
+    }
+}
+
+/// Common part of ship and station classes
+struct Scheme<'a> {
+    tiles: &'a [String],
+    bounds: (i32, i32),
+    decks: i32,
+    legend: &'a str,
+    glyphs: &'a Glyphs,
+}
+
+impl<'a> From<&'a ShipClass> for Scheme<'a> {
+    fn from(ship: &'a ShipClass) -> Self {
+        Self {
+            tiles: &ship.tiles,
+            bounds: ship.bounds,
+            decks: ship.decks,
+            legend: &ship.legend,
+            glyphs: &ship.glyphs,
+        }
+    }
+}
+
+impl<'a> From<&'a StationClass> for Scheme<'a> {
+    fn from(station: &'a StationClass) -> Self {
+        Self {
+            tiles: &station.tiles,
+            bounds: station.bounds,
+            decks: station.decks,
+            legend: &station.legend,
+            glyphs: &station.glyphs,
+        }
+    }
+}
+
+impl<'a> Scheme<'a> {
+    fn check(
+        &self,
+        legends: &HashMap<&str, GlyphLegend>,
+        components: &Components,
+    ) -> Vec<DataErrorKind> {
+        let mut problems = Vec::new();
+        if self.bounds.0 < 0
+            || self.bounds.1 < 0
+            || self.decks < 1
+            || self.tiles.len() != (self.bounds.0 * self.bounds.1 * self.decks) as usize
+        {
+            problems.push(DataErrorKind::BoundsMismatch {
+                tiles: self.tiles.len(),
+                bounds: self.bounds,
+                decks: self.decks,
+            });
+        }
+        match legends.get(self.legend) {
+            Some(shared) => {
+                for glyph in unknown_glyphs(self.tiles, self.glyphs, Some(shared)) {
+                    problems.push(DataErrorKind::UnknownGlyph(glyph.to_string()));
+                }
+            }
+            // every glyph would be unknown without the legend
+            None => problems.push(DataErrorKind::UnknownLegend(self.legend.to_string())),
         }
+        for component in unknown_components(self.glyphs, components) {
+            problems.push(DataErrorKind::UnknownComponent(component.to_string()));
+        }
+        problems
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::data::commodity::TradeCategory;
+    use crate::data::data_error::DataErrorKind;
     use crate::data::game_data::GameData;
     use crate::data::item::ItemTag;
+    use crate::data::mod_manifest::{ModManifest, MANIFEST};
+    use crate::test_dir::TestDir;
+    use std::path::{Path, PathBuf};
 
     #[test]
     fn test_load() {
@@ -76,7 +433,259 @@ mod tests {
         let dugong = data.ships.get("dugong").unwrap();
         assert_eq!(dugong.name, "Dugong");
         assert_eq!(dugong.tiles.len() as i32, dugong.bounds.0 * dugong.bounds.1);
+        assert_eq!(dugong.decks, 1);
         assert_eq!(dugong.tiles.as_slice()[30], "@");
         assert!(!data.names.is_empty());
+        let ore = data.commodities.get("ore").unwrap();
+        assert_eq!(ore.category, TradeCategory::Minerals);
+        let smuggler = data.backgrounds.get("smuggler").unwrap();
+        assert!(data.ships.contains_key(smuggler.ship.as_str()));
+        let vesk = data.species.get("vesk").unwrap();
+        assert!(vesk.lifespan < data.species["human"].lifespan);
+        assert!(vesk
+            .names
+            .iter()
+            .all(|n| data.names.iter().any(|p| p.id == *n)));
+        assert!(data.errors.is_empty(), "{:?}", data.errors);
+    }
+
+    fn write(dir: &Path, file: &str, json: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(file), json).unwrap();
+    }
+
+    fn pack(dir: &Path) -> ModManifest {
+        ModManifest {
+            path: dir.to_path_buf(),
+            ..ModManifest::core()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let tmp = TestDir::new("data-validate");
+        let dir = tmp.path().to_path_buf();
+        write(&dir, "broken.json", "[ { \"type\": ");
+        write(
+            &dir,
+            "entities.json",
+            r#"[
+              {
+                "type": "glyph_legend",
+                "id": "default",
+                "glyphs": {
+                  ".": [ { "part": "floor" } ],
+                  "&": [ { "part": "component", "id": "ghost" } ]
+                }
+              },
+              {
+                "type": "ship_class",
+                "id": "box",
+                "name": "Box",
+                "tiles": [ ".", ".", "?" ],
+                "bounds": [2, 2]
+              },
+              {
+                "type": "ship_class",
+                "id": "fancy",
+                "name": "Fancy",
+                "tiles": [ ".", "." ],
+                "bounds": [2, 1],
+                "legend": "fancy"
+              },
+              {
+                "type": "ship_class",
+                "id": "fancy",
+                "name": "Fancy",
+                "tiles": [ ".", "." ],
+                "bounds": [2, 1],
+                "legend": "fancy"
+              },
+              {
+                "type": "names_pack",
+                "id": "empty",
+                "first_names_male": [],
+                "first_names_female": [ "Ashley" ],
+                "last_names_male": [ "Smith" ]
+              },
+              {
+                "type": "species",
+                "id": "lizard",
+                "name": "Lizard",
+                "body_plan": "serpentine",
+                "adult_age": 5,
+                "lifespan": 40,
+                "genders": [ "Male" ],
+                "skin_tones": [ "Olive" ],
+                "names": [ "empty" ]
+              },
+              {
+                "type": "background",
+                "id": "drifter",
+                "name": "Drifter",
+                "ship": "ghost"
+              },
+              {
+                "type": "background",
+                "id": "trader",
+                "name": "Trader",
+                "items": { "junk": 2 },
+                "ship": "box"
+              }
+            ]"#,
+        );
+        let data = GameData::load_packs(vec![pack(&dir)], Vec::new());
+
+        let broken = dir.join("broken.json");
+        let entities = dir.join("entities.json");
+        let errors: Vec<(PathBuf, Option<&str>, DataErrorKind)> = data
+            .errors
+            .iter()
+            .map(|e| (e.path.clone(), e.id.as_deref(), e.kind.clone()))
+            .collect();
+        assert!(matches!(
+            errors[0],
+            (ref path, None, DataErrorKind::Malformed(..)) if *path == broken
+        ));
+        assert_eq!(
+            errors[1..],
+            [
+                (entities.clone(), Some("fancy"), DataErrorKind::DuplicateId),
+                (
+                    entities.clone(),
+                    Some("default"),
+                    DataErrorKind::UnknownComponent("ghost".to_string())
+                ),
+                (
+                    entities.clone(),
+                    Some("box"),
+                    DataErrorKind::BoundsMismatch {
+                        tiles: 3,
+                        bounds: (2, 2),
+                        decks: 1,
+                    }
+                ),
+                (
+                    entities.clone(),
+                    Some("box"),
+                    DataErrorKind::UnknownGlyph("?".to_string())
+                ),
+                (
+                    entities.clone(),
+                    Some("fancy"),
+                    DataErrorKind::UnknownLegend("fancy".to_string())
+                ),
+                (
+                    entities.clone(),
+                    Some("empty"),
+                    DataErrorKind::EmptyNames("first_names_male")
+                ),
+                (
+                    entities.clone(),
+                    Some("lizard"),
+                    DataErrorKind::UnknownNamesPack("empty".to_string())
+                ),
+                (
+                    entities.clone(),
+                    Some("lizard"),
+                    DataErrorKind::EmptyOptions("names")
+                ),
+                (
+                    entities.clone(),
+                    Some("drifter"),
+                    DataErrorKind::UnknownShipClass("ghost".to_string())
+                ),
+                (
+                    entities.clone(),
+                    Some("trader"),
+                    DataErrorKind::UnknownItem("junk".to_string())
+                ),
+            ]
+        );
+        assert!(data.has_fatal_errors());
+        assert!(data.names.is_empty());
+        assert!(data.species.is_empty());
+        assert!(!data.backgrounds.contains_key("drifter"));
+        assert!(data.backgrounds["trader"].items.is_empty());
+        assert!(data.mods.is_empty());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let tmp = TestDir::new("data-overrides");
+        let dir = tmp.path();
+        let base = dir.join("base");
+        let addon = dir.join("addon");
+        write(
+            &base,
+            "base.json",
+            r#"[
+              {
+                "type": "names_pack",
+                "id": "test",
+                "first_names_male": [ "Ilya" ],
+                "first_names_female": [ "Ashley" ],
+                "last_names_male": [ "Smith" ]
+              },
+              {
+                "type": "commodity",
+                "id": "ore",
+                "name": "Ore",
+                "category": "MINERALS",
+                "base_price": 20
+              }
+            ]"#,
+        );
+        write(
+            &addon,
+            "addon.json",
+            r#"[
+              {
+                "type": "names_pack",
+                "id": "test",
+                "extend": true,
+                "first_names_male": [ "Ivan" ],
+                "first_names_female": [],
+                "last_names_male": []
+              },
+              {
+                "type": "commodity",
+                "id": "ore",
+                "name": "Rich ore",
+                "category": "MINERALS",
+                "base_price": 40
+              },
+              {
+                "type": "commodity",
+                "id": "gold",
+                "extend": true,
+                "name": "Gold",
+                "category": "METALS",
+                "base_price": 400
+              }
+            ]"#,
+        );
+        write(&addon, MANIFEST, r#"{ "id": "addon" }"#);
+        let addon = ModManifest {
+            id: "addon".to_string(),
+            ..pack(&addon)
+        };
+        let data = GameData::load_packs(vec![pack(&base), addon], Vec::new());
+
+        assert_eq!(data.mod_ids(), vec!["addon"]);
+        let names = &data.names[0];
+        assert_eq!(names.first_names_male, vec!["Ilya", "Ivan"]);
+        assert_eq!(names.last_names_male, vec!["Smith"]);
+        assert_eq!(data.commodities.get("ore").unwrap().base_price, 40);
+        assert!(!data.commodities.contains_key("gold"));
+        let errors: Vec<(Option<&str>, &DataErrorKind)> = data
+            .errors
+            .iter()
+            .map(|e| (e.id.as_deref(), &e.kind))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![(Some("gold"), &DataErrorKind::NotExtendable("commodity"))]
+        );
     }
 }
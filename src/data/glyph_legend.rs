@@ -0,0 +1,229 @@
+use crate::data::ship_component::Components;
+use crate::game::ship_parts::component::Component;
+use crate::game::ship_parts::door::Door;
+use crate::game::ship_parts::floor::Floor;
+use crate::game::ship_parts::frame::Frame;
+use crate::game::ship_parts::ladder::Ladder;
+use crate::game::ship_parts::lift::Lift;
+use crate::game::ship_parts::roof::Roof;
+use crate::game::ship_parts::seat::Seat;
+use crate::game::ship_parts::terminal::Terminal;
+use crate::game::ship_parts::wall::{Wall, WallSegment};
+use crate::game::ship_parts::wing::{Wing, WingSegment};
+use crate::game::ship_parts::ShipPart;
+use crate::game::ship_tile::ShipTile;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub const DEFAULT_LEGEND: &str = "default";
+
+pub fn default_legend() -> String {
+    DEFAULT_LEGEND.to_string()
+}
+
+/// One part of the stack, with its parameters
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "part")]
+#[serde(rename_all = "snake_case")]
+pub enum PartSpec {
+    Frame,
+    Floor,
+    Roof,
+    Seat,
+    Terminal,
+    Ladder,
+    Lift,
+    Door {
+        #[serde(default)]
+        open: bool,
+        #[serde(default)]
+        locked: bool,
+        #[serde(default)]
+        airlock: bool,
+        #[serde(default)]
+        jammed: bool,
+    },
+    Wall {
+        style: WallSegment,
+    },
+    Wing {
+        segment: WingSegment,
+    },
+    /// Engine, thruster or fuel tank, depends on the kind of `ShipComponent`
+    Component {
+        id: String,
+    },
+}
+
+impl PartSpec {
+    /// `None` for unknown components, `GameData` reports them when loading
+    pub fn build(&self, components: &Components) -> Option<ShipPart> {
+        Some(match self {
+            PartSpec::Frame => Frame::new().into(),
+            PartSpec::Floor => Floor::new().into(),
+            PartSpec::Roof => Roof::new().into(),
+            PartSpec::Seat => Seat::new().into(),
+            PartSpec::Terminal => Terminal::new().into(),
+            PartSpec::Ladder => Ladder::new().into(),
+            PartSpec::Lift => Lift::new().into(),
+            PartSpec::Door {
+                open,
+                locked,
+                airlock,
+                jammed,
+            } => Door::new(*open, *locked)
+                .with_airlock(*airlock)
+                .with_jammed(*jammed)
+                .into(),
+            PartSpec::Wall { style } => Wall::new(*style).into(),
+            PartSpec::Wing { segment } => Wing::new(*segment).into(),
+            PartSpec::Component { id } => Component::new(components.get(id.as_str())?).into(),
+        })
+    }
+}
+
+/// Stacks of parts for every glyph, in the order they are added to the tile
+pub type Glyphs = HashMap<String, Vec<PartSpec>>;
+
+/// Glyphs shared by ship and station classes
+#[derive(Debug, Deserialize)]
+pub struct GlyphLegend {
+    pub id: String,
+    pub glyphs: Glyphs,
+}
+
+impl GlyphLegend {
+    /// Adds new glyphs and replaces existing ones
+    pub fn extend(&mut self, other: GlyphLegend) {
+        self.glyphs.extend(other.glyphs);
+    }
+}
+
+/// Custom glyphs of the class override glyphs of the shared legend
+pub fn find_glyph<'a>(
+    glyph: &str,
+    custom: &'a Glyphs,
+    shared: Option<&'a GlyphLegend>,
+) -> Option<&'a [PartSpec]> {
+    custom
+        .get(glyph)
+        .or_else(|| shared.and_then(|l| l.glyphs.get(glyph)))
+        .map(Vec::as_slice)
+}
+
+/// Unknown glyphs make void tiles, `GameData` reports them when loading
+pub fn build_tiles(
+    tiles: &[String],
+    custom: &Glyphs,
+    shared: Option<&GlyphLegend>,
+    components: &Components,
+) -> Vec<ShipTile> {
+    tiles
+        .iter()
+        .map(|glyph| ShipTile {
+            parts: find_glyph(glyph, custom, shared)
+                .map(|specs| specs.iter().filter_map(|s| s.build(components)).collect())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Every glyph missing in both legends, once
+pub fn unknown_glyphs<'a>(
+    tiles: &'a [String],
+    custom: &Glyphs,
+    shared: Option<&GlyphLegend>,
+) -> Vec<&'a str> {
+    let mut unknown: Vec<&str> = Vec::new();
+    for glyph in tiles {
+        if find_glyph(glyph, custom, shared).is_none() && !unknown.contains(&glyph.as_str()) {
+            unknown.push(glyph.as_str());
+        }
+    }
+    unknown
+}
+
+/// Every component id used in the glyphs but missing in the data, once
+pub fn unknown_components<'a>(glyphs: &'a Glyphs, components: &Components) -> Vec<&'a str> {
+    let mut unknown: Vec<&str> = glyphs
+        .values()
+        .flatten()
+        .filter_map(|spec| match spec {
+            PartSpec::Component { id } if !components.contains_key(id.as_str()) => {
+                Some(id.as_str())
+            }
+            _ => None,
+        })
+        .collect();
+    // HashMap order is random, but reports should be the same every time
+    unknown.sort();
+    unknown.dedup();
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_tiles, unknown_components, unknown_glyphs, GlyphLegend, Glyphs};
+    use crate::data::ship_component::{ComponentKind, Components};
+    use crate::game::ship_parts::ShipPart;
+
+    #[test]
+    fn test_custom_glyphs() {
+        let legend: GlyphLegend = serde_json::from_str(
+            r#"{
+              "id": "test",
+              "glyphs": {
+                " ": [],
+                ".": [ { "part": "frame" }, { "part": "floor" }, { "part": "roof" } ],
+                "║": [ { "part": "frame" }, { "part": "wall", "style": "Vertical" } ]
+              }
+            }"#,
+        )
+        .unwrap();
+        let custom: Glyphs = serde_json::from_str(
+            r#"{
+              ".": [ { "part": "floor" } ],
+              "x": [ { "part": "floor" }, { "part": "door", "locked": true } ]
+            }"#,
+        )
+        .unwrap();
+        let tiles: Vec<String> = [" ", ".", "║", "x", "?"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(unknown_glyphs(&tiles, &custom, Some(&legend)), vec!["?"]);
+        let tiles = build_tiles(&tiles, &custom, Some(&legend), &Components::new());
+        assert!(tiles[0].is_void());
+        assert_eq!(tiles[1].parts.len(), 1);
+        assert!(matches!(tiles[2].parts[1], ShipPart::Wall(..)));
+        assert!(matches!(tiles[3].parts[1], ShipPart::Door(..)));
+        assert!(tiles[4].is_void());
+    }
+
+    #[test]
+    fn test_components() {
+        let custom: Glyphs = serde_json::from_str(
+            r#"{
+              "&": [ { "part": "frame" }, { "part": "component", "id": "drive" } ],
+              "?": [ { "part": "frame" }, { "part": "component", "id": "ghost" } ]
+            }"#,
+        )
+        .unwrap();
+        let mut components = Components::new();
+        components.insert(
+            "drive",
+            serde_json::from_str(
+                r#"{ "id": "drive", "name": "Drive", "kind": "engine", "mass": 5, "hp": 20, "thrust": 100 }"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(unknown_components(&custom, &components), vec!["ghost"]);
+        let tiles: Vec<String> = ["&", "?"].iter().map(|s| s.to_string()).collect();
+        let tiles = build_tiles(&tiles, &custom, None, &components);
+        assert!(matches!(
+            &tiles[0].parts[1],
+            ShipPart::Component(c) if c.kind() == ComponentKind::Engine
+        ));
+        assert_eq!(tiles[1].parts.len(), 1);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::data::commodity::TradeCategory;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -7,8 +8,41 @@ pub struct Item {
     pub tags: Vec<ItemTag>,
 }
 
+impl Item {
+    pub fn trade_category(&self) -> Option<TradeCategory> {
+        self.tags.iter().find_map(ItemTag::trade_category)
+    }
+}
+
 #[derive(Deserialize, Debug, Eq, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ItemTag {
     BodyPart,
+    // trade categories
+    Food,
+    Medicine,
+    Minerals,
+    Metals,
+    Fuel,
+    Machinery,
+    Electronics,
+    Luxury,
+    Contraband,
+}
+
+impl ItemTag {
+    pub fn trade_category(&self) -> Option<TradeCategory> {
+        match self {
+            ItemTag::BodyPart => None,
+            ItemTag::Food => Some(TradeCategory::Food),
+            ItemTag::Medicine => Some(TradeCategory::Medicine),
+            ItemTag::Minerals => Some(TradeCategory::Minerals),
+            ItemTag::Metals => Some(TradeCategory::Metals),
+            ItemTag::Fuel => Some(TradeCategory::Fuel),
+            ItemTag::Machinery => Some(TradeCategory::Machinery),
+            ItemTag::Electronics => Some(TradeCategory::Electronics),
+            ItemTag::Luxury => Some(TradeCategory::Luxury),
+            ItemTag::Contraband => Some(TradeCategory::Contraband),
+        }
+    }
 }
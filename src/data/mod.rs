@@ -1,5 +1,14 @@
+pub mod background;
+pub mod commodity;
+pub mod data_error;
 mod entity;
 pub mod game_data;
+pub mod glyph_legend;
 pub mod item;
+pub mod mod_manifest;
 pub mod names_pack;
 pub mod ship_class;
+pub mod ship_component;
+pub mod ship_generator;
+pub mod species;
+pub mod station_class;
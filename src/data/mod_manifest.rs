@@ -0,0 +1,175 @@
+use crate::data::data_error::{DataError, DataErrorKind};
+use serde::Deserialize;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Id of the pack in `data/core`, every mod can depend on it
+pub const CORE: &str = "core";
+/// Every directory in `data/mods` with this file is a mod
+pub const MANIFEST: &str = "mod.json";
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    /// Ids of mods that should be loaded before this one
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Mods with lesser order are loaded first and can be overridden by later ones
+    #[serde(default)]
+    pub load_order: i32,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl ModManifest {
+    pub fn core() -> Self {
+        Self {
+            id: CORE.to_string(),
+            name: "Core".to_string(),
+            version: crate::VERSION.to_string(),
+            dependencies: Vec::new(),
+            load_order: i32::MIN,
+            path: ["data", CORE].iter().collect(),
+        }
+    }
+
+    fn load(dir: &Path) -> Result<Self, DataError> {
+        let path = dir.join(MANIFEST);
+        let file = File::open(&path)
+            .map_err(|e| DataError::new(path.clone(), None, DataErrorKind::Io(e.to_string())))?;
+        serde_json::from_reader::<_, ModManifest>(BufReader::new(file))
+            .map(|manifest| ModManifest {
+                path: dir.to_path_buf(),
+                ..manifest
+            })
+            .map_err(|e| DataError::new(path, None, DataErrorKind::Malformed(e.to_string())))
+    }
+}
+
+/// Every mod in `data/mods`, in no particular order
+pub fn installed_mods(errors: &mut Vec<DataError>) -> Vec<ModManifest> {
+    let dir: PathBuf = ["data", "mods"].iter().collect();
+    let mut mods = Vec::new();
+    // having no mods is fine
+    if let Ok(read_dir) = dir.read_dir() {
+        for entry in read_dir.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.join(MANIFEST).is_file() {
+                match ModManifest::load(&path) {
+                    Ok(manifest) => mods.push(manifest),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+    }
+    mods
+}
+
+/// Sorts by `load_order` and id, moving every mod after its dependencies.
+/// Mods with missing (or cyclic) dependencies and duplicates are skipped.
+pub fn resolve_load_order(
+    mut mods: Vec<ModManifest>,
+    errors: &mut Vec<DataError>,
+) -> Vec<ModManifest> {
+    mods.sort_by(|a, b| {
+        a.load_order
+            .cmp(&b.load_order)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    let mut resolved: Vec<ModManifest> = Vec::with_capacity(mods.len());
+    let mut pending: Vec<ModManifest> = Vec::with_capacity(mods.len());
+    for manifest in mods {
+        if manifest.id == CORE
+            || pending.iter().any(|m| m.id == manifest.id)
+            || resolved.iter().any(|m| m.id == manifest.id)
+        {
+            errors.push(DataError::new(
+                manifest.path.join(MANIFEST),
+                Some(manifest.id.as_str()),
+                DataErrorKind::DuplicateId,
+            ));
+        } else {
+            pending.push(manifest);
+        }
+    }
+    while let Some(i) = pending.iter().position(|m| {
+        m.dependencies
+            .iter()
+            .all(|d| d == CORE || resolved.iter().any(|r| r.id == *d))
+    }) {
+        resolved.push(pending.remove(i));
+    }
+    for manifest in pending {
+        let missing = manifest
+            .dependencies
+            .iter()
+            .find(|d| *d != CORE && !resolved.iter().any(|r| r.id == **d))
+            .cloned()
+            .unwrap_or_default();
+        errors.push(DataError::new(
+            manifest.path.join(MANIFEST),
+            Some(manifest.id.as_str()),
+            DataErrorKind::MissingDependency(missing),
+        ));
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_load_order, ModManifest};
+    use crate::data::data_error::DataErrorKind;
+
+    fn manifest(id: &str, load_order: i32, dependencies: &[&str]) -> ModManifest {
+        ModManifest {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: "0.1.0".to_string(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            load_order,
+            path: ["data", "mods", id].iter().collect(),
+        }
+    }
+
+    #[test]
+    fn test_load_order() {
+        let mut errors = Vec::new();
+        let mods = resolve_load_order(
+            vec![
+                manifest("ships", 0, &["names"]),
+                manifest("names", 10, &["core"]),
+                manifest("extra", 0, &[]),
+                manifest("broken", -5, &["unknown"]),
+                manifest("chicken", 0, &["egg"]),
+                manifest("egg", 0, &["chicken"]),
+                manifest("extra", 3, &[]),
+            ],
+            &mut errors,
+        );
+        let ids: Vec<&str> = mods.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["extra", "names", "ships"]);
+        let problems: Vec<(Option<&str>, &DataErrorKind)> =
+            errors.iter().map(|e| (e.id.as_deref(), &e.kind)).collect();
+        assert_eq!(
+            problems,
+            vec![
+                (Some("extra"), &DataErrorKind::DuplicateId),
+                (
+                    Some("broken"),
+                    &DataErrorKind::MissingDependency("unknown".to_string())
+                ),
+                (
+                    Some("chicken"),
+                    &DataErrorKind::MissingDependency("egg".to_string())
+                ),
+                (
+                    Some("egg"),
+                    &DataErrorKind::MissingDependency("chicken".to_string())
+                ),
+            ]
+        );
+    }
+}
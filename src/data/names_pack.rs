@@ -13,6 +13,26 @@ pub struct NamesPack {
 }
 
 impl NamesPack {
+    pub fn extend(&mut self, other: NamesPack) {
+        self.first_names_male.extend(other.first_names_male);
+        self.first_names_female.extend(other.first_names_female);
+        self.last_names_male.extend(other.last_names_male);
+        self.last_names_female.extend(other.last_names_female);
+    }
+
+    /// `random_name` panics if one of these lists is empty
+    pub fn empty_list(&self) -> Option<&'static str> {
+        if self.first_names_male.is_empty() {
+            Some("first_names_male")
+        } else if self.first_names_female.is_empty() {
+            Some("first_names_female")
+        } else if self.last_names_male.is_empty() {
+            Some("last_names_male")
+        } else {
+            None
+        }
+    }
+
     #[allow(dead_code)]
     pub fn random_name<R: Rng + ?Sized>(&self, rng: &mut R, gender: &Gender) -> String {
         let first_names = match gender {
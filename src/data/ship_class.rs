@@ -1,79 +1,54 @@
-use crate::game::ship::Ship;
-use crate::game::ship_parts::door::Door;
-use crate::game::ship_parts::floor::Floor;
-use crate::game::ship_parts::frame::Frame;
-use crate::game::ship_parts::roof::Roof;
-use crate::game::ship_parts::seat::Seat;
-use crate::game::ship_parts::terminal::Terminal;
-use crate::game::ship_parts::wall::Wall;
-use crate::game::ship_parts::wing::Wing;
-use crate::game::ship_parts::ShipPart;
-use crate::game::ship_tile::ShipTile;
+use crate::data::game_data::GameData;
+use crate::data::glyph_legend::{build_tiles, default_legend, Glyphs};
+use crate::game::ship::{default_decks, Ship};
+use geometry::Point;
 use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(Debug, Deserialize)]
 pub struct ShipClass {
     pub id: String,
     pub name: String,
+    /// Decks one after another, from the lowest one
     pub tiles: Vec<String>,
+    /// Size of every deck
     pub bounds: (i32, i32),
+    #[serde(default = "default_decks")]
+    pub decks: i32,
+    /// Id of the shared `GlyphLegend`
+    #[serde(default = "default_legend")]
+    pub legend: String,
+    /// Glyphs of this class only, override the shared legend
+    #[serde(default)]
+    pub glyphs: Glyphs,
+    /// Units of commodities the hold can take
+    #[serde(default)]
+    pub cargo_capacity: u32,
 }
 
-impl From<&str> for ShipTile {
-    fn from(s: &str) -> Self {
-        if s == " " {
-            return ShipTile { parts: vec![] };
-        }
-        let mut parts: Vec<ShipPart> = vec![Frame::new().into()];
-        match s {
-            ch @ ("d" | "b" | "M" | "V" | "P") => {
-                parts.push(Wing::new(ch).into());
-            }
-            ch @ ("╔" | "═" | "╗" | "║" | "╝" | "╚" | "╠" | "╦" | "╣" | "╩" | "╬") =>
-            {
-                parts.push(Wall::new(ch).into());
-            }
-            "." => {
-                parts.push(Floor::new().into());
-                parts.push(Roof::new().into());
-            }
-            "+" => {
-                parts.push(Floor::new().into());
-                parts.push(Door::new(false, false).into());
-                parts.push(Roof::new().into());
-            }
-            "=" => {
-                parts.push(Floor::new().into());
-                parts.push(Door::new(false, true).into());
-                parts.push(Roof::new().into());
-            }
-            "@" => {
-                parts.push(Floor::new().into());
-                parts.push(Terminal::new().into());
-                parts.push(Roof::new().into());
-            }
-            "h" => {
-                parts.push(Floor::new().into());
-                parts.push(Seat::new().into());
-                parts.push(Roof::new().into());
-            }
-            _ => println!("'{}' is not a valid tile", s),
-        }
-        ShipTile { parts }
-    }
-}
-
-pub fn generate_ship<S: Into<String>>(name: S, scheme: &ShipClass) -> Ship {
-    Ship {
+/// New ship with full tanks
+pub fn generate_ship<S: Into<String>>(name: S, scheme: &ShipClass, data: &GameData) -> Ship {
+    let mut ship = Ship {
         name: name.into(),
         class_name: scheme.name.clone(),
-        tiles: scheme
-            .tiles
-            .iter()
-            .map(|s| ShipTile::from(s.as_str()))
-            .collect(),
+        tiles: build_tiles(
+            &scheme.tiles,
+            &scheme.glyphs,
+            data.legends.get(scheme.legend.as_str()),
+            &data.components,
+        ),
         bounds: scheme.bounds,
-    }
+        decks: scheme.decks,
+        quadrant: Point::new(0, 0),
+        destination: None,
+        credits: 0,
+        cargo: HashMap::new(),
+        cargo_capacity: scheme.cargo_capacity,
+        fuel: 0.0,
+        docked: None,
+    };
+    ship.fuel = ship.stats().fuel_capacity;
+    ship
 }
 
 #[cfg(test)]
@@ -85,7 +60,7 @@ mod tests {
     #[test]
     fn make_dugong() {
         let data = GameData::load();
-        let ship = generate_ship("Dugong I", data.ships.get("dugong").unwrap());
+        let ship = generate_ship("Dugong I", data.ships.get("dugong").unwrap(), &data);
         assert_eq!(ship.name, "Dugong I");
         assert_eq!(ship.class_name, "Dugong");
         let tiles = ship.tiles.as_slice();
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    /// Main drive, pushes the ship between quadrants
+    Engine,
+    /// Small maneuvering engine, adds a bit of thrust
+    Thruster,
+    FuelTank,
+}
+
+/// Stats of engines, thrusters and fuel tanks, parts copy them when a ship is built
+#[derive(Deserialize, Debug, Clone)]
+pub struct ShipComponent {
+    pub id: String,
+    pub name: String,
+    pub kind: ComponentKind,
+    /// Tonnes
+    pub mass: u32,
+    pub hp: u32,
+    /// Kilonewtons, for engines and thrusters
+    #[serde(default)]
+    pub thrust: u32,
+    /// Units of fuel, for fuel tanks
+    #[serde(default)]
+    pub fuel_capacity: u32,
+}
+
+pub type Components = HashMap<&'static str, ShipComponent>;
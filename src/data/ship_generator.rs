@@ -0,0 +1,326 @@
+use crate::data::glyph_legend::{default_legend, Glyphs};
+use crate::data::ship_class::ShipClass;
+use crate::game::ship::default_decks;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const SEED_SALT: u64 = 0x5419_C1A5;
+/// Units of cargo per floor tile of the hold
+const CARGO_PER_TILE: u32 = 3;
+
+const VOID: char = ' ';
+const FLOOR: char = '.';
+/// Placeholder, replaced with a proper box-drawing glyph when the layout is ready
+const WALL: char = '#';
+const DOOR: char = '+';
+const AIRLOCK: char = '=';
+const TERMINAL: char = '@';
+const SEAT: char = 'h';
+const WING: char = 'M';
+const ENGINE: char = '☼';
+const THRUSTER: char = '^';
+const FUEL_TANK: char = 'Θ';
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Room {
+    Cockpit,
+    Compartment,
+    Airlock,
+    Cargo,
+}
+
+/// Rows of the hull between two bulkheads
+#[derive(Debug, Copy, Clone)]
+struct Section {
+    room: Room,
+    rows: i32,
+    /// Column of the wall dividing the section in two rooms
+    split: Option<i32>,
+}
+
+struct Layout {
+    width: i32,
+    height: i32,
+    glyphs: Vec<char>,
+}
+
+impl Layout {
+    fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            glyphs: vec![VOID; (width * height) as usize],
+        }
+    }
+
+    fn get(&self, x: i32, y: i32) -> char {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            VOID
+        } else {
+            self.glyphs[(y * self.width + x) as usize]
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, glyph: char) {
+        self.glyphs[(y * self.width + x) as usize] = glyph;
+    }
+
+    fn is_wall(&self, x: i32, y: i32) -> bool {
+        matches!(self.get(x, y), WALL | DOOR | AIRLOCK)
+    }
+
+    /// Box-drawing glyph joining wall with its neighbours
+    fn wall_glyph(&self, x: i32, y: i32) -> char {
+        let n = self.is_wall(x, y - 1);
+        let s = self.is_wall(x, y + 1);
+        let w = self.is_wall(x - 1, y);
+        let e = self.is_wall(x + 1, y);
+        match (n, s, w, e) {
+            (true, true, true, true) => '╬',
+            (true, true, false, true) => '╠',
+            (true, true, true, false) => '╣',
+            (false, true, true, true) => '╦',
+            (true, false, true, true) => '╩',
+            (false, true, false, true) => '╔',
+            (false, true, true, false) => '╗',
+            (true, false, false, true) => '╚',
+            (true, false, true, false) => '╝',
+            (_, _, false, false) => '║',
+            (false, false, _, _) => '═',
+        }
+    }
+
+    fn into_tiles(self) -> Vec<String> {
+        let mut tiles = Vec::with_capacity(self.glyphs.len());
+        for (i, glyph) in self.glyphs.iter().enumerate() {
+            let (x, y) = (i as i32 % self.width, i as i32 / self.width);
+            let glyph = if *glyph == WALL {
+                self.wall_glyph(x, y)
+            } else {
+                *glyph
+            };
+            tiles.push(glyph.to_string());
+        }
+        tiles
+    }
+}
+
+fn class_name(length: i32) -> &'static str {
+    match length {
+        ..=16 => "Shuttle",
+        17..=22 => "Corvette",
+        _ => "Frigate",
+    }
+}
+
+/// Column for a door in the bulkhead, away from the walls of both neighbouring sections
+fn door_column<R: Rng + ?Sized>(rng: &mut R, from: i32, to: i32, walls: &[Option<i32>]) -> i32 {
+    let columns: Vec<i32> = (from..=to).filter(|x| !walls.contains(&Some(*x))).collect();
+    columns[rng.gen_range(0..columns.len())]
+}
+
+/// Random single-deck ship: hull with wings, thrusters and engines, cockpit in the front,
+/// compartments with airlocks in the middle and cargo hold with fuel tanks in the back.
+/// Same seed always gives the same class.
+pub fn generate_class(seed: u64) -> ShipClass {
+    let mut rng = StdRng::seed_from_u64(seed ^ SEED_SALT);
+
+    let inner_width = rng.gen_range(2..=4) * 2 + 1;
+    let wings = rng.gen_range(1..=3);
+    let hull_width = inner_width + 2;
+    let width = hull_width + wings * 2;
+    let (left, right) = (wings, wings + hull_width - 1);
+    let center = (left + right) / 2;
+
+    let mut sections = vec![Section {
+        room: Room::Cockpit,
+        rows: 2,
+        split: None,
+    }];
+    let compartments = rng.gen_range(1..=3);
+    let airlock = rng.gen_range(0..compartments);
+    for i in 0..compartments {
+        let room = if i == airlock {
+            Room::Airlock
+        } else {
+            Room::Compartment
+        };
+        let rows = rng.gen_range(if room == Room::Airlock { 3 } else { 2 }..=4);
+        let split = if rng.gen_bool(0.5) {
+            Some(center + rng.gen_range(-1..=1))
+        } else {
+            None
+        };
+        sections.push(Section { room, rows, split });
+    }
+    sections.push(Section {
+        room: Room::Cargo,
+        rows: rng.gen_range(3..=5),
+        split: None,
+    });
+
+    let inner_height = sections.iter().map(|s| s.rows).sum::<i32>() + sections.len() as i32 - 1;
+    let hull_height = inner_height + 2;
+    let height = hull_height + 1;
+    let mut layout = Layout::new(width, height);
+
+    // hull
+    for y in 0..hull_height {
+        for x in left..=right {
+            let border = x == left || x == right || y == 0 || y == hull_height - 1;
+            layout.set(x, y, if border { WALL } else { FLOOR });
+        }
+    }
+
+    // rooms
+    let mut cargo_tiles = 0;
+    let mut top = 1;
+    let mut above: Option<Section> = None;
+    for section in sections.iter() {
+        if let Some(prev) = above {
+            let bulkhead = top - 1;
+            for x in left + 1..right {
+                layout.set(x, bulkhead, WALL);
+            }
+            let walls = [prev.split, section.split];
+            if let Some(split) = section.split {
+                let x = door_column(&mut rng, left + 1, split - 1, &walls);
+                layout.set(x, bulkhead, DOOR);
+                let x = door_column(&mut rng, split + 1, right - 1, &walls);
+                layout.set(x, bulkhead, DOOR);
+            } else {
+                let x = door_column(&mut rng, left + 1, right - 1, &walls);
+                layout.set(x, bulkhead, DOOR);
+            }
+        }
+        if let Some(split) = section.split {
+            for y in top..top + section.rows {
+                layout.set(split, y, WALL);
+            }
+        }
+        match section.room {
+            Room::Cockpit => {
+                layout.set(center, top, TERMINAL);
+                layout.set(center, top + 1, SEAT);
+            }
+            Room::Airlock => {
+                let y = top + section.rows / 2;
+                layout.set(left, y, AIRLOCK);
+                layout.set(right, y, AIRLOCK);
+            }
+            Room::Cargo => {
+                // tanks in the back corners, where they can't block the door
+                let bottom = top + section.rows - 1;
+                layout.set(left + 1, bottom, FUEL_TANK);
+                layout.set(right - 1, bottom, FUEL_TANK);
+                cargo_tiles = section.rows * inner_width - 2;
+            }
+            Room::Compartment => {}
+        }
+        top += section.rows + 1;
+        above = Some(*section);
+    }
+
+    // wings, outer columns start further back
+    let start = rng.gen_range(1..=hull_height / 3);
+    for i in 0..wings {
+        let first = start + i;
+        for (x, front, back) in [(left - 1 - i, 'd', 'V'), (right + 1 + i, 'b', 'P')] {
+            for y in first..height {
+                let glyph = if y == first {
+                    front
+                } else if y == height - 1 && i == wings - 1 {
+                    back
+                } else {
+                    WING
+                };
+                layout.set(x, y, glyph);
+            }
+        }
+    }
+    layout.set(left - wings, start + wings, THRUSTER);
+    layout.set(right + wings, start + wings, THRUSTER);
+    layout.set(left - 1, height - 1, ENGINE);
+    layout.set(right + 1, height - 1, ENGINE);
+
+    ShipClass {
+        id: format!("generated_{:x}", seed),
+        name: class_name(hull_height).to_string(),
+        tiles: layout.into_tiles(),
+        bounds: (width, height),
+        decks: default_decks(),
+        legend: default_legend(),
+        glyphs: Glyphs::new(),
+        cargo_capacity: cargo_tiles as u32 * CARGO_PER_TILE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate_class;
+    use crate::data::game_data::GameData;
+    use crate::data::glyph_legend::unknown_glyphs;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_same_seed() {
+        let first = generate_class(42);
+        let second = generate_class(42);
+        assert_eq!(first.tiles, second.tiles);
+        assert_eq!(first.bounds, second.bounds);
+        let other = generate_class(43);
+        assert_ne!(first.tiles, other.tiles);
+    }
+
+    #[test]
+    fn test_layout() {
+        let data = GameData::load();
+        let legend = data.legends.get("default");
+        for seed in 0..50 {
+            let class = generate_class(seed);
+            let (width, height) = class.bounds;
+            assert_eq!(class.tiles.len(), (width * height) as usize);
+            assert!(unknown_glyphs(&class.tiles, &class.glyphs, legend).is_empty());
+            for glyph in ["@", "h", "=", "d", "b", "☼", "^", "Θ"] {
+                assert!(class.tiles.iter().any(|t| t == glyph), "{} {}", seed, glyph);
+            }
+            assert!(class.cargo_capacity > 0);
+
+            // every room is reachable from the pilot seat
+            let walkable = |i: usize| matches!(class.tiles[i].as_str(), "." | "+" | "=" | "h");
+            let start = class.tiles.iter().position(|t| t == "h").unwrap();
+            let mut visited = HashSet::from([start]);
+            let mut queue = vec![start];
+            while let Some(i) = queue.pop() {
+                let (x, y) = (i as i32 % width, i as i32 / width);
+                for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+                    let (x, y) = (x + dx, y + dy);
+                    if x < 0 || y < 0 || x >= width || y >= height {
+                        continue;
+                    }
+                    let j = (y * width + x) as usize;
+                    if walkable(j) && visited.insert(j) {
+                        queue.push(j);
+                    }
+                }
+            }
+            let unreachable = (0..class.tiles.len())
+                .filter(|i| walkable(*i) && !visited.contains(i))
+                .count();
+            assert_eq!(unreachable, 0, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_generate_ship() {
+        let data = GameData::load();
+        let class = generate_class(7);
+        let ship = crate::game::ship::Ship::generate("Wreck", &class, &data);
+        assert_eq!(ship.tiles.len(), class.tiles.len());
+        assert_eq!(ship.cargo_capacity, class.cargo_capacity);
+        let stats = ship.stats();
+        assert!(stats.acceleration() > 0.0);
+        assert!(stats.range(stats.fuel_capacity) > 0.0);
+        assert_eq!(ship.fuel, stats.fuel_capacity);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::human::body::BodyPlan;
+use crate::human::skin_tone::SkinTone;
+use serde::Deserialize;
+
+fn one() -> f32 {
+    1.0
+}
+
+/// Characters copy these traits to their `Body` when they are created
+#[derive(Deserialize, Debug, Clone)]
+pub struct Species {
+    pub id: String,
+    pub name: String,
+    pub body_plan: BodyPlan,
+    /// Youngest age of a new character
+    pub adult_age: u8,
+    /// Oldest age of a new character, old age effects start from the half of it
+    pub lifespan: u16,
+    /// Multiplier for walking, `Passage::Passable` is measured for a two-legged human
+    #[serde(default = "one")]
+    pub walk_cost: f32,
+    /// Multiplier for ladders and other ways between decks
+    #[serde(default = "one")]
+    pub climb_cost: f32,
+    /// "Male" and "Female" use names from the packs, others use random names of both
+    pub genders: Vec<String>,
+    pub skin_tones: Vec<SkinTone>,
+    /// Ids of `NamesPack`
+    pub names: Vec<String>,
+}
@@ -0,0 +1,86 @@
+use crate::data::game_data::GameData;
+use crate::data::glyph_legend::{build_tiles, default_legend, Glyphs};
+use crate::game::ship::{default_decks, Ship};
+use crate::game::station::StationKind;
+use geometry::Point;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Layout of station interior, uses the same glyphs as `ShipClass`
+#[derive(Debug, Deserialize)]
+pub struct StationClass {
+    pub id: String,
+    pub name: String,
+    /// Decks one after another, from the lowest one
+    pub tiles: Vec<String>,
+    /// Size of every deck
+    pub bounds: (i32, i32),
+    #[serde(default = "default_decks")]
+    pub decks: i32,
+    /// Id of the shared `GlyphLegend`
+    #[serde(default = "default_legend")]
+    pub legend: String,
+    /// Glyphs of this class only, override the shared legend
+    #[serde(default)]
+    pub glyphs: Glyphs,
+    /// Kinds of stations built by this scheme, any kind if empty
+    #[serde(default)]
+    pub kinds: Vec<StationKind>,
+}
+
+impl StationClass {
+    pub fn suits(&self, kind: StationKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+/// Stations are built from the same tiles as ships but never travel
+pub fn generate_station<S: Into<String>>(
+    name: S,
+    scheme: &StationClass,
+    quadrant: Point,
+    data: &GameData,
+) -> Ship {
+    Ship {
+        name: name.into(),
+        class_name: scheme.name.clone(),
+        tiles: build_tiles(
+            &scheme.tiles,
+            &scheme.glyphs,
+            data.legends.get(scheme.legend.as_str()),
+            &data.components,
+        ),
+        bounds: scheme.bounds,
+        decks: scheme.decks,
+        quadrant,
+        destination: None,
+        credits: 0,
+        cargo: HashMap::new(),
+        cargo_capacity: 0,
+        fuel: 0.0,
+        docked: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate_station;
+    use crate::data::game_data::GameData;
+    use crate::game::station::StationKind;
+    use geometry::Point;
+
+    #[test]
+    fn make_station() {
+        let data = GameData::load();
+        for scheme in data.stations.values() {
+            assert_eq!(scheme.tiles.len() as i32, scheme.bounds.0 * scheme.bounds.1);
+            let station = generate_station("Test", scheme, Point::new(1, 2), &data);
+            assert!(!station.airlocks().is_empty());
+            assert_eq!(station.quadrant, Point::new(1, 2));
+        }
+        let kinds = [StationKind::Mining, StationKind::Outpost];
+        for kind in kinds {
+            assert!(data.stations.values().any(|s| s.suits(kind)));
+        }
+    }
+}
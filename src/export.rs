@@ -0,0 +1,316 @@
+//! Headless export of galaxy density maps, doesn't create a window.
+//!
+//! Usage: `spaceage export [--size normal] [--class spiral] [--arms 2]
+//! [--windings 1.25] [--width 0.03] [--format png|pgm] [--out dir] <seed>...`
+//!
+//! Every seed is hashed the same way as on the "Create new game" screen,
+//! so exported image shows exactly the galaxy you will get in game.
+
+use crate::astro::galaxy::GalaxyMeta;
+use crate::astro::galaxy_class::GalaxyClass;
+use crate::astro::galaxy_generator::generate_quadrants;
+use crate::astro::galaxy_size::GalaxySize;
+use crate::astro::spiral_arms::SpiralArms;
+use crate::colors::Colors;
+use crate::ui::galaxy_pixels;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub const COMMAND: &str = "export";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Pgm,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Pgm => "pgm",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    InvalidArguments(String),
+    SystemError(String),
+    EncodingError(String),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::InvalidArguments(message) => write!(f, "{}", message),
+            ExportError::SystemError(message) => write!(f, "can't write file: {}", message),
+            ExportError::EncodingError(message) => write!(f, "can't encode image: {}", message),
+        }
+    }
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::SystemError(e.to_string())
+    }
+}
+
+impl From<png::EncodingError> for ExportError {
+    fn from(e: png::EncodingError) -> Self {
+        ExportError::EncodingError(e.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub struct ExportOptions {
+    pub size: GalaxySize,
+    pub class: GalaxyClass,
+    pub arms: SpiralArms,
+    pub format: ImageFormat,
+    pub out: PathBuf,
+    pub seeds: Vec<String>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            size: GalaxySize::Normal,
+            class: GalaxyClass::Spiral,
+            arms: SpiralArms::default(),
+            format: ImageFormat::Png,
+            out: PathBuf::from("."),
+            seeds: Vec::new(),
+        }
+    }
+}
+
+/// "Bared Spiral" -> "baredspiral", so both `--class bared-spiral` and `--class BaredSpiral` work
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn parse_named<T: enum_iterator::Sequence + Copy, F: Fn(&T) -> &str>(
+    value: &str,
+    name: F,
+) -> Option<T> {
+    let value = normalize(value);
+    enum_iterator::all::<T>().find(|item| normalize(name(item)) == value)
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, ExportError> {
+    value
+        .parse()
+        .map_err(|_| ExportError::InvalidArguments(format!("Invalid value for {}: {}", key, value)))
+}
+
+impl ExportOptions {
+    pub fn parse(args: &[String]) -> Result<Self, ExportError> {
+        let mut options = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                options.seeds.push(arg.clone());
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| ExportError::InvalidArguments(format!("No value for {}", arg)))?;
+            match arg.as_str() {
+                "--size" => {
+                    options.size = parse_named(value, GalaxySize::name).ok_or_else(|| {
+                        ExportError::InvalidArguments(format!("Unknown galaxy size: {}", value))
+                    })?;
+                }
+                "--class" => {
+                    options.class = parse_named(value, GalaxyClass::name).ok_or_else(|| {
+                        ExportError::InvalidArguments(format!("Unknown galaxy class: {}", value))
+                    })?;
+                }
+                "--arms" => options.arms.count = parse_number(arg, value)?,
+                "--windings" => options.arms.windings = parse_number(arg, value)?,
+                "--width" => options.arms.width = parse_number(arg, value)?,
+                "--format" => {
+                    options.format = match value.to_lowercase().as_str() {
+                        "png" => ImageFormat::Png,
+                        "pgm" => ImageFormat::Pgm,
+                        _ => {
+                            return Err(ExportError::InvalidArguments(format!(
+                                "Unknown image format: {}",
+                                value
+                            )))
+                        }
+                    };
+                }
+                "--out" => options.out = PathBuf::from(value),
+                _ => {
+                    return Err(ExportError::InvalidArguments(format!(
+                        "Unknown option: {}",
+                        arg
+                    )))
+                }
+            }
+        }
+        if options.seeds.is_empty() {
+            return Err(ExportError::InvalidArguments(
+                "At least one seed is required".to_string(),
+            ));
+        }
+        options.arms.validate();
+        Ok(options)
+    }
+
+    fn file_name(&self, seed: &str) -> String {
+        let seed: String = seed
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        format!(
+            "{}-{}-{}.{}",
+            normalize(self.class.name()),
+            normalize(self.size.name()),
+            seed,
+            self.format.extension()
+        )
+    }
+}
+
+/// Binary greyscale image, brightness is the stars density
+pub fn encode_pgm(size: usize, quadrants: &[u32]) -> Vec<u8> {
+    let header = format!("P5\n{} {}\n255\n", size, size);
+    let mut data = Vec::with_capacity(header.len() + size * size);
+    data.extend_from_slice(header.as_bytes());
+    data.extend(
+        galaxy_pixels(size, quadrants)
+            .chunks(4)
+            .map(|pixel| pixel[3]),
+    );
+    data
+}
+
+/// RGB image looking the same as the galaxy preview in game
+pub fn encode_png<W: Write>(size: usize, quadrants: &[u32], w: W) -> Result<(), ExportError> {
+    let bg = [
+        Colors::SPACE_VIOLET.r,
+        Colors::SPACE_VIOLET.g,
+        Colors::SPACE_VIOLET.b,
+    ];
+    let data: Vec<u8> = galaxy_pixels(size, quadrants)
+        .chunks(4)
+        .flat_map(|pixel| {
+            let alpha = pixel[3] as f32 / 255.0;
+            (0..3).map(move |i| {
+                (bg[i] * 255.0 * (1.0 - alpha) + pixel[i] as f32 * alpha).round() as u8
+            })
+        })
+        .collect();
+    let mut encoder = png::Encoder::new(w, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    Ok(())
+}
+
+pub fn export(options: &ExportOptions, seed: &str) -> Result<PathBuf, ExportError> {
+    let meta = GalaxyMeta::new(
+        seed.to_string(),
+        seed.to_string(),
+        options.size,
+        options.class,
+        options.arms,
+    );
+    let size = meta.size.into();
+    let quadrants = generate_quadrants(meta.seed, size, meta.class, &meta.arms);
+    let path = options.out.join(options.file_name(seed));
+    let mut file = BufWriter::new(File::create(&path)?);
+    match options.format {
+        ImageFormat::Png => encode_png(size, &quadrants, &mut file)?,
+        ImageFormat::Pgm => file.write_all(&encode_pgm(size, &quadrants))?,
+    }
+    file.flush()?;
+    Ok(path)
+}
+
+/// Entry point for `spaceage export ...`, returns process exit code
+pub fn run(args: &[String]) -> i32 {
+    let options = match ExportOptions::parse(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}", err);
+            eprintln!(
+                "Usage: spaceage {} [--size normal] [--class spiral] [--arms 2] \
+                [--windings 1.25] [--width 0.03] [--format png|pgm] [--out dir] <seed>...",
+                COMMAND
+            );
+            return 2;
+        }
+    };
+    if !Path::new(&options.out).exists() {
+        if let Err(err) = std::fs::create_dir_all(&options.out) {
+            eprintln!("Can't create {}: {}", options.out.display(), err);
+            return 1;
+        }
+    }
+    let mut code = 0;
+    for seed in options.seeds.iter() {
+        match export(&options, seed) {
+            Ok(path) => println!("{}", path.display()),
+            Err(err) => {
+                eprintln!("Can't export galaxy for seed {}: {}", seed, err);
+                code = 1;
+            }
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_pgm, encode_png, ExportOptions, ImageFormat};
+    use crate::astro::galaxy_class::GalaxyClass;
+    use crate::astro::galaxy_size::GalaxySize;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = ExportOptions::parse(&args(
+            "--class bared-spiral --size HUGE --arms 4 --format pgm 42 Andromeda",
+        ))
+        .unwrap();
+        assert_eq!(options.class, GalaxyClass::BaredSpiral);
+        assert!(matches!(options.size, GalaxySize::Huge));
+        assert_eq!(options.arms.count, 4);
+        assert_eq!(options.format, ImageFormat::Pgm);
+        assert_eq!(options.seeds, vec!["42", "Andromeda"]);
+        assert_eq!(
+            options.file_name("Andromeda"),
+            "baredspiral-huge-Andromeda.pgm"
+        );
+
+        assert!(ExportOptions::parse(&args("--class unknown 42")).is_err());
+        assert!(ExportOptions::parse(&args("--size normal")).is_err());
+        assert!(ExportOptions::parse(&args("42 --arms")).is_err());
+    }
+
+    #[test]
+    fn test_encode() {
+        let quadrants = vec![0, 262_144, 131_072, 0];
+        let pgm = encode_pgm(2, &quadrants);
+        assert!(pgm.starts_with(b"P5\n2 2\n255\n"));
+        // rows go by y, quadrants are stored by columns
+        assert_eq!(&pgm[pgm.len() - 4..], &[0, 128, 255, 0]);
+
+        let mut png = Vec::new();
+        encode_png(2, &quadrants, &mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -1,8 +1,15 @@
 use crate::game::passage::Passage;
+use crate::game::ship::DeckDirection;
 use crate::game::ship_parts::ShipPartAction;
 use crate::game::world::World;
+use crate::human::skills::Skill;
 use geometry::Direction;
+use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Walking through airlocks takes longer than through usual doors
+const BOARDING_LENGTH: u32 = 30;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[non_exhaustive]
@@ -10,14 +17,54 @@ pub enum ActionType {
     SkippingTime,
     Walking(Direction),
     ActivatingPart(Direction, ShipPartAction),
+    /// Climbing a ladder or riding a lift
+    ChangingDeck(DeckDirection),
 }
 
 impl ActionType {
+    /// Length for the current avatar, skilled characters act faster
     pub fn length(&self, world: &World) -> Option<u32> {
+        let body = &world.avatar.character.body;
+        // species move differently, climbing ladders is hard without hands
+        let cost = match self {
+            ActionType::Walking(_) => body.walk_cost,
+            ActionType::ChangingDeck(_) => body.climb_cost,
+            _ => 1.0,
+        };
+        let length = self.base_length(world)? as f32 * cost;
+        let modifier = match self.skill(world) {
+            Some((skill, _)) => world
+                .avatar
+                .character
+                .length_modifier(skill, world.timestamp()),
+            None => 1.0,
+        };
+        Some(((length * modifier).round() as u32).max(1))
+    }
+
+    /// Skill used by the action and its difficulty
+    pub fn skill(&self, world: &World) -> Option<(Skill, u8)> {
+        match self {
+            ActionType::SkippingTime => None,
+            ActionType::Walking(_) => Some((Skill::Athletics, 0)),
+            ActionType::ActivatingPart(dir, action) => world.tile_at(*dir)?.action_skill(*action),
+            ActionType::ChangingDeck(dir) => world
+                .tile_at(Direction::Here)?
+                .action_skill(dir.part_action()),
+        }
+    }
+
+    fn base_length(&self, world: &World) -> Option<u32> {
         match self {
             ActionType::SkippingTime => Some(1),
             ActionType::Walking(dir) => {
-                let tile = world.ship.get_tile(world.avatar.pos + *dir)?;
+                if world.can_board(*dir) {
+                    return Some(BOARDING_LENGTH);
+                }
+                if world.is_occupied_at(*dir) {
+                    return None;
+                }
+                let tile = world.tile_at(*dir)?;
                 if let Passage::Passable(length) = tile.passage() {
                     Some(length as u32)
                 } else {
@@ -30,10 +77,38 @@ impl ActionType {
                 {
                     None // TODO: hint explaining why it isn't working
                 } else {
-                    let tile = world.ship.get_tile(world.avatar.pos + *dir)?;
+                    let tile = world.tile_at(*dir)?;
                     tile.action_length(*action)
                 }
             }
+            ActionType::ChangingDeck(dir) => {
+                world.deck_target(*dir)?;
+                world
+                    .tile_at(Direction::Here)?
+                    .action_length(dir.part_action())
+            }
+        }
+    }
+}
+
+/// Outcome of an action the player has to see, `World::take_events()` collects them
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ActionEvent {
+    /// Character got a new level of the skill
+    LevelUp(String, Skill),
+    /// Skill check failed, time of the action is spent for nothing
+    Failed(String, Skill),
+}
+
+impl Display for ActionEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionEvent::LevelUp(name, skill) => {
+                write!(f, "{} is better at {} now!", name, skill.name())
+            }
+            ActionEvent::Failed(name, skill) => {
+                write!(f, "{} failed a {} check!", name, skill.name())
+            }
         }
     }
 }
@@ -51,17 +126,39 @@ impl Action {
     }
 
     pub fn act(&self, world: &mut World) {
+        if let Some((skill, difficulty)) = self.typ.skill(world) {
+            let now = world.timestamp();
+            let character = &mut world.avatar.character;
+            if character.train(skill, 1 + difficulty as u32, now) {
+                let event = ActionEvent::LevelUp(character.name.clone(), skill);
+                world.push_event(event);
+            }
+            let character = &world.avatar.character;
+            if difficulty > 0
+                && thread_rng().gen::<f32>() >= character.success_chance(skill, difficulty)
+            {
+                // TODO: animation
+                let event = ActionEvent::Failed(character.name.clone(), skill);
+                world.push_event(event);
+                return;
+            }
+        }
         match self.typ {
             ActionType::SkippingTime => {}
             ActionType::Walking(dir) => {
-                world.move_avatar(dir);
+                if world.can_board(dir) {
+                    world.board(dir);
+                } else if !world.is_occupied_at(dir) {
+                    // somebody could step here while we were walking
+                    world.move_avatar(dir);
+                }
             }
             ActionType::ActivatingPart(dir, action) => {
-                let pos = world.avatar.pos + dir;
-                if let Some(tile) = world.ship.get_tile_mut(pos) {
+                if let Some(tile) = world.tile_at_mut(dir) {
                     tile.act(action);
                 }
             }
+            ActionType::ChangingDeck(dir) => world.change_deck(dir),
         }
     }
 }
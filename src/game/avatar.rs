@@ -3,12 +3,25 @@ use crate::human::character::Character;
 use geometry::{Direction, Point};
 use serde::{Deserialize, Serialize};
 
+/// Structure the avatar walks on
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Aboard {
+    #[default]
+    Ship,
+    Station(usize),
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Avatar {
     pub character: Character,
     pub pos: Point, // tile in ship
+    /// Index of the deck, 0 is the lowest one
+    #[serde(default)]
+    pub deck: i32,
     pub vision: Direction,
     pub action: Option<Action>,
+    #[serde(default)]
+    pub aboard: Aboard,
 }
 
 impl Avatar {
@@ -16,8 +29,10 @@ impl Avatar {
         Avatar {
             character,
             pos,
+            deck: 0,
             vision: Direction::East,
             action: None,
+            aboard: Aboard::Ship,
         }
     }
 }
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+/// Timestamp of the first tick, 1 January 3000
+const START_TIME: i64 = 32_503_680_000;
+const TICKS_PER_SECOND: i64 = 60;
+
+/// Seconds of the in-game calendar, same as `World::time()`
+pub fn timestamp(tick: u128) -> i64 {
+    START_TIME + tick as i64 / TICKS_PER_SECOND
+}
+
+pub fn date(timestamp: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp, 0).unwrap()
+}
+
+/// Full years passed between two timestamps, zero if `to` is earlier
+pub fn years_between(from: i64, to: i64) -> u32 {
+    if to <= from {
+        return 0;
+    }
+    let (from, to) = (date(from), date(to));
+    let mut years = to.year() - from.year();
+    if (to.month(), to.day(), to.num_seconds_from_midnight())
+        < (from.month(), from.day(), from.num_seconds_from_midnight())
+    {
+        years -= 1;
+    }
+    years as u32
+}
+
+/// Same day and time `years` earlier, 29 February becomes 28 February
+pub fn years_before(timestamp: i64, years: u32) -> i64 {
+    let date = date(timestamp);
+    let year = date.year() - years as i32;
+    date.with_year(year)
+        .or_else(|| date.with_day(28).and_then(|d| d.with_year(year)))
+        .unwrap()
+        .timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{timestamp, years_before, years_between};
+
+    #[test]
+    fn test_years() {
+        let now = timestamp(0);
+        let birth = years_before(now, 30);
+        assert_eq!(years_between(birth, now), 30);
+        assert_eq!(years_between(birth, now - 1), 29);
+        assert_eq!(years_between(now, birth), 0);
+        // a year of game time later
+        let later = timestamp(366 * 24 * 60 * 60 * 60);
+        assert_eq!(years_between(birth, later), 31);
+    }
+}
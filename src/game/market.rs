@@ -0,0 +1,245 @@
+use crate::data::commodity::Commodity;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// `World::time()` counts 60 ticks per second
+pub const TICKS_PER_DAY: u128 = 60 * 60 * 60 * 24;
+/// Stock at which commodity costs exactly `base_price`
+const REFERENCE_STOCK: f32 = 100.0;
+/// How strong price reacts to the stock, 0.5 means 4x stock is 2x cheaper
+const ELASTICITY: f32 = 0.5;
+/// Part of the stock imbalance fixed by local producers and consumers every day
+const RESTOCK_RATE: f64 = 0.2;
+/// Difference between buying and selling prices
+const SPREAD: f32 = 0.1;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TradeError {
+    NotTraded,
+    NotEnoughStock,
+    NotEnoughCredits,
+    NotEnoughCargo,
+    NoCargoSpace,
+    /// Deal is worth more credits than can be counted
+    TooExpensive,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Goods {
+    pub commodity: String,
+    pub stock: u32,
+    /// Stock the market returns to if nobody trades here
+    pub equilibrium: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Market {
+    pub goods: Vec<Goods>,
+    /// Random drift of prices is the same for the same seed and day
+    seed: u64,
+    /// Tick of the last `update()`
+    updated: u128,
+}
+
+/// Random value in -1..1 for every day
+fn daily_noise(seed: u64, index: usize, day: u128) -> f32 {
+    let mut rng = StdRng::seed_from_u64(
+        seed ^ (day as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ index as u64,
+    );
+    rng.gen_range(-1.0..1.0)
+}
+
+impl Market {
+    pub fn new(seed: u64, goods: Vec<Goods>, tick: u128) -> Self {
+        Self {
+            goods,
+            seed,
+            updated: tick,
+        }
+    }
+
+    fn index(&self, commodity: &str) -> Option<usize> {
+        self.goods.iter().position(|g| g.commodity == commodity)
+    }
+
+    pub fn goods(&self, commodity: &str) -> Option<&Goods> {
+        self.goods.iter().find(|g| g.commodity == commodity)
+    }
+
+    /// Stocks move back to equilibrium with time
+    pub fn update(&mut self, tick: u128) {
+        if tick <= self.updated {
+            return;
+        }
+        let days = (tick - self.updated) as f64 / TICKS_PER_DAY as f64;
+        let keep = (1.0 - RESTOCK_RATE).powf(days);
+        for goods in self.goods.iter_mut() {
+            let equilibrium = goods.equilibrium as f64;
+            goods.stock = (equilibrium + (goods.stock as f64 - equilibrium) * keep).round() as u32;
+        }
+        self.updated = tick;
+    }
+
+    /// Smooth drift in -1..1, interpolated between random values of two days
+    fn drift(&self, index: usize, tick: u128) -> f32 {
+        let day = tick / TICKS_PER_DAY;
+        let t = (tick % TICKS_PER_DAY) as f32 / TICKS_PER_DAY as f32;
+        let today = daily_noise(self.seed, index, day);
+        let tomorrow = daily_noise(self.seed, index, day + 1);
+        today + (tomorrow - today) * t
+    }
+
+    /// Middle price between buying and selling at some stock level
+    fn price(&self, commodity: &Commodity, index: usize, stock: u32, tick: u128) -> f32 {
+        let supply = (REFERENCE_STOCK / stock.max(1) as f32)
+            .powf(ELASTICITY)
+            .clamp(0.25, 4.0);
+        let drift = 1.0 + commodity.volatility * self.drift(index, tick);
+        commodity.base_price as f32 * supply * drift
+    }
+
+    /// Credits the station asks for `amount` units, every next unit costs more
+    pub fn buy_cost(
+        &self,
+        commodity: &Commodity,
+        amount: u32,
+        tick: u128,
+    ) -> Result<u32, TradeError> {
+        let index = self
+            .index(commodity.id.as_str())
+            .ok_or(TradeError::NotTraded)?;
+        let stock = self.goods[index].stock;
+        (1..=amount)
+            .try_fold(0u32, |cost, i| {
+                let price = self.price(commodity, index, stock.saturating_sub(i), tick);
+                cost.checked_add(((price * (1.0 + SPREAD / 2.0)).ceil() as u32).max(1))
+            })
+            .ok_or(TradeError::TooExpensive)
+    }
+
+    /// Credits the station pays for `amount` units, every next unit costs less.
+    /// Selling back just bought goods always costs exactly the spread.
+    pub fn sell_revenue(
+        &self,
+        commodity: &Commodity,
+        amount: u32,
+        tick: u128,
+    ) -> Result<u32, TradeError> {
+        let index = self
+            .index(commodity.id.as_str())
+            .ok_or(TradeError::NotTraded)?;
+        let stock = self.goods[index].stock;
+        (0..amount)
+            .try_fold(0u32, |revenue, i| {
+                let price = self.price(commodity, index, stock.saturating_add(i), tick);
+                revenue.checked_add((price * (1.0 - SPREAD / 2.0)).floor() as u32)
+            })
+            .ok_or(TradeError::TooExpensive)
+    }
+
+    /// Price the station asks for one unit, `None` if commodity isn't traded here
+    pub fn buy_price(&self, commodity: &Commodity, tick: u128) -> Option<u32> {
+        self.buy_cost(commodity, 1, tick).ok()
+    }
+
+    /// Price the station pays for one unit, `None` if commodity isn't traded here
+    pub fn sell_price(&self, commodity: &Commodity, tick: u128) -> Option<u32> {
+        self.sell_revenue(commodity, 1, tick).ok()
+    }
+
+    /// Station sells goods
+    pub fn take(&mut self, commodity: &str, amount: u32) -> Result<(), TradeError> {
+        let index = self.index(commodity).ok_or(TradeError::NotTraded)?;
+        let goods = &mut self.goods[index];
+        if goods.stock < amount {
+            return Err(TradeError::NotEnoughStock);
+        }
+        goods.stock -= amount;
+        Ok(())
+    }
+
+    /// Station buys goods
+    pub fn put(&mut self, commodity: &str, amount: u32) -> Result<(), TradeError> {
+        let index = self.index(commodity).ok_or(TradeError::NotTraded)?;
+        self.goods[index].stock = self.goods[index].stock.saturating_add(amount);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Goods, Market, TradeError, TICKS_PER_DAY};
+    use crate::data::commodity::{Commodity, TradeCategory};
+
+    fn ore() -> Commodity {
+        Commodity {
+            id: "ore".to_string(),
+            name: "Ore".to_string(),
+            category: TradeCategory::Minerals,
+            base_price: 100,
+            volatility: 0.1,
+        }
+    }
+
+    fn market() -> Market {
+        Market::new(
+            42,
+            vec![Goods {
+                commodity: "ore".to_string(),
+                stock: 100,
+                equilibrium: 100,
+            }],
+            0,
+        )
+    }
+
+    #[test]
+    fn test_supply_and_demand() {
+        let ore = ore();
+        let mut market = market();
+        let price = market.buy_price(&ore, 0).unwrap();
+        assert!((95..=120).contains(&price));
+        assert!(market.sell_price(&ore, 0).unwrap() < price);
+
+        let cost = market.buy_cost(&ore, 75, 0).unwrap();
+        market.take("ore", 75).unwrap();
+        assert!(market.buy_price(&ore, 0).unwrap() > price * 3 / 2);
+        assert!(market.sell_revenue(&ore, 75, 0).unwrap() < cost);
+        assert_eq!(market.take("ore", 26), Err(TradeError::NotEnoughStock));
+        assert_eq!(market.take("gold", 1), Err(TradeError::NotTraded));
+
+        market.update(TICKS_PER_DAY * 30);
+        assert!(market.goods("ore").unwrap().stock > 95);
+    }
+
+    #[test]
+    fn test_huge_deals() {
+        let mut ore = ore();
+        ore.base_price = u32::MAX / 4;
+        let market = market();
+        assert_eq!(market.buy_cost(&ore, 10, 0), Err(TradeError::TooExpensive));
+        assert_eq!(
+            market.sell_revenue(&ore, 10, 0),
+            Err(TradeError::TooExpensive)
+        );
+        assert!(market.buy_cost(&ore, 1, 0).is_ok());
+    }
+
+    #[test]
+    fn test_drift() {
+        let ore = ore();
+        let market = market();
+        let prices: Vec<u32> = (0..10)
+            .map(|day| market.buy_price(&ore, day * TICKS_PER_DAY).unwrap())
+            .collect();
+        assert!(prices.iter().any(|p| *p != prices[0]));
+        assert!(prices.iter().all(|p| (94..=122).contains(p)));
+        assert_eq!(
+            prices,
+            (0..10)
+                .map(|day| market.buy_price(&ore, day * TICKS_PER_DAY).unwrap())
+                .collect::<Vec<u32>>()
+        );
+    }
+}
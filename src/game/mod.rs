@@ -1,7 +1,10 @@
 pub mod action;
 pub mod avatar;
+pub mod calendar;
+pub mod market;
 mod passage;
 pub mod ship;
 pub mod ship_parts;
 pub mod ship_tile;
+pub mod station;
 pub mod world;
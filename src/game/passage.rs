@@ -1,5 +1,5 @@
 #[derive(Debug, Eq, PartialEq)]
 pub enum Passage {
-    Passable(u8), // ticks to pass (for 2-legged human)
+    Passable(u8), // ticks to pass (for 2-legged human, see `Body::walk_cost`)
     Unpassable,
 }
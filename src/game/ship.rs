@@ -1,38 +1,270 @@
+use crate::data::game_data::GameData;
 use crate::data::ship_class::{generate_ship, ShipClass};
 use crate::fov::FovMap;
+use crate::game::passage::Passage;
+use crate::game::ship_parts::{ShipPart, ShipPartAction};
 use crate::game::ship_tile::ShipTile;
 use geometry::Point;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Tonnes of hull for every tile with a frame
+const FRAME_MASS: u32 = 1;
+/// Tonnes of every unit of cargo
+const CARGO_MASS: u32 = 1;
+/// Fuel burnt per quadrant for every tonne of mass
+const FUEL_PER_TONNE: f32 = 0.05;
+/// Meters, galaxy is not to scale
+const QUADRANT_LENGTH: f32 = 2.5e8;
+/// Same as in `World::time()`
+const TICKS_PER_SECOND: f32 = 60.0;
+/// FTL voyages aren't to scale either, an hour of the flight model is a week of the calendar
+const VOYAGE_TIME_SCALE: f32 = 168.0;
+
+pub fn default_decks() -> i32 {
+    1
+}
+
+/// Saves made before the galaxy map get the start quadrant from the format 0 migration,
+/// this corner is only a fallback for ship lines without it
+fn default_quadrant() -> Point {
+    Point::new(0, 0)
+}
+
+/// Way to the next deck
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DeckDirection {
+    Up,
+    Down,
+}
+
+impl DeckDirection {
+    pub fn delta(&self) -> i32 {
+        match self {
+            DeckDirection::Up => 1,
+            DeckDirection::Down => -1,
+        }
+    }
+
+    /// Action the ladder or lift should support to go this way
+    pub fn part_action(&self) -> ShipPartAction {
+        match self {
+            DeckDirection::Up => ShipPartAction::GoUp,
+            DeckDirection::Down => ShipPartAction::GoDown,
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            DeckDirection::Up => DeckDirection::Down,
+            DeckDirection::Down => DeckDirection::Up,
+        }
+    }
+}
+
+/// Performance derived from parts and cargo, see `Ship::stats()`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShipStats {
+    /// Tonnes
+    pub mass: u32,
+    /// Kilonewtons of every engine and thruster
+    pub thrust: f32,
+    pub fuel_capacity: f32,
+}
+
+impl ShipStats {
+    /// Meters per second squared
+    pub fn acceleration(&self) -> f32 {
+        if self.mass == 0 {
+            0.0
+        } else {
+            self.thrust / self.mass as f32
+        }
+    }
+
+    /// Heavier ships burn more
+    pub fn fuel_usage(&self) -> f32 {
+        self.mass as f32 * FUEL_PER_TONNE
+    }
+
+    pub fn fuel_needed(&self, distance: f32) -> f32 {
+        distance * self.fuel_usage()
+    }
+
+    /// Quadrants the ship flies on this fuel, `fuel_capacity` for full tanks
+    pub fn range(&self, fuel: f32) -> f32 {
+        if self.mass == 0 {
+            0.0
+        } else {
+            fuel / self.fuel_usage()
+        }
+    }
+
+    /// Ticks to accelerate half the way and brake the rest, `None` if the ship can't move.
+    /// Every quadrant takes weeks, so long voyages span years.
+    pub fn travel_time(&self, distance: f32) -> Option<u128> {
+        let acceleration = self.acceleration();
+        if acceleration <= 0.0 {
+            return None;
+        }
+        let seconds = 2.0 * (distance * QUADRANT_LENGTH / acceleration).sqrt();
+        Some((seconds * VOYAGE_TIME_SCALE * TICKS_PER_SECOND).round() as u128)
+    }
+}
+
+/// One level of the ship, every deck has the same bounds
+pub struct Deck<'a> {
+    pub tiles: &'a [ShipTile],
+    pub bounds: (i32, i32),
+}
 
 #[allow(dead_code)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Ship {
     pub name: String,
     pub class_name: String,
+    /// Decks one after another, from the lowest one
     pub tiles: Vec<ShipTile>,
+    /// Size of every deck
     pub bounds: (i32, i32),
+    #[serde(default = "default_decks")]
+    pub decks: i32,
+    #[serde(default = "default_quadrant")]
+    pub quadrant: Point, // position in galaxy
+    pub destination: Option<Point>,
+    /// Ship's account, shared by the whole crew
+    #[serde(default)]
+    pub credits: u32,
+    /// Units of commodities in the hold
+    #[serde(default)]
+    pub cargo: HashMap<String, u32>,
+    #[serde(default)]
+    pub cargo_capacity: u32,
+    /// Units of fuel left in the tanks
+    #[serde(default)]
+    pub fuel: f32,
+    /// Index of the station in `World.stations`
+    #[serde(default)]
+    pub docked: Option<usize>,
     // pub squawk: Squawk,  // TODO: implement squawk code (as Part)
 }
 
 impl Ship {
-    pub fn generate<S: Into<String>>(name: S, scheme: &ShipClass) -> Self {
-        generate_ship(name, scheme)
+    pub fn generate<S: Into<String>>(name: S, scheme: &ShipClass, data: &GameData) -> Self {
+        generate_ship(name, scheme, data)
     }
 
+    /// Center of the lowest deck
     pub fn find_start_point(&self) -> Point {
         Point::new(self.bounds.0 / 2, self.bounds.1 / 2)
     }
 
-    pub fn get_tile(&self, point: Point) -> Option<&ShipTile> {
-        self.tiles.get(point.to_index(self.bounds.0)?)
+    /// Quadrants to the point in the galaxy
+    pub fn distance(&self, to: Point) -> f32 {
+        f32::hypot(
+            (to.x - self.quadrant.x) as f32,
+            (to.y - self.quadrant.y) as f32,
+        )
+    }
+
+    pub fn cargo_total(&self) -> u32 {
+        self.cargo.values().sum()
+    }
+
+    pub fn cargo_space(&self) -> u32 {
+        self.cargo_capacity.saturating_sub(self.cargo_total())
+    }
+
+    pub fn stats(&self) -> ShipStats {
+        let mut stats = ShipStats {
+            mass: self.cargo_total() * CARGO_MASS,
+            thrust: 0.0,
+            fuel_capacity: 0.0,
+        };
+        for part in self.tiles.iter().flat_map(|t| t.parts.iter()) {
+            match part {
+                ShipPart::Frame(..) => stats.mass += FRAME_MASS,
+                ShipPart::Component(component) => {
+                    stats.mass += component.mass();
+                    stats.thrust += component.thrust();
+                    stats.fuel_capacity += component.fuel_capacity();
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    fn deck_size(&self) -> usize {
+        (self.bounds.0 * self.bounds.1) as usize
+    }
+
+    pub fn deck(&self, deck: i32) -> Option<Deck<'_>> {
+        if deck < 0 || deck >= self.decks {
+            return None;
+        }
+        let start = deck as usize * self.deck_size();
+        Some(Deck {
+            tiles: self.tiles.get(start..start + self.deck_size())?,
+            bounds: self.bounds,
+        })
+    }
+
+    /// Airlocks on every deck
+    pub fn airlocks(&self) -> Vec<(i32, Point)> {
+        let size = self.deck_size();
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| tile.is_airlock())
+            .map(|(i, _)| {
+                (
+                    (i / size) as i32,
+                    Point::from_index(i % size, self.bounds.0),
+                )
+            })
+            .collect()
     }
 
-    pub fn get_tile_mut(&mut self, point: Point) -> Option<&mut ShipTile> {
-        self.tiles.get_mut(point.to_index(self.bounds.0)?)
+    pub fn lock_airlocks(&mut self, locked: bool) {
+        for tile in self.tiles.iter_mut() {
+            tile.set_airlock_locked(locked);
+        }
+    }
+
+    fn index(&self, deck: i32, point: Point) -> Option<usize> {
+        if deck < 0 || deck >= self.decks || point.y >= self.bounds.1 {
+            return None;
+        }
+        Some(deck as usize * self.deck_size() + point.to_index(self.bounds.0)?)
+    }
+
+    pub fn get_tile(&self, deck: i32, point: Point) -> Option<&ShipTile> {
+        self.tiles.get(self.index(deck, point)?)
+    }
+
+    pub fn get_tile_mut(&mut self, deck: i32, point: Point) -> Option<&mut ShipTile> {
+        let index = self.index(deck, point)?;
+        self.tiles.get_mut(index)
+    }
+
+    /// Deck reached by the ladder or lift in this tile, if there is a ladder or lift there too
+    pub fn deck_target(&self, deck: i32, point: Point, dir: DeckDirection) -> Option<i32> {
+        let target = deck + dir.delta();
+        let from = self.get_tile(deck, point)?;
+        let to = self.get_tile(target, point)?;
+        if from.supports_action(dir.part_action())
+            && to.supports_action(dir.opposite().part_action())
+            && matches!(to.passage(), Passage::Passable(..))
+        {
+            Some(target)
+        } else {
+            None
+        }
     }
 }
 
-impl FovMap for Ship {
+impl FovMap for Deck<'_> {
     fn dimensions(&self) -> Point {
         self.bounds.into()
     }
@@ -46,21 +278,50 @@ impl FovMap for Ship {
 mod tests {
     use crate::data::game_data::GameData;
     use crate::data::ship_class::generate_ship;
+    use crate::data::ship_component::ComponentKind;
     use crate::fov::field_of_view_set;
+    use crate::game::ship_parts::ShipPart;
     use geometry::Point;
 
     #[test]
     fn check_fov() {
         let data = GameData::load();
-        let ship = generate_ship("Dugong I", data.ships.get("dugong").unwrap());
+        let ship = generate_ship("Dugong I", data.ships.get("dugong").unwrap(), &data);
 
         let fov = field_of_view_set(
             Point::new(6, 14),
             ship.bounds.0.max(ship.bounds.1) as u32,
-            &ship,
+            &ship.deck(0).unwrap(),
         );
         assert!(fov.contains(&Point::new(5, 15)));
         assert!(fov.contains(&Point::new(5, 14)));
         assert!(fov.contains(&Point::new(4, 15)));
     }
+
+    #[test]
+    fn test_stats() {
+        let data = GameData::load();
+        let mut ship = generate_ship("Dugong I", data.ships.get("dugong").unwrap(), &data);
+        let stats = ship.stats();
+        assert!(stats.mass > 0);
+        assert!(stats.acceleration() > 0.0);
+        assert_eq!(stats.fuel_capacity, 400.0);
+        assert_eq!(ship.fuel, stats.fuel_capacity);
+
+        for part in ship.tiles.iter_mut().flat_map(|t| t.parts.iter_mut()) {
+            if let ShipPart::Component(component) = part {
+                match component.kind() {
+                    ComponentKind::Engine => component.damage(30),
+                    ComponentKind::FuelTank => component.damage(40),
+                    ComponentKind::Thruster => {}
+                }
+            }
+        }
+        let damaged = ship.stats();
+        assert_eq!(damaged.mass, stats.mass);
+        assert!(damaged.thrust < stats.thrust);
+        assert!(damaged.travel_time(1.0) > stats.travel_time(1.0));
+        assert_eq!(damaged.fuel_capacity, 0.0);
+        assert_eq!(damaged.range(damaged.fuel_capacity), 0.0);
+    }
 }
@@ -0,0 +1,92 @@
+use super::super::passage::Passage;
+use super::{ShipPartInteract, ShipPartView};
+use crate::ascii::tile::Tile;
+use crate::colors::Colors;
+use crate::data::ship_component::{ComponentKind, ShipComponent};
+use serde::{Deserialize, Serialize};
+
+/// Engine, thruster or fuel tank, the kind of `ShipComponent` decides what it gives to the ship
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Component {
+    /// Id of the `ShipComponent` it was built from
+    component: String,
+    kind: ComponentKind,
+    hp: u32,
+    max_hp: u32,
+    mass: u32,
+    thrust: u32,
+    fuel_capacity: u32,
+}
+
+impl Component {
+    pub fn new(component: &ShipComponent) -> Self {
+        Self {
+            component: component.id.clone(),
+            kind: component.kind,
+            hp: component.hp,
+            max_hp: component.hp,
+            mass: component.mass,
+            thrust: component.thrust,
+            fuel_capacity: component.fuel_capacity,
+        }
+    }
+
+    pub fn kind(&self) -> ComponentKind {
+        self.kind
+    }
+
+    pub fn mass(&self) -> u32 {
+        self.mass
+    }
+
+    /// Share of performance left, damaged parts work worse.
+    /// Nothing in the game damages parts yet, so it's always 1.0 outside of tests.
+    pub fn condition(&self) -> f32 {
+        if self.max_hp == 0 {
+            0.0
+        } else {
+            self.hp.min(self.max_hp) as f32 / self.max_hp as f32
+        }
+    }
+
+    /// Damaged engine or thruster gives less thrust
+    pub fn thrust(&self) -> f32 {
+        self.thrust as f32 * self.condition()
+    }
+
+    /// Damaged tank leaks and holds less fuel
+    pub fn fuel_capacity(&self) -> f32 {
+        self.fuel_capacity as f32 * self.condition()
+    }
+
+    /// Only for tests until combat or wear are added, see `condition()`
+    #[cfg(test)]
+    pub fn damage(&mut self, amount: u32) {
+        self.hp = self.hp.saturating_sub(amount);
+    }
+}
+
+impl ShipPartView for Component {
+    fn z_index(&self) -> i8 {
+        9
+    }
+
+    fn tile(&self) -> Tile {
+        let (ch, color) = match self.kind {
+            ComponentKind::Engine => ('☼', Colors::ORANGE),
+            ComponentKind::Thruster => ('▲', Colors::LIGHT_GRAY),
+            ComponentKind::FuelTank => ('Θ', Colors::AMBER),
+        };
+        Tile::new(ch, color, Some(Colors::DARK_GRAY))
+    }
+
+    fn is_transparent(&self) -> bool {
+        false
+    }
+}
+
+impl ShipPartInteract for Component {
+    fn passage(&self) -> Passage {
+        Passage::Unpassable
+    }
+}
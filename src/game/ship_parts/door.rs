@@ -2,13 +2,25 @@ use super::super::passage::Passage;
 use super::{ShipPartAction, ShipPartInteract, ShipPartView};
 use crate::ascii::tile::Tile;
 use crate::colors::Colors;
+use crate::human::skills::Skill;
 use serde::{Deserialize, Serialize};
 
+/// Forcing a jammed door open takes a while even for an engineer
+const JAMMED_OPEN_LENGTH: u32 = 60;
+/// Engineering level giving a 50% chance to open a jammed door
+const JAMMED_DIFFICULTY: u8 = 3;
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
 pub struct Door {
     hp: u32,
     open: bool,
     locked: bool,
+    /// Outer door, leads to the docked ship or station
+    #[serde(default)]
+    airlock: bool,
+    /// Stuck in the frame, has to be forced open
+    #[serde(default)]
+    jammed: bool,
 }
 
 impl Door {
@@ -17,6 +29,38 @@ impl Door {
             hp: 42,
             open,
             locked,
+            airlock: false,
+            jammed: false,
+        }
+    }
+
+    /// Airlocks are locked until somebody docks to them
+    pub fn airlock() -> Self {
+        Self::new(false, true).with_airlock(true)
+    }
+
+    pub fn with_airlock(mut self, airlock: bool) -> Self {
+        self.airlock = airlock;
+        self
+    }
+
+    pub fn with_jammed(mut self, jammed: bool) -> Self {
+        self.jammed = jammed;
+        self
+    }
+
+    pub fn is_jammed(&self) -> bool {
+        self.jammed
+    }
+
+    pub fn is_airlock(&self) -> bool {
+        self.airlock
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+        if locked {
+            self.open = false;
         }
     }
 }
@@ -35,6 +79,8 @@ impl ShipPartView for Door {
     fn tile(&self) -> Tile {
         if self.open {
             Tile::with_floor('.', Colors::LIGHT_STEEL_BLUE)
+        } else if self.jammed {
+            Tile::with_floor('≡', Colors::ORANGE)
         } else {
             Tile::with_floor('≡', Colors::LIGHT_STEEL_BLUE)
         }
@@ -65,7 +111,9 @@ impl ShipPartInteract for Door {
     fn action_length(&self, action: ShipPartAction) -> Option<u32> {
         match action {
             ShipPartAction::Open => {
-                if !self.open {
+                if !self.open && self.jammed {
+                    Some(JAMMED_OPEN_LENGTH)
+                } else if !self.open {
                     Some(10)
                 } else {
                     None
@@ -82,6 +130,13 @@ impl ShipPartInteract for Door {
         }
     }
 
+    fn action_skill(&self, action: ShipPartAction) -> Option<(Skill, u8)> {
+        match action {
+            ShipPartAction::Open if self.jammed => Some((Skill::Engineering, JAMMED_DIFFICULTY)),
+            _ => None,
+        }
+    }
+
     fn act(&mut self, action: ShipPartAction) {
         match action {
             ShipPartAction::Open => {
@@ -89,6 +144,7 @@ impl ShipPartInteract for Door {
                     println!("Door is locked!"); // TODO: log, animation
                 } else {
                     self.open = true;
+                    self.jammed = false;
                 }
             }
             ShipPartAction::Close => {
@@ -0,0 +1,58 @@
+use super::super::passage::Passage;
+use super::{ShipPartInteract, ShipPartView};
+use crate::ascii::tile::Tile;
+use crate::colors::Colors;
+use crate::game::ship_parts::ShipPartAction;
+use crate::human::skills::Skill;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Ladder {
+    hp: u32,
+}
+
+impl Ladder {
+    pub fn new() -> Self {
+        Self { hp: 50 }
+    }
+}
+
+impl Default for Ladder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShipPartView for Ladder {
+    fn z_index(&self) -> i8 {
+        8
+    }
+
+    fn tile(&self) -> Tile {
+        Tile::with_floor('H', Colors::LIGHT_GRAY)
+    }
+}
+
+impl ShipPartInteract for Ladder {
+    fn passage(&self) -> Passage {
+        Passage::Passable(10)
+    }
+
+    fn supported_actions(&self) -> &[ShipPartAction] {
+        &[ShipPartAction::GoUp, ShipPartAction::GoDown]
+    }
+
+    fn action_length(&self, action: ShipPartAction) -> Option<u32> {
+        match action {
+            ShipPartAction::GoUp | ShipPartAction::GoDown => Some(40),
+            _ => None,
+        }
+    }
+
+    fn action_skill(&self, action: ShipPartAction) -> Option<(Skill, u8)> {
+        match action {
+            ShipPartAction::GoUp | ShipPartAction::GoDown => Some((Skill::Athletics, 0)),
+            _ => None,
+        }
+    }
+}
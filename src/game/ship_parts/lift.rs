@@ -0,0 +1,51 @@
+use super::super::passage::Passage;
+use super::{ShipPartInteract, ShipPartView};
+use crate::ascii::tile::Tile;
+use crate::colors::Colors;
+use crate::game::ship_parts::ShipPartAction;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct Lift {
+    hp: u32,
+}
+
+impl Lift {
+    pub fn new() -> Self {
+        Self { hp: 50 }
+    }
+}
+
+impl Default for Lift {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShipPartView for Lift {
+    fn z_index(&self) -> i8 {
+        8
+    }
+
+    fn tile(&self) -> Tile {
+        Tile::new('≡', Colors::LIGHT_GRAY, Some(Colors::DARK_SLATE_GRAY))
+    }
+}
+
+impl ShipPartInteract for Lift {
+    fn passage(&self) -> Passage {
+        Passage::Passable(10)
+    }
+
+    fn supported_actions(&self) -> &[ShipPartAction] {
+        &[ShipPartAction::GoUp, ShipPartAction::GoDown]
+    }
+
+    /// Faster than climbing a ladder
+    fn action_length(&self, action: ShipPartAction) -> Option<u32> {
+        match action {
+            ShipPartAction::GoUp | ShipPartAction::GoDown => Some(20),
+            _ => None,
+        }
+    }
+}
@@ -1,15 +1,21 @@
+pub mod component;
 pub mod door;
 pub mod floor;
 pub mod frame;
+pub mod ladder;
+pub mod lift;
 pub mod roof;
 pub mod seat;
 pub mod terminal;
 pub mod wall;
 pub mod wing;
 
+use self::component::Component;
 use self::door::Door;
 use self::floor::Floor;
 use self::frame::Frame;
+use self::ladder::Ladder;
+use self::lift::Lift;
 use self::roof::Roof;
 use self::seat::Seat;
 use self::terminal::Terminal;
@@ -17,6 +23,7 @@ use self::wall::Wall;
 use self::wing::Wing;
 use crate::ascii::tile::Tile;
 use crate::game::passage::Passage;
+use crate::human::skills::Skill;
 use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -27,6 +34,9 @@ pub enum ShipPartAction {
     Open,
     Close,
     UseTerminal,
+    /// Going to the deck above by ladder or lift
+    GoUp,
+    GoDown,
 }
 
 #[enum_dispatch(ShipPart)]
@@ -57,6 +67,11 @@ pub trait ShipPartInteract {
     fn action_length(&self, _action: ShipPartAction) -> Option<u32> {
         None
     }
+    /// Skill making the action faster and its difficulty,
+    /// actions with difficulty above zero can fail
+    fn action_skill(&self, _action: ShipPartAction) -> Option<(Skill, u8)> {
+        None
+    }
     fn act(&mut self, _action: ShipPartAction) {}
 }
 
@@ -71,6 +86,9 @@ pub enum ShipPart {
     Door,
     Seat,
     Terminal,
+    Ladder,
+    Lift,
+    Component,
 }
 
 impl PartialOrd<Self> for ShipPart {
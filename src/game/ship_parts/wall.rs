@@ -63,17 +63,14 @@ pub struct Wall {
 }
 
 impl Wall {
-    pub fn new(ch: &str) -> Self {
-        Self {
-            hp: 100,
-            var: WallSegment::from(ch),
-        }
+    pub fn new(var: WallSegment) -> Self {
+        Self { hp: 100, var }
     }
 }
 
 impl Default for Wall {
     fn default() -> Self {
-        Self::new("╬")
+        Self::new(WallSegment::Cross)
     }
 }
 
@@ -45,17 +45,14 @@ pub struct Wing {
 }
 
 impl Wing {
-    pub fn new(ch: &str) -> Self {
-        Self {
-            hp: 30,
-            var: WingSegment::from(ch),
-        }
+    pub fn new(var: WingSegment) -> Self {
+        Self { hp: 30, var }
     }
 }
 
 impl Default for Wing {
     fn default() -> Self {
-        Self::new("M")
+        Self::new(WingSegment::Normal)
     }
 }
 
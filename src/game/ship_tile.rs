@@ -1,5 +1,6 @@
 use crate::game::passage::Passage;
 use crate::game::ship_parts::{ShipPart, ShipPartAction, ShipPartInteract, ShipPartView};
+use crate::human::skills::Skill;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -38,6 +39,13 @@ impl ShipTile {
             .action_length(action)
     }
 
+    pub fn action_skill(&self, action: ShipPartAction) -> Option<(Skill, u8)> {
+        self.parts
+            .iter()
+            .find(|p| p.supports_action(action))?
+            .action_skill(action)
+    }
+
     pub fn act(&mut self, action: ShipPartAction) {
         self.parts
             .iter_mut()
@@ -47,6 +55,27 @@ impl ShipTile {
             });
     }
 
+    /// Tiles without roof are on the outer side of the hull
+    pub fn is_outside(&self) -> bool {
+        !self.parts.iter().any(|p| matches!(p, ShipPart::Roof(..)))
+    }
+
+    pub fn is_airlock(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|p| matches!(p, ShipPart::Door(door) if door.is_airlock()))
+    }
+
+    pub fn set_airlock_locked(&mut self, locked: bool) {
+        for part in self.parts.iter_mut() {
+            if let ShipPart::Door(door) = part {
+                if door.is_airlock() {
+                    door.set_locked(locked);
+                }
+            }
+        }
+    }
+
     pub fn is_transparent(&self) -> bool {
         self.parts.iter().all(|p| p.is_transparent())
     }
@@ -0,0 +1,353 @@
+use crate::astro::galaxy::Galaxy;
+use crate::astro::quadrant::QUADRANT_SIZE;
+use crate::data::commodity::TradeCategory;
+use crate::data::game_data::GameData;
+use crate::data::names_pack::NamesPack;
+use crate::game::market::{Goods, Market};
+use crate::game::ship::Ship;
+use enum_iterator::{all, Sequence};
+use geometry::Point;
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+const SEED_SALT: u64 = 0x57A7_1025;
+const SUFFIXES: [&str; 6] = ["Station", "Port", "Hub", "Depot", "Dock", "Terminal"];
+const PRODUCED_STOCK: u32 = 400;
+const CONSUMED_STOCK: u32 = 40;
+const NEUTRAL_STOCK: u32 = 100;
+
+#[derive(Serialize, Deserialize, Sequence, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StationKind {
+    Agricultural,
+    Mining,
+    Refinery,
+    Industrial,
+    HighTech,
+    /// Lawless station out of factions territories
+    Outpost,
+}
+
+impl StationKind {
+    pub fn name(&self) -> &str {
+        match self {
+            StationKind::Agricultural => "Agricultural",
+            StationKind::Mining => "Mining",
+            StationKind::Refinery => "Refinery",
+            StationKind::Industrial => "Industrial",
+            StationKind::HighTech => "High-tech",
+            StationKind::Outpost => "Outpost",
+        }
+    }
+
+    pub fn produces(&self) -> &[TradeCategory] {
+        match self {
+            StationKind::Agricultural => &[TradeCategory::Food, TradeCategory::Luxury],
+            StationKind::Mining => &[TradeCategory::Minerals],
+            StationKind::Refinery => &[TradeCategory::Metals, TradeCategory::Fuel],
+            StationKind::Industrial => &[TradeCategory::Machinery],
+            StationKind::HighTech => &[TradeCategory::Electronics, TradeCategory::Medicine],
+            StationKind::Outpost => &[TradeCategory::Contraband],
+        }
+    }
+
+    pub fn consumes(&self) -> &[TradeCategory] {
+        match self {
+            StationKind::Agricultural => &[
+                TradeCategory::Machinery,
+                TradeCategory::Medicine,
+                TradeCategory::Fuel,
+            ],
+            StationKind::Mining => &[
+                TradeCategory::Food,
+                TradeCategory::Machinery,
+                TradeCategory::Fuel,
+            ],
+            StationKind::Refinery => &[TradeCategory::Minerals, TradeCategory::Food],
+            StationKind::Industrial => &[
+                TradeCategory::Metals,
+                TradeCategory::Electronics,
+                TradeCategory::Food,
+            ],
+            StationKind::HighTech => &[
+                TradeCategory::Metals,
+                TradeCategory::Luxury,
+                TradeCategory::Food,
+            ],
+            StationKind::Outpost => &[
+                TradeCategory::Food,
+                TradeCategory::Fuel,
+                TradeCategory::Medicine,
+            ],
+        }
+    }
+
+    /// Contraband is traded only where there is no law
+    pub fn trades(&self, category: TradeCategory) -> bool {
+        category != TradeCategory::Contraband || *self == StationKind::Outpost
+    }
+
+    fn equilibrium(&self, category: TradeCategory) -> u32 {
+        if self.produces().contains(&category) {
+            PRODUCED_STOCK
+        } else if self.consumes().contains(&category) {
+            CONSUMED_STOCK
+        } else {
+            NEUTRAL_STOCK
+        }
+    }
+}
+
+/// Stations don't travel, they stay on the same orbit forever
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Orbit {
+    /// Star system, chunk of the quadrant
+    pub system: Point,
+    /// Number of the orbit, counting from the star
+    pub number: u8,
+}
+
+/// Saves made before orbits put old stations on the first orbit of the quadrant corner
+fn default_orbit() -> Orbit {
+    Orbit {
+        system: Point::new(0, 0),
+        number: 1,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Station {
+    pub name: String,
+    pub kind: StationKind,
+    /// Id of `StationClass`, empty in old saves until `fix_class()`
+    #[serde(default)]
+    pub class: String,
+    pub quadrant: Point,
+    #[serde(default = "default_orbit")]
+    pub orbit: Orbit,
+    pub faction: Option<u8>,
+    pub market: Market,
+    /// Empty until somebody docks to the station
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structure: Option<Ship>,
+}
+
+impl Station {
+    /// Stations from old saves and removed mods get the first class suitable for the kind
+    pub fn fix_class(&mut self, data: &GameData) {
+        if data.stations.contains_key(self.class.as_str()) {
+            return;
+        }
+        if let Some(class) = suitable_classes(self.kind, data).first() {
+            self.class = class.to_string();
+        }
+    }
+}
+
+pub fn stations_count(size: usize) -> usize {
+    size / 4
+}
+
+fn station_name(rng: &mut StdRng, pack: Option<&NamesPack>, taken: &[Station]) -> String {
+    let mut name = format!("Station {}", taken.len() + 1);
+    if let Some(pack) = pack {
+        for _ in 0..100 {
+            name = format!(
+                "{} {}",
+                pack.last_names_male[rng.gen_range(0..pack.last_names_male.len())],
+                SUFFIXES[rng.gen_range(0..SUFFIXES.len())]
+            );
+            if taken.iter().all(|s| s.name != name) {
+                break;
+            }
+        }
+    }
+    name
+}
+
+/// Sorted ids, HashMap order is random
+fn suitable_classes(kind: StationKind, data: &GameData) -> Vec<&str> {
+    let mut classes: Vec<&str> = data
+        .stations
+        .values()
+        .filter(|c| c.suits(kind))
+        .map(|c| c.id.as_str())
+        .collect();
+    classes.sort();
+    classes
+}
+
+fn choose_class(rng: &mut StdRng, kind: StationKind, data: &GameData) -> String {
+    let classes = suitable_classes(kind, data);
+    if classes.is_empty() {
+        String::new()
+    } else {
+        classes[rng.gen_range(0..classes.len())].to_string()
+    }
+}
+
+fn make_market(rng: &mut StdRng, kind: StationKind, data: &GameData, tick: u128) -> Market {
+    let mut commodities: Vec<_> = data
+        .commodities
+        .values()
+        .filter(|c| kind.trades(c.category))
+        .collect();
+    // HashMap order is random, but markets should be the same for the same seed
+    commodities.sort_by(|a, b| a.id.cmp(&b.id));
+    let goods = commodities
+        .into_iter()
+        .map(|c| {
+            let equilibrium = kind.equilibrium(c.category);
+            Goods {
+                commodity: c.id.clone(),
+                stock: (equilibrium as f32 * rng.gen_range(0.5..1.5)) as u32,
+                equilibrium,
+            }
+        })
+        .collect();
+    Market::new(rng.gen(), goods, tick)
+}
+
+/// Stations at faction homes and in random populated quadrants,
+/// the same for every galaxy with this seed
+pub fn generate_stations(galaxy: &Galaxy, data: &GameData, tick: u128) -> Vec<Station> {
+    let size = galaxy.size();
+    let mut rng = StdRng::seed_from_u64(galaxy.meta.seed ^ SEED_SALT);
+    let stars: Vec<u32> = galaxy.quadrants.iter().map(|q| q.stars_count).collect();
+    let mut quadrants: Vec<Point> = galaxy.factions.iter().map(|f| f.home).collect();
+    if let Ok(weights) = WeightedIndex::new(&stars) {
+        for _ in 0..stations_count(size) * 10 {
+            if quadrants.len() >= galaxy.factions.len() + stations_count(size) {
+                break;
+            }
+            let i = weights.sample(&mut rng);
+            let point = Point::new((i / size) as i32, (i % size) as i32);
+            if !quadrants.contains(&point) {
+                quadrants.push(point);
+            }
+        }
+    }
+    let packs: Vec<&NamesPack> = data
+        .names
+        .iter()
+        .filter(|p| !p.last_names_male.is_empty())
+        .collect();
+    let kinds: Vec<StationKind> = all::<StationKind>()
+        .filter(|k| *k != StationKind::Outpost)
+        .collect();
+    let mut stations = Vec::with_capacity(quadrants.len());
+    for quadrant in quadrants {
+        let faction = galaxy.owner(quadrant);
+        let kind = if faction.is_none() && rng.gen_bool(0.5) {
+            StationKind::Outpost
+        } else {
+            kinds[rng.gen_range(0..kinds.len())]
+        };
+        let pack = faction
+            .and_then(|f| packs.iter().find(|p| p.id == f.names_pack))
+            .or_else(|| {
+                if packs.is_empty() {
+                    None
+                } else {
+                    Some(&packs[rng.gen_range(0..packs.len())])
+                }
+            })
+            .copied();
+        let station = Station {
+            name: station_name(&mut rng, pack, &stations),
+            kind,
+            class: choose_class(&mut rng, kind, data),
+            quadrant,
+            orbit: Orbit {
+                system: Point::new(
+                    rng.gen_range(0..QUADRANT_SIZE as i32),
+                    rng.gen_range(0..QUADRANT_SIZE as i32),
+                ),
+                number: rng.gen_range(1..=8),
+            },
+            faction: faction.map(|f| f.id),
+            market: make_market(&mut rng, kind, data, tick),
+            structure: None,
+        };
+        stations.push(station);
+    }
+    stations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_stations, Station, StationKind};
+    use crate::astro::galaxy::{Galaxy, GalaxyMeta};
+    use crate::astro::galaxy_class::GalaxyClass;
+    use crate::astro::galaxy_size::GalaxySize;
+    use crate::astro::spiral_arms::SpiralArms;
+    use crate::data::game_data::GameData;
+
+    #[test]
+    fn test_generate_stations() {
+        let data = GameData::load();
+        let mut galaxy = Galaxy::from(GalaxyMeta::new(
+            "Test".to_string(),
+            "42".to_string(),
+            GalaxySize::Small,
+            GalaxyClass::Spiral,
+            SpiralArms::default(),
+        ));
+        galaxy.generate_factions(&data.names);
+        let stations = generate_stations(&galaxy, &data, 0);
+        assert!(stations.len() > galaxy.factions.len());
+        for faction in galaxy.factions.iter() {
+            assert!(stations
+                .iter()
+                .any(|s| s.quadrant == faction.home && s.faction == Some(faction.id)));
+        }
+        for station in stations.iter() {
+            assert!(galaxy.quadrant(station.quadrant).unwrap().stars_count > 0);
+            assert!(data
+                .stations
+                .get(station.class.as_str())
+                .unwrap()
+                .suits(station.kind));
+            let contraband = station.market.goods("narcotics").is_some();
+            assert_eq!(contraband, station.kind == StationKind::Outpost);
+            if station.kind == StationKind::Outpost {
+                assert_eq!(station.faction, None);
+            }
+        }
+
+        let again = generate_stations(&galaxy, &data, 0);
+        assert!(stations
+            .iter()
+            .zip(again.iter())
+            .all(|(a, b)| a.name == b.name && a.quadrant == b.quadrant && a.kind == b.kind));
+    }
+
+    #[test]
+    fn test_station_without_class_and_orbit() {
+        let data = GameData::load();
+        let mut galaxy = Galaxy::from(GalaxyMeta::new(
+            "Test".to_string(),
+            "42".to_string(),
+            GalaxySize::Small,
+            GalaxyClass::Spiral,
+            SpiralArms::default(),
+        ));
+        galaxy.generate_factions(&data.names);
+        let station = generate_stations(&galaxy, &data, 0).remove(0);
+        let mut value = serde_json::to_value(&station).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("class");
+        object.remove("orbit");
+        let mut old: Station = serde_json::from_value(value).unwrap();
+        assert_eq!(old.class, "");
+        assert_eq!(old.orbit.number, 1);
+        old.fix_class(&data);
+        assert!(data
+            .stations
+            .get(old.class.as_str())
+            .unwrap()
+            .suits(old.kind));
+    }
+}
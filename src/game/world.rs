@@ -1,15 +1,63 @@
 #![allow(dead_code)]
 use crate::astro::galaxy::Galaxy;
+use crate::data::commodity::{Commodity, TradeCategory};
 use crate::data::game_data::GameData;
-use crate::game::avatar::Avatar;
-use crate::game::ship::Ship;
-use crate::savefile::{save, SaveFile};
-use chrono::{DateTime, NaiveDateTime, Utc};
-use geometry::Direction;
+use crate::data::ship_component::ComponentKind;
+use crate::data::ship_generator::generate_class;
+use crate::data::station_class::generate_station;
+use crate::game::action::ActionEvent;
+use crate::game::avatar::{Aboard, Avatar};
+use crate::game::calendar;
+use crate::game::market::TradeError;
+use crate::game::passage::Passage;
+use crate::game::ship::{DeckDirection, Ship};
+use crate::game::ship_parts::ShipPart;
+use crate::game::ship_tile::ShipTile;
+use crate::game::station::{generate_stations, Station};
+use crate::human::character::Character;
+use crate::human::skills::Skill;
+use crate::savefile::{save, slot_path, LoadError, SaveError, SaveFile};
+use chrono::{DateTime, Utc};
+use geometry::{Direction, Point};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-const START_TIME: i64 = 32_503_680_000;
+const START_CREDITS: u32 = 1000;
+const HIRE_COST: u32 = 500;
+/// Fuel the tanks get from one unit of bought fuel
+const FUEL_PER_UNIT: f32 = 20.0;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DockError {
+    NoStation,
+    AlreadyDocked,
+    NotDocked,
+    /// Ship can't leave without the captain
+    AvatarNotAboard,
+    /// Somebody from the crew is still on the station
+    CrewNotAboard,
+    UnknownStationClass,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HireError {
+    /// Crew is hired on stations only
+    NotDocked,
+    NotEnoughCredits,
+    /// No free tile aboard for a new crew member
+    NoRoom,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TravelError {
+    NoDestination,
+    /// Undock first
+    Docked,
+    AvatarNotAboard,
+    /// Engines are missing or destroyed
+    NoThrust,
+    NotEnoughFuel,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct World {
@@ -18,6 +66,24 @@ pub struct World {
     pub galaxy: Galaxy,
     pub avatar: Avatar,
     pub ship: Ship,
+    pub stations: Vec<Station>,
+    /// Crew members the player doesn't control right now
+    #[serde(default)]
+    pub crew: Vec<Avatar>,
+    /// Ids of mods the world was created with, see `GameData::load_with()`
+    #[serde(default)]
+    pub mods: Vec<String>,
+    /// Name of the save slot, see `SaveFile::slot_name()`
+    #[serde(default)]
+    pub slot: String,
+    /// Ticks between autosaves from `Settings.game`, 0 turns them off
+    #[serde(skip)]
+    autosave_interval: u128,
+    #[serde(skip)]
+    last_save: u128,
+    /// Outcomes of finished actions the scene hasn't shown yet
+    #[serde(skip)]
+    events: Vec<ActionEvent>,
 }
 
 impl World {
@@ -26,6 +92,7 @@ impl World {
         galaxy: Galaxy,
         avatar: Avatar,
         ship: Ship,
+        stations: Vec<Station>,
         current_tick: u128,
     ) -> Self {
         Self {
@@ -34,36 +101,494 @@ impl World {
             galaxy,
             avatar,
             ship,
+            stations,
+            crew: Vec::new(),
+            mods: Vec::new(),
+            slot: String::new(),
+            autosave_interval: 0,
+            last_save: current_tick,
+            events: Vec::new(),
         }
     }
 
-    pub fn create(savefile: &SaveFile, data: &GameData) -> Self {
-        if let Ok(galaxy) = savefile.load_galaxy() {
-            let ship = Ship::generate("Dugong", data.ships.get("dugong").unwrap());
-            World::new(
-                savefile.path.clone(),
-                galaxy,
-                Avatar::new(savefile.character.clone().unwrap(), ship.find_start_point()),
-                ship,
-                savefile.current_tick,
-            )
-        } else {
-            panic!("Can't load galaxy: {:?}", savefile.path)
+    pub fn with_crew(mut self, crew: Vec<Avatar>) -> Self {
+        self.crew = crew;
+        self
+    }
+
+    pub fn with_mods(mut self, mods: Vec<String>) -> Self {
+        self.mods = mods;
+        self
+    }
+
+    pub fn with_slot(mut self, slot: String) -> Self {
+        self.slot = slot;
+        self
+    }
+
+    pub fn with_autosave(mut self, interval: u32) -> Self {
+        self.autosave_interval = interval as u128;
+        self
+    }
+
+    fn autosave_due(&self) -> bool {
+        self.autosave_interval > 0 && self.current_tick >= self.last_save + self.autosave_interval
+    }
+
+    pub fn create(savefile: &SaveFile, data: &GameData) -> Result<Self, LoadError> {
+        let character = savefile.character.clone().ok_or(LoadError::NoCharacter)?;
+        let mut galaxy = savefile.load_galaxy()?;
+        galaxy.generate_factions(&data.names);
+        let stations = generate_stations(&galaxy, data, savefile.current_tick);
+        let background = character
+            .background
+            .as_ref()
+            .and_then(|id| data.backgrounds.get(id.as_str()));
+        // characters without a background start on a ship built for this galaxy
+        let generated;
+        let class = match background.and_then(|b| data.ships.get(b.ship.as_str())) {
+            Some(class) => class,
+            None => {
+                generated = generate_class(galaxy.meta.seed);
+                &generated
+            }
+        };
+        let mut ship = Ship::generate(class.name.as_str(), class, data);
+        ship.quadrant = galaxy.start_quadrant();
+        ship.credits = START_CREDITS;
+        if let Some(background) = background {
+            let mut items: Vec<(&String, &u32)> = background.items.iter().collect();
+            items.sort();
+            for (id, amount) in items {
+                let amount = (*amount).min(ship.cargo_space());
+                if amount > 0 {
+                    *ship.cargo.entry(id.clone()).or_default() += amount;
+                }
+            }
+        }
+        if let Some(quadrant) = galaxy.quadrant_mut(ship.quadrant) {
+            quadrant.visited = true;
+        }
+        let mut world = World::new(
+            savefile.path.clone(),
+            galaxy,
+            Avatar::new(character, ship.find_start_point()),
+            ship,
+            stations,
+            savefile.current_tick,
+        )
+        .with_mods(data.mod_ids())
+        .with_slot(savefile.slot.clone());
+        for character in savefile.crew.iter() {
+            if let Some((deck, pos)) = world.free_spot() {
+                let mut member = Avatar::new(character.clone(), pos);
+                member.deck = deck;
+                world.crew.push(member);
+            }
         }
+        Ok(world)
+    }
+
+    pub fn save(&self) -> Result<(), SaveError> {
+        save(self)
     }
 
-    pub fn save(&self) {
+    /// Moves the world to another save slot of its galaxy, the old slot stays as it was
+    pub fn save_as(&mut self, slot: &str) -> Result<(), SaveError> {
+        self.path = slot_path(&self.galaxy.meta.name, slot);
+        self.slot = slot.trim().to_string();
+        self.last_save = self.current_tick;
         save(self)
-            .map_err(|e| panic!("Error on saving world to {:?}: {:?}", self.path, e))
-            .ok();
     }
 
     pub fn time(&self) -> DateTime<Utc> {
-        DateTime::from_naive_utc_and_offset(
-            NaiveDateTime::from_timestamp_opt(START_TIME + self.current_tick as i64 / 60, 0)
-                .unwrap(),
-            Utc,
-        )
+        calendar::date(self.timestamp())
+    }
+
+    /// Seconds of the in-game calendar, characters' birth dates use it
+    pub fn timestamp(&self) -> i64 {
+        calendar::timestamp(self.current_tick)
+    }
+
+    pub fn push_event(&mut self, event: ActionEvent) {
+        self.events.push(event);
+    }
+
+    /// Events of the actions finished since the last call
+    pub fn take_events(&mut self) -> Vec<ActionEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn set_destination(&mut self, destination: Option<Point>) {
+        self.ship.destination = destination.filter(|d| *d != self.ship.quadrant);
+    }
+
+    /// Flies to the destination, burning fuel and skipping time; returns ticks spent
+    pub fn travel(&mut self) -> Result<u128, TravelError> {
+        let destination = self.ship.destination.ok_or(TravelError::NoDestination)?;
+        if self.ship.docked.is_some() {
+            return Err(TravelError::Docked);
+        }
+        if self.avatar.aboard != Aboard::Ship {
+            return Err(TravelError::AvatarNotAboard);
+        }
+        let stats = self.ship.stats();
+        let distance = self.ship.distance(destination);
+        let ticks = stats.travel_time(distance).ok_or(TravelError::NoThrust)?;
+        let fuel = stats.fuel_needed(distance);
+        if fuel > self.ship.fuel {
+            return Err(TravelError::NotEnoughFuel);
+        }
+        let now = self.timestamp();
+        let pilot = self.pilot_mut();
+        let modifier = pilot.character.length_modifier(Skill::Piloting, now);
+        let ticks = (ticks as f64 * modifier as f64) as u128;
+        pilot
+            .character
+            .train(Skill::Piloting, distance.ceil() as u32, now);
+        self.ship.fuel -= fuel;
+        self.ship.quadrant = destination;
+        self.ship.destination = None;
+        if let Some(quadrant) = self.galaxy.quadrant_mut(destination) {
+            quadrant.visited = true;
+        }
+        self.current_tick += ticks;
+        Ok(ticks)
+    }
+
+    /// The best pilot aboard the ship flies it
+    fn pilot_mut(&mut self) -> &mut Avatar {
+        let now = self.timestamp();
+        let skill = |a: &Avatar| a.character.length_modifier(Skill::Piloting, now);
+        let best = self
+            .crew
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.aboard == Aboard::Ship)
+            .min_by(|(_, a), (_, b)| skill(a).total_cmp(&skill(b)))
+            .map(|(i, _)| i);
+        match best {
+            Some(i) if skill(&self.crew[i]) < skill(&self.avatar) => &mut self.crew[i],
+            _ => &mut self.avatar,
+        }
+    }
+
+    pub fn station_at(&self, quadrant: Point) -> Option<usize> {
+        self.stations.iter().position(|s| s.quadrant == quadrant)
+    }
+
+    /// Units of fuel needed to fill the tanks, the last one may be burnt partly
+    fn fuel_to_fill(&self) -> u32 {
+        let missing = self.ship.stats().fuel_capacity - self.ship.fuel;
+        (missing / FUEL_PER_UNIT).ceil().max(0.0) as u32
+    }
+
+    /// Buys commodity on the station, returns credits spent.
+    /// Fuel fills the tanks first and only the rest goes to the hold.
+    pub fn buy(
+        &mut self,
+        station: usize,
+        commodity: &Commodity,
+        amount: u32,
+    ) -> Result<u32, TradeError> {
+        let to_tanks = if commodity.category == TradeCategory::Fuel {
+            amount.min(self.fuel_to_fill())
+        } else {
+            0
+        };
+        let to_hold = amount - to_tanks;
+        if to_hold > self.ship.cargo_space() {
+            return Err(TradeError::NoCargoSpace);
+        }
+        let market = &mut self.stations[station].market;
+        market.update(self.current_tick);
+        let price = market.buy_cost(commodity, amount, self.current_tick)?;
+        if price > self.ship.credits {
+            return Err(TradeError::NotEnoughCredits);
+        }
+        market.take(commodity.id.as_str(), amount)?;
+        self.ship.credits -= price;
+        if to_tanks > 0 {
+            let capacity = self.ship.stats().fuel_capacity;
+            self.ship.fuel = (self.ship.fuel + to_tanks as f32 * FUEL_PER_UNIT).min(capacity);
+        }
+        if to_hold > 0 {
+            *self.ship.cargo.entry(commodity.id.clone()).or_insert(0) += to_hold;
+        }
+        Ok(price)
+    }
+
+    /// Sells commodity from the hold to the station, returns credits earned
+    pub fn sell(
+        &mut self,
+        station: usize,
+        commodity: &Commodity,
+        amount: u32,
+    ) -> Result<u32, TradeError> {
+        let in_hold = self.ship.cargo.get(&commodity.id).copied().unwrap_or(0);
+        if in_hold < amount {
+            return Err(TradeError::NotEnoughCargo);
+        }
+        let market = &mut self.stations[station].market;
+        market.update(self.current_tick);
+        let price = market.sell_revenue(commodity, amount, self.current_tick)?;
+        let credits = self
+            .ship
+            .credits
+            .checked_add(price)
+            .ok_or(TradeError::TooExpensive)?;
+        market.put(commodity.id.as_str(), amount)?;
+        self.ship.credits = credits;
+        if in_hold == amount {
+            self.ship.cargo.remove(&commodity.id);
+        } else {
+            self.ship
+                .cargo
+                .insert(commodity.id.clone(), in_hold - amount);
+        }
+        Ok(price)
+    }
+
+    /// Ship or station the avatar walks on
+    pub fn structure(&self) -> &Ship {
+        match self.avatar.aboard {
+            Aboard::Ship => &self.ship,
+            Aboard::Station(i) => self.stations[i].structure.as_ref().unwrap(),
+        }
+    }
+
+    pub fn structure_mut(&mut self) -> &mut Ship {
+        match self.avatar.aboard {
+            Aboard::Ship => &mut self.ship,
+            Aboard::Station(i) => self.stations[i].structure.as_mut().unwrap(),
+        }
+    }
+
+    /// Docks to the station in the current quadrant and unlocks airlocks
+    pub fn dock(&mut self, data: &GameData) -> Result<usize, DockError> {
+        if self.ship.docked.is_some() {
+            return Err(DockError::AlreadyDocked);
+        }
+        let index = self
+            .station_at(self.ship.quadrant)
+            .ok_or(DockError::NoStation)?;
+        let station = &mut self.stations[index];
+        if station.structure.is_none() {
+            station.fix_class(data);
+            let scheme = data
+                .stations
+                .get(station.class.as_str())
+                .ok_or(DockError::UnknownStationClass)?;
+            station.structure = Some(generate_station(
+                station.name.clone(),
+                scheme,
+                station.quadrant,
+                data,
+            ));
+        }
+        if let Some(structure) = station.structure.as_mut() {
+            structure.lock_airlocks(false);
+        }
+        self.ship.lock_airlocks(false);
+        self.ship.docked = Some(index);
+        Ok(index)
+    }
+
+    pub fn undock(&mut self) -> Result<(), DockError> {
+        let index = self.ship.docked.ok_or(DockError::NotDocked)?;
+        if self.avatar.aboard != Aboard::Ship {
+            return Err(DockError::AvatarNotAboard);
+        }
+        if self.crew.iter().any(|m| m.aboard != Aboard::Ship) {
+            return Err(DockError::CrewNotAboard);
+        }
+        if let Some(structure) = self.stations[index].structure.as_mut() {
+            structure.lock_airlocks(true);
+        }
+        self.ship.lock_airlocks(true);
+        self.ship.docked = None;
+        Ok(())
+    }
+
+    /// Takes control of the crew member, previous avatar goes to the end of the roster
+    pub fn switch_avatar(&mut self, index: usize) {
+        if index < self.crew.len() {
+            let member = self.crew.remove(index);
+            let previous = std::mem::replace(&mut self.avatar, member);
+            self.crew.push(previous);
+        }
+    }
+
+    pub fn is_occupied(&self, aboard: Aboard, deck: i32, pos: Point) -> bool {
+        std::iter::once(&self.avatar)
+            .chain(self.crew.iter())
+            .any(|m| m.aboard == aboard && m.deck == deck && m.pos == pos)
+    }
+
+    /// Somebody stands on the tile in direction from the avatar
+    pub fn is_occupied_at(&self, dir: Direction) -> bool {
+        !dir.is_here()
+            && self.is_occupied(self.avatar.aboard, self.avatar.deck, self.avatar.pos + dir)
+    }
+
+    /// Ships from saves made before engines can't fly, they are rebuilt by their class
+    /// with full tanks and everybody aboard takes free spots of the new layout
+    pub fn refit_legacy_ship(&mut self, data: &GameData) {
+        let has_engines = self
+            .ship
+            .tiles
+            .iter()
+            .flat_map(|t| t.parts.iter())
+            .any(|p| match p {
+                ShipPart::Component(component) => component.kind() != ComponentKind::FuelTank,
+                _ => false,
+            });
+        if has_engines {
+            return;
+        }
+        let generated;
+        let class = match data.ships.values().find(|c| c.name == self.ship.class_name) {
+            Some(class) => class,
+            None => {
+                generated = generate_class(self.galaxy.meta.seed);
+                &generated
+            }
+        };
+        let refitted = Ship::generate(self.ship.name.clone(), class, data);
+        self.ship.tiles = refitted.tiles;
+        self.ship.bounds = refitted.bounds;
+        self.ship.decks = refitted.decks;
+        self.ship.cargo_capacity = refitted.cargo_capacity;
+        self.ship.fuel = refitted.fuel;
+        if self.ship.docked.is_some() {
+            self.ship.lock_airlocks(false);
+        }
+
+        let start = (0, self.ship.find_start_point());
+        let aboard: Vec<usize> = (0..self.crew.len())
+            .filter(|i| self.crew[*i].aboard == Aboard::Ship)
+            .collect();
+        for i in aboard.iter() {
+            self.crew[*i].deck = -1;
+        }
+        if self.avatar.aboard == Aboard::Ship {
+            self.avatar.deck = -1;
+            let (deck, pos) = self.free_spot().unwrap_or(start);
+            self.avatar.deck = deck;
+            self.avatar.pos = pos;
+            self.avatar.action = None;
+        }
+        for i in aboard {
+            let (deck, pos) = self.free_spot().unwrap_or(start);
+            let member = &mut self.crew[i];
+            member.deck = deck;
+            member.pos = pos;
+            member.action = None;
+        }
+    }
+
+    /// Free tile inside the ship nearest to its start point
+    fn free_spot(&self) -> Option<(i32, Point)> {
+        let start = self.ship.find_start_point();
+        let size = (self.ship.bounds.0 * self.ship.bounds.1) as usize;
+        self.ship
+            .tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| {
+                let deck = (i / size) as i32;
+                (tile, deck, Point::from_index(i % size, self.ship.bounds.0))
+            })
+            .filter(|(tile, deck, pos)| {
+                !tile.is_void()
+                    && !tile.is_outside()
+                    && matches!(tile.passage(), Passage::Passable(..))
+                    && !self.is_occupied(Aboard::Ship, *deck, *pos)
+            })
+            .min_by_key(|(_, deck, pos)| (*deck, pos.square_distance_to(start)))
+            .map(|(_, deck, pos)| (deck, pos))
+    }
+
+    /// New crew member comes aboard for `HIRE_COST` credits
+    pub fn hire(&mut self, character: Character) -> Result<(), HireError> {
+        if self.ship.docked.is_none() {
+            return Err(HireError::NotDocked);
+        }
+        if self.ship.credits < HIRE_COST {
+            return Err(HireError::NotEnoughCredits);
+        }
+        let (deck, pos) = self.free_spot().ok_or(HireError::NoRoom)?;
+        self.ship.credits -= HIRE_COST;
+        let mut member = Avatar::new(character, pos);
+        member.deck = deck;
+        self.crew.push(member);
+        Ok(())
+    }
+
+    /// Airlock of the docked structure on the other side of the airlock avatar stands in
+    fn boarding_target(&self, dir: Direction) -> Option<(Aboard, i32, Point)> {
+        let station = self.ship.docked?;
+        let structure = self.structure();
+        let deck = self.avatar.deck;
+        let outside = match structure.get_tile(deck, self.avatar.pos + dir) {
+            Some(t) => {
+                t.is_void() || (t.is_outside() && matches!(t.passage(), Passage::Passable(..)))
+            }
+            None => true,
+        };
+        if !outside || !structure.get_tile(deck, self.avatar.pos)?.is_airlock() {
+            return None;
+        }
+        let (aboard, other) = match self.avatar.aboard {
+            Aboard::Ship => (
+                Aboard::Station(station),
+                self.stations[station].structure.as_ref()?,
+            ),
+            Aboard::Station(_) => (Aboard::Ship, &self.ship),
+        };
+        // entering from the opposite side
+        let (deck, pos) = other
+            .airlocks()
+            .into_iter()
+            .min_by_key(|(_, p)| p.x * dir.dx() + p.y * dir.dy())?;
+        Some((aboard, deck, pos))
+    }
+
+    pub fn can_board(&self, dir: Direction) -> bool {
+        self.boarding_target(dir).is_some()
+    }
+
+    /// Moves avatar through the airlock to the docked ship or station
+    pub fn board(&mut self, dir: Direction) {
+        if let Some((aboard, deck, pos)) = self.boarding_target(dir) {
+            self.avatar.aboard = aboard;
+            self.avatar.deck = deck;
+            self.avatar.pos = pos;
+            self.avatar.vision = dir;
+        }
+    }
+
+    /// Tile in direction from the avatar on the current deck
+    pub fn tile_at(&self, dir: Direction) -> Option<&ShipTile> {
+        self.structure()
+            .get_tile(self.avatar.deck, self.avatar.pos + dir)
+    }
+
+    pub fn tile_at_mut(&mut self, dir: Direction) -> Option<&mut ShipTile> {
+        let (deck, pos) = (self.avatar.deck, self.avatar.pos + dir);
+        self.structure_mut().get_tile_mut(deck, pos)
+    }
+
+    /// Deck reached by the ladder or lift avatar stands on
+    pub fn deck_target(&self, dir: DeckDirection) -> Option<i32> {
+        self.structure()
+            .deck_target(self.avatar.deck, self.avatar.pos, dir)
+    }
+
+    pub fn change_deck(&mut self, dir: DeckDirection) {
+        if let Some(deck) = self.deck_target(dir) {
+            self.avatar.deck = deck;
+        }
     }
 
     pub fn move_avatar(&mut self, dir: Direction) {
@@ -79,9 +604,22 @@ impl World {
                 self.avatar.action = None;
             }
         }
+        for i in 0..self.crew.len() {
+            if matches!(&self.crew[i].action, Some(a) if a.finish <= self.current_tick) {
+                // actions always act on the avatar, so crew member takes its place for a moment
+                std::mem::swap(&mut self.avatar, &mut self.crew[i]);
+                if let Some(action) = self.avatar.action.clone() {
+                    action.act(self);
+                }
+                self.avatar.action = None;
+                std::mem::swap(&mut self.avatar, &mut self.crew[i]);
+            }
+        }
     }
 
-    pub fn tick(&mut self) {
+    /// True if the world was autosaved. Fails only if the autosave can't be written,
+    /// the world goes on anyway and the next autosave tries again.
+    pub fn tick(&mut self) -> Result<bool, SaveError> {
         self.act();
         const SPEND_LIMIT: u8 = 100;
         let mut spend = 0;
@@ -90,5 +628,420 @@ impl World {
             spend += 1;
             self.act();
         }
+        if self.autosave_due() {
+            // failed autosave waits for the next interval instead of every tick
+            self.last_save = self.current_tick;
+            self.save()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DockError, HireError, TravelError, World, FUEL_PER_UNIT, HIRE_COST};
+    use crate::astro::galaxy::{Galaxy, GalaxyMeta};
+    use crate::astro::galaxy_class::GalaxyClass;
+    use crate::astro::galaxy_size::GalaxySize;
+    use crate::astro::spiral_arms::SpiralArms;
+    use crate::data::game_data::GameData;
+    use crate::game::action::{Action, ActionEvent, ActionType};
+    use crate::game::avatar::{Aboard, Avatar};
+    use crate::game::calendar::{timestamp, years_between};
+    use crate::game::market::{TradeError, TICKS_PER_DAY};
+    use crate::game::passage::Passage;
+    use crate::game::ship::{DeckDirection, Ship};
+    use crate::game::ship_parts::door::Door;
+    use crate::game::ship_parts::floor::Floor;
+    use crate::game::ship_parts::{ShipPart, ShipPartAction};
+    use crate::game::station::generate_stations;
+    use crate::human::attributes::Attributes;
+    use crate::human::character::Character;
+    use crate::human::skills::{Skill, Skills};
+    use geometry::{Direction, Point};
+    use rand::thread_rng;
+    use std::path::PathBuf;
+
+    fn world(data: &GameData) -> World {
+        let mut galaxy = Galaxy::from(GalaxyMeta::new(
+            "Test".to_string(),
+            "42".to_string(),
+            GalaxySize::Small,
+            GalaxyClass::Spiral,
+            SpiralArms::default(),
+        ));
+        galaxy.generate_factions(&data.names);
+        let stations = generate_stations(&galaxy, data, 0);
+        let mut ship = Ship::generate("Dugong", data.ships.get("dugong").unwrap(), data);
+        ship.quadrant = stations[0].quadrant;
+        let character = Character::random(&mut thread_rng(), data, timestamp(0));
+        let avatar = Avatar::new(character, ship.find_start_point());
+        World::new(PathBuf::new(), galaxy, avatar, ship, stations, 0)
+    }
+
+    #[test]
+    fn test_trade() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        let water = data.commodities.get("water").unwrap();
+        assert_eq!(world.buy(0, water, 1), Err(TradeError::NotEnoughCredits));
+        world.ship.credits = 1000;
+        let spent = world.buy(0, water, 10).unwrap();
+        assert_eq!(world.ship.credits, 1000 - spent);
+        assert_eq!(world.ship.cargo.get("water"), Some(&10));
+        assert_eq!(
+            world.buy(0, water, world.ship.cargo_capacity),
+            Err(TradeError::NoCargoSpace)
+        );
+        let earned = world.sell(0, water, 10).unwrap();
+        assert!(earned < spent);
+        assert!(world.ship.cargo.is_empty());
+        assert_eq!(world.sell(0, water, 1), Err(TradeError::NotEnoughCargo));
+    }
+
+    #[test]
+    fn test_dock_and_board() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        assert_eq!(world.undock(), Err(DockError::NotDocked));
+        assert_eq!(world.dock(&data), Ok(0));
+        assert_eq!(world.dock(&data), Err(DockError::AlreadyDocked));
+        assert!(world.stations[0].structure.is_some());
+
+        let airlock = Point::new(3, 10);
+        world.avatar.pos = airlock + Direction::East;
+        world
+            .structure_mut()
+            .get_tile_mut(0, airlock)
+            .unwrap()
+            .act(ShipPartAction::Open);
+        world.move_avatar(Direction::West);
+        assert!(!world.can_board(Direction::North));
+        assert!(world.can_board(Direction::West));
+        world.board(Direction::West);
+        assert_eq!(world.avatar.aboard, Aboard::Station(0));
+        assert!(world.tile_at(Direction::Here).unwrap().is_airlock());
+        assert_eq!(world.undock(), Err(DockError::AvatarNotAboard));
+
+        assert!(world.can_board(Direction::East));
+        world.board(Direction::East);
+        assert_eq!(world.avatar.aboard, Aboard::Ship);
+        assert_eq!(world.avatar.pos, airlock);
+        assert_eq!(world.undock(), Ok(()));
+        assert!(!world.can_board(Direction::West));
+    }
+
+    #[test]
+    fn test_crew() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        let captain = world.avatar.character.name.clone();
+        let mate = Character::random(&mut thread_rng(), &data, world.timestamp());
+        assert_eq!(world.hire(mate.clone()), Err(HireError::NotDocked));
+        world.dock(&data).unwrap();
+        world.ship.credits = HIRE_COST;
+        assert_eq!(world.hire(mate.clone()), Ok(()));
+        assert_eq!(world.ship.credits, 0);
+        assert_eq!(world.hire(mate), Err(HireError::NotEnoughCredits));
+        assert_eq!(world.crew.len(), 1);
+        let spot = world.crew[0].pos;
+        assert_ne!(spot, world.avatar.pos);
+
+        // crew member keeps walking after the player switches to the captain
+        world.switch_avatar(0);
+        assert_ne!(world.avatar.character.name, captain);
+        let dir = [Direction::North, Direction::South, Direction::West]
+            .into_iter()
+            .find(|d| Action::new(ActionType::Walking(*d), &world).is_some())
+            .unwrap();
+        world.avatar.action = Action::new(ActionType::Walking(dir), &world);
+        world.switch_avatar(0);
+        assert_eq!(world.avatar.character.name, captain);
+        world.avatar.action = Action::new(ActionType::SkippingTime, &world);
+        while world.crew[0].action.is_some() {
+            world.tick().unwrap();
+            world.avatar.action = Action::new(ActionType::SkippingTime, &world);
+        }
+        assert_eq!(world.crew[0].pos, spot + dir);
+
+        // nobody can walk into the other one
+        world.avatar.pos = world.crew[0].pos + Direction::East;
+        assert!(world.is_occupied_at(Direction::West));
+        assert!(Action::new(ActionType::Walking(Direction::West), &world).is_none());
+
+        world.crew[0].aboard = Aboard::Station(0);
+        assert_eq!(world.undock(), Err(DockError::CrewNotAboard));
+        world.crew[0].aboard = Aboard::Ship;
+        assert_eq!(world.undock(), Ok(()));
+    }
+
+    #[test]
+    fn test_autosave_due() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        world.current_tick = 1000;
+        assert!(!world.autosave_due());
+        let mut world = world.with_autosave(600);
+        assert!(world.autosave_due());
+        world.last_save = 500;
+        assert!(!world.autosave_due());
+        world.current_tick = 1100;
+        assert!(world.autosave_due());
+    }
+
+    #[test]
+    fn test_failed_autosave() {
+        let data = GameData::load();
+        // empty path can't be written
+        let mut world = world(&data).with_autosave(600);
+        world.current_tick = 1000;
+        assert!(world.tick().is_err());
+        assert_eq!(world.current_tick, 1000);
+        assert_eq!(world.tick().ok(), Some(false));
+        world.current_tick = 1600;
+        assert!(world.tick().is_err());
+    }
+
+    #[test]
+    fn test_travel() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        let start = world.ship.quadrant;
+        let target = start + Point::new(3, 4);
+        assert_eq!(world.travel(), Err(TravelError::NoDestination));
+        world.set_destination(Some(target));
+        world.dock(&data).unwrap();
+        assert_eq!(world.travel(), Err(TravelError::Docked));
+        world.undock().unwrap();
+
+        let full = world.ship.fuel;
+        let stats = world.ship.stats();
+        let ticks = world.travel().unwrap();
+        assert_eq!(world.current_tick, ticks);
+        assert_eq!(world.ship.quadrant, target);
+        assert!(world.ship.destination.is_none());
+        assert!((full - world.ship.fuel - stats.fuel_needed(5.0)).abs() < 0.01);
+
+        // loaded ship is slower and burns more
+        world
+            .ship
+            .cargo
+            .insert("ore".to_string(), world.ship.cargo_capacity);
+        let loaded = world.ship.stats();
+        assert!(loaded.travel_time(5.0) > stats.travel_time(5.0));
+        assert!(loaded.range(loaded.fuel_capacity) < stats.range(stats.fuel_capacity));
+
+        world.set_destination(Some(start));
+        world.ship.fuel = 1.0;
+        assert_eq!(world.travel(), Err(TravelError::NotEnoughFuel));
+        assert_eq!(world.ship.quadrant, target);
+    }
+
+    #[test]
+    fn test_long_voyage() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        let mate = Character::random(&mut thread_rng(), &data, world.timestamp());
+        world.crew.push(Avatar::new(mate, world.ship.find_start_point()));
+        let start = world.ship.quadrant;
+        let departure = world.timestamp();
+        let ages: Vec<u32> = std::iter::once(&world.avatar)
+            .chain(world.crew.iter())
+            .map(|m| m.character.age(departure))
+            .collect();
+
+        let one_quadrant = world.ship.stats().travel_time(1.0).unwrap();
+        assert!(one_quadrant > TICKS_PER_DAY * 7);
+        // there and back again, refuelling at both ends
+        for i in 0..6 {
+            let target = if i % 2 == 0 {
+                start + Point::new(30, 0)
+            } else {
+                start
+            };
+            world.ship.fuel = world.ship.stats().fuel_capacity;
+            world.set_destination(Some(target));
+            world.travel().unwrap();
+        }
+        let now = world.timestamp();
+        assert!(years_between(departure, now) >= 1);
+        for (member, age) in std::iter::once(&world.avatar)
+            .chain(world.crew.iter())
+            .zip(ages)
+        {
+            assert!(member.character.age(now) > age);
+        }
+    }
+
+    #[test]
+    fn test_refuel() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        let start = world.ship.quadrant;
+        let capacity = world.ship.stats().fuel_capacity;
+        world.set_destination(Some(start + Point::new(2, 0)));
+        world.travel().unwrap();
+        world.set_destination(Some(start));
+        world.travel().unwrap();
+        assert!(world.ship.fuel < capacity);
+        // tanks are almost dry after a long voyage
+        world.ship.fuel = 1.0;
+        world.set_destination(Some(start + Point::new(2, 0)));
+        assert_eq!(world.travel(), Err(TravelError::NotEnoughFuel));
+
+        world.dock(&data).unwrap();
+        world.ship.credits = 100_000;
+        let hydrogen = data.commodities.get("hydrogen").unwrap();
+        world.buy(0, hydrogen, 2).unwrap();
+        assert_eq!(world.ship.fuel, 1.0 + 2.0 * FUEL_PER_UNIT);
+        assert!(world.ship.cargo.is_empty());
+        // what doesn't fit into the tanks goes to the hold
+        world.ship.fuel = capacity - FUEL_PER_UNIT / 2.0;
+        world.buy(0, hydrogen, 3).unwrap();
+        assert_eq!(world.ship.fuel, capacity);
+        assert_eq!(world.ship.cargo.get("hydrogen"), Some(&2));
+        world.sell(0, hydrogen, 2).unwrap();
+
+        world.undock().unwrap();
+        assert!(world.travel().is_ok());
+        assert_eq!(world.ship.quadrant, start + Point::new(2, 0));
+    }
+
+    #[test]
+    fn test_refit_legacy_ship() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        for tile in world.ship.tiles.iter_mut() {
+            tile.parts.retain(|p| !matches!(p, ShipPart::Component(..)));
+        }
+        world.ship.fuel = 0.0;
+        world.set_destination(Some(world.ship.quadrant + Point::new(1, 1)));
+        assert_eq!(world.travel(), Err(TravelError::NoThrust));
+
+        world.ship.credits = HIRE_COST;
+        world.dock(&data).unwrap();
+        world
+            .hire(Character::random(&mut thread_rng(), &data, timestamp(0)))
+            .unwrap();
+        world.refit_legacy_ship(&data);
+        assert_eq!(world.ship.fuel, world.ship.stats().fuel_capacity);
+        assert!(world.ship.fuel > 0.0);
+        for member in std::iter::once(&world.avatar).chain(world.crew.iter()) {
+            let tile = world.ship.get_tile(member.deck, member.pos).unwrap();
+            assert!(matches!(tile.passage(), Passage::Passable(..)));
+        }
+        assert_eq!(world.crew.len(), 1);
+        world.undock().unwrap();
+        assert!(world.travel().is_ok());
+
+        // ships with engines stay as they are
+        let fuel = world.ship.fuel;
+        world.refit_legacy_ship(&data);
+        assert_eq!(world.ship.fuel, fuel);
+    }
+
+    #[test]
+    fn test_change_deck() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        world.ship = Ship::generate("Manatee", data.ships.get("manatee").unwrap(), &data);
+        assert_eq!(world.ship.decks, 2);
+        let ladder = Point::new(5, 3);
+        let lift = Point::new(5, 5);
+
+        world.avatar.pos = Point::new(4, 4);
+        assert_eq!(world.deck_target(DeckDirection::Up), None);
+        world.avatar.pos = ladder;
+        assert_eq!(world.deck_target(DeckDirection::Down), None);
+        assert_eq!(world.deck_target(DeckDirection::Up), Some(1));
+        let climbing = Action::new(ActionType::ChangingDeck(DeckDirection::Up), &world).unwrap();
+        world.avatar.pos = lift;
+        let riding = Action::new(ActionType::ChangingDeck(DeckDirection::Up), &world).unwrap();
+        assert!(riding.finish < climbing.finish);
+
+        world.avatar.action = Some(riding);
+        world.tick().unwrap();
+        assert_eq!(world.avatar.deck, 1);
+        assert_eq!(world.avatar.pos, lift);
+        assert_eq!(world.deck_target(DeckDirection::Up), None);
+        // upper deck is smaller
+        assert!(world.tile_at(Direction::Here).is_some());
+        world.avatar.pos = Point::new(5, 7);
+        assert!(world.tile_at(Direction::Here).unwrap().is_void());
+        world.avatar.pos = ladder;
+        world.change_deck(DeckDirection::Down);
+        assert_eq!(world.avatar.deck, 0);
+    }
+
+    #[test]
+    fn test_jammed_door() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        let tile = world.tile_at_mut(Direction::East).unwrap();
+        tile.parts = vec![
+            Floor::new().into(),
+            Door::default().with_jammed(true).into(),
+        ];
+        let open = ActionType::ActivatingPart(Direction::East, ShipPartAction::Open);
+        assert_eq!(open.skill(&world), Some((Skill::Engineering, 3)));
+
+        world.avatar.character = world
+            .avatar
+            .character
+            .clone()
+            .with_abilities(Attributes::default(), Skills::default());
+        let unskilled = open.length(&world).unwrap();
+        let mut skills = Skills::default();
+        skills.set_level(Skill::Engineering, 10);
+        world.avatar.character = world
+            .avatar
+            .character
+            .clone()
+            .with_abilities(Attributes::default(), skills);
+        let skilled = open.length(&world).unwrap();
+        assert!(skilled < unskilled);
+
+        // a master engineer never fails
+        let action = Action::new(open, &world).unwrap();
+        action.act(&mut world);
+        let tile = world.tile_at(Direction::East).unwrap();
+        assert!(matches!(tile.passage(), Passage::Passable(..)));
+    }
+
+    #[test]
+    fn test_action_events() {
+        let data = GameData::load();
+        let mut world = world(&data);
+        world.avatar.character = world
+            .avatar
+            .character
+            .clone()
+            .with_abilities(Attributes::default(), Skills::default());
+        let name = world.avatar.character.name.clone();
+        let open = ActionType::ActivatingPart(Direction::East, ShipPartAction::Open);
+        let mut level_ups = 0;
+        for _ in 0..100 {
+            world.tile_at_mut(Direction::East).unwrap().parts = vec![
+                Floor::new().into(),
+                Door::default().with_jammed(true).into(),
+            ];
+            world.avatar.action = Action::new(open.clone(), &world);
+            world.current_tick = world.avatar.action.as_ref().unwrap().finish;
+            world.tick().unwrap();
+            let events = world.take_events();
+            let failed = ActionEvent::Failed(name.clone(), Skill::Engineering);
+            let opened = matches!(
+                world.tile_at(Direction::East).unwrap().passage(),
+                Passage::Passable(..)
+            );
+            assert_eq!(events.contains(&failed), !opened);
+            level_ups += events
+                .iter()
+                .filter(|e| matches!(e, ActionEvent::LevelUp(_, Skill::Engineering)))
+                .count();
+        }
+        assert!(level_ups > 0);
+        assert!(world.take_events().is_empty());
     }
 }
@@ -0,0 +1,91 @@
+use enum_iterator::{all, Sequence};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+pub const MIN_ATTRIBUTE: u8 = 1;
+pub const MAX_ATTRIBUTE: u8 = 10;
+/// Every attribute of a new character before the points-buy
+pub const AVERAGE_ATTRIBUTE: u8 = 5;
+/// Points above the average spent on the points-buy step,
+/// lowering an attribute below the average gives points back
+pub const ATTRIBUTE_POINTS: i32 = 6;
+
+#[derive(Serialize, Deserialize, Sequence, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Attribute {
+    Strength,
+    Dexterity,
+    Intelligence,
+    Perception,
+}
+
+impl Attribute {
+    pub fn name(&self) -> &str {
+        match self {
+            Attribute::Strength => "Strength",
+            Attribute::Dexterity => "Dexterity",
+            Attribute::Intelligence => "Intelligence",
+            Attribute::Perception => "Perception",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct Attributes {
+    strength: u8,
+    dexterity: u8,
+    intelligence: u8,
+    perception: u8,
+}
+
+impl Attributes {
+    pub fn get(&self, attribute: Attribute) -> u8 {
+        match attribute {
+            Attribute::Strength => self.strength,
+            Attribute::Dexterity => self.dexterity,
+            Attribute::Intelligence => self.intelligence,
+            Attribute::Perception => self.perception,
+        }
+    }
+
+    pub fn set(&mut self, attribute: Attribute, value: u8) {
+        let value = value.clamp(MIN_ATTRIBUTE, MAX_ATTRIBUTE);
+        match attribute {
+            Attribute::Strength => self.strength = value,
+            Attribute::Dexterity => self.dexterity = value,
+            Attribute::Intelligence => self.intelligence = value,
+            Attribute::Perception => self.perception = value,
+        }
+    }
+
+    /// Points above the average, negative if the character is below it
+    pub fn points_spent(&self) -> i32 {
+        all::<Attribute>()
+            .map(|a| self.get(a) as i32 - AVERAGE_ATTRIBUTE as i32)
+            .sum()
+    }
+
+    /// Spends `ATTRIBUTE_POINTS` randomly, like a player would on the points-buy step
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut attributes = Self::default();
+        let list: Vec<Attribute> = all::<Attribute>().collect();
+        while attributes.points_spent() < ATTRIBUTE_POINTS {
+            let attribute = list[rng.gen_range(0..list.len())];
+            let value = attributes.get(attribute);
+            if value < MAX_ATTRIBUTE {
+                attributes.set(attribute, value + 1);
+            }
+        }
+        attributes
+    }
+}
+
+impl Default for Attributes {
+    fn default() -> Self {
+        Self {
+            strength: AVERAGE_ATTRIBUTE,
+            dexterity: AVERAGE_ATTRIBUTE,
+            intelligence: AVERAGE_ATTRIBUTE,
+            perception: AVERAGE_ATTRIBUTE,
+        }
+    }
+}
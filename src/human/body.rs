@@ -0,0 +1,59 @@
+use crate::data::species::Species;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyPlan {
+    Biped,
+    Quadruped,
+    Serpentine,
+}
+
+impl BodyPlan {
+    pub fn name(&self) -> &str {
+        match self {
+            BodyPlan::Biped => "Biped",
+            BodyPlan::Quadruped => "Quadruped",
+            BodyPlan::Serpentine => "Serpentine",
+        }
+    }
+}
+
+/// Traits of the species, copied when a character is created
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Body {
+    /// Id of `Species`
+    pub species: String,
+    pub plan: BodyPlan,
+    pub adult_age: u8,
+    pub lifespan: u16,
+    pub walk_cost: f32,
+    pub climb_cost: f32,
+}
+
+impl From<&Species> for Body {
+    fn from(species: &Species) -> Self {
+        Self {
+            species: species.id.clone(),
+            plan: species.body_plan,
+            adult_age: species.adult_age,
+            lifespan: species.lifespan,
+            walk_cost: species.walk_cost,
+            climb_cost: species.climb_cost,
+        }
+    }
+}
+
+/// Human, for characters created before species were added
+impl Default for Body {
+    fn default() -> Self {
+        Self {
+            species: "human".to_string(),
+            plan: BodyPlan::Biped,
+            adult_age: 16,
+            lifespan: 120,
+            walk_cost: 1.0,
+            climb_cost: 1.0,
+        }
+    }
+}
@@ -1,48 +1,305 @@
+use crate::data::background::Background;
 use crate::data::game_data::GameData;
+use crate::data::species::Species;
+use crate::game::calendar::{years_before, years_between};
+use crate::human::attributes::{Attributes, AVERAGE_ATTRIBUTE};
+use crate::human::body::Body;
 use crate::human::gender::Gender;
 use crate::human::main_hand::MainHand;
+use crate::human::skills::{Skill, Skills};
 use crate::human::skin_tone::SkinTone;
 use rand::distributions::Standard;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+/// Skilled characters can't do things faster than this share of usual time
+const MIN_LENGTH_MODIFIER: f32 = 0.6;
+const MAX_LENGTH_MODIFIER: f32 = 1.5;
+const MIN_SUCCESS_CHANCE: f32 = 0.05;
+/// Random characters are adults but not too old for the space, share of the lifespan
+const RANDOM_MAX_AGE: f32 = 0.58;
+/// Young characters learn faster, shares of the lifespan
+const FAST_LEARNING_AGE: f32 = 0.2;
+/// Learning slows down after this share of the lifespan
+const SLOW_LEARNING_AGE: f32 = 0.42;
+/// Physical actions get slower after this share of the lifespan
+const OLD_AGE: f32 = 0.5;
+/// Experience for every use of a skill by a character of learning rate 1.0
+const EXP_PER_USE: f32 = 10.0;
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Character {
     pub name: String,
+    /// Species traits, human for characters created before species were added
+    #[serde(default)]
+    pub body: Body,
     pub gender: Gender,
-    pub age: u8,
+    /// Timestamp of the in-game calendar, see `World::timestamp()`,
+    /// headers of old saves have ages instead, they are only migrated on loading the world
+    #[serde(default)]
+    pub birth: i64,
     pub main_hand: MainHand,
     pub skin_tone: SkinTone,
+    #[serde(default)]
+    pub attributes: Attributes,
+    #[serde(default)]
+    pub skills: Skills,
+    /// Id of `Background`, `None` for characters created before backgrounds were added
+    #[serde(default)]
+    pub background: Option<String>,
 }
 
 impl Character {
     pub fn new<S: Into<String>>(
         name: S,
         gender: Gender,
-        age: u8,
+        birth: i64,
         main_hand: MainHand,
         skin_tone: SkinTone,
     ) -> Self {
         Self {
             name: name.into(),
+            body: Body::default(),
             gender,
-            age,
+            birth,
             main_hand,
             skin_tone,
+            attributes: Attributes::default(),
+            skills: Skills::default(),
+            background: None,
+        }
+    }
+
+    pub fn with_species(mut self, species: &Species) -> Self {
+        self.body = Body::from(species);
+        self
+    }
+
+    /// Raises skills to the levels given by the background
+    pub fn with_background(mut self, background: &Background) -> Self {
+        for (skill, level) in background.skills.iter() {
+            if self.skills.level(*skill) < *level {
+                self.skills.set_level(*skill, *level);
+            }
         }
+        self.background = Some(background.id.clone());
+        self
+    }
+
+    pub fn with_abilities(mut self, attributes: Attributes, skills: Skills) -> Self {
+        self.attributes = attributes;
+        self.skills = skills;
+        self
+    }
+
+    /// Full years at the moment, `now` is a timestamp of the in-game calendar
+    pub fn age(&self, now: i64) -> u32 {
+        years_between(self.birth, now)
+    }
+
+    /// Share of the lifespan of the species, species age at different speed
+    fn relative_age(&self, now: i64) -> f32 {
+        self.age(now) as f32 / self.body.lifespan.max(1) as f32
     }
 
-    pub fn random<R: Rng + ?Sized>(rng: &mut R, data: &GameData) -> Character {
-        let gender = rng.sample(Standard);
-        let packs = data.names.as_slice();
+    /// Multiplier for the experience, young characters learn faster than old ones
+    pub fn learning_rate(&self, now: i64) -> f32 {
+        let age = self.relative_age(now);
+        if age < FAST_LEARNING_AGE {
+            1.5
+        } else if age < SLOW_LEARNING_AGE {
+            1.0
+        } else {
+            (1.0 - 2.4 * (age - SLOW_LEARNING_AGE)).max(0.25)
+        }
+    }
+
+    /// Gives experience for using the skill, returns true if it got a new level
+    pub fn train(&mut self, skill: Skill, uses: u32, now: i64) -> bool {
+        let exp = uses as f32 * EXP_PER_USE * self.learning_rate(now);
+        self.skills.train(skill, exp.round() as u32)
+    }
+
+    /// Attribute of the skill compared to the average
+    fn talent(&self, skill: Skill) -> f32 {
+        self.attributes.get(skill.attribute()) as f32 - AVERAGE_ATTRIBUTE as f32
+    }
+
+    /// Multiplier for the length of actions using the skill,
+    /// 1.0 for an average adult character without training
+    pub fn length_modifier(&self, skill: Skill, now: i64) -> f32 {
+        let level = self.skills.level(skill) as f32;
+        let modifier = (1.0 - 0.04 * level - 0.02 * self.talent(skill))
+            .clamp(MIN_LENGTH_MODIFIER, MAX_LENGTH_MODIFIER);
+        let age = self.relative_age(now);
+        if skill == Skill::Athletics && age > OLD_AGE {
+            (modifier * (1.0 + 1.2 * (age - OLD_AGE))).min(MAX_LENGTH_MODIFIER)
+        } else {
+            modifier
+        }
+    }
+
+    /// Chance to succeed in the action using the skill, difficulty is a skill level
+    /// giving 50% for an average character
+    pub fn success_chance(&self, skill: Skill, difficulty: u8) -> f32 {
+        let level = self.skills.level(skill) as f32;
+        (0.5 + 0.1 * (level - difficulty as f32) + 0.02 * self.talent(skill))
+            .clamp(MIN_SUCCESS_CHANCE, 1.0)
+    }
+
+    /// `now` is a timestamp of the in-game calendar
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, data: &GameData, now: i64) -> Character {
+        let mut species: Vec<&Species> = data.species.values().collect();
+        species.sort_by(|a, b| a.id.cmp(&b.id));
+        let species = species.get(rng.gen_range(0..species.len().max(1))).copied();
+        let (gender, skin_tone, packs) = match species {
+            Some(species) => (
+                Gender::from(species.genders[rng.gen_range(0..species.genders.len())].clone()),
+                species.skin_tones[rng.gen_range(0..species.skin_tones.len())],
+                data.names
+                    .iter()
+                    .filter(|p| species.names.contains(&p.id))
+                    .collect(),
+            ),
+            None => (
+                rng.sample(Standard),
+                rng.sample(Standard),
+                data.names.iter().collect::<Vec<_>>(),
+            ),
+        };
         let pack = &packs[rng.gen_range(0..packs.len())];
         let name = pack.random_name(rng, &gender);
+        let body = species.map(Body::from).unwrap_or_default();
+        let max_age = (body.lifespan as f32 * RANDOM_MAX_AGE) as u32;
+        let age = rng.gen_range(body.adult_age as u32 + 2..=max_age.max(body.adult_age as u32 + 2));
+        let birth = years_before(now, age) - rng.gen_range(0..365) * SECONDS_PER_DAY;
+        let mut backgrounds: Vec<&Background> = data.backgrounds.values().collect();
+        backgrounds.sort_by(|a, b| a.id.cmp(&b.id));
+        let mut character = Character::new(name, gender, birth, rng.sample(Standard), skin_tone)
+            .with_abilities(Attributes::random(rng), Skills::random(rng));
+        character.body = body;
+        if backgrounds.is_empty() {
+            character
+        } else {
+            character.with_background(backgrounds[rng.gen_range(0..backgrounds.len())])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Character;
+    use crate::data::game_data::GameData;
+    use crate::game::calendar::{timestamp, years_before};
+    use crate::human::attributes::{Attribute, Attributes};
+    use crate::human::gender::Gender;
+    use crate::human::main_hand::MainHand;
+    use crate::human::skills::{Skill, Skills};
+    use crate::human::skin_tone::SkinTone;
+
+    fn now() -> i64 {
+        timestamp(0)
+    }
+
+    fn average() -> Character {
         Character::new(
-            name,
-            gender,
-            rng.gen_range(0..=199),
-            rng.sample(Standard),
-            rng.sample(Standard),
+            "Ashley",
+            Gender::Female,
+            years_before(now(), 30),
+            MainHand::Right,
+            SkinTone::Almond,
         )
     }
+
+    #[test]
+    fn test_modifiers() {
+        let average = average();
+        assert_eq!(average.length_modifier(Skill::Engineering, now()), 1.0);
+        assert_eq!(average.success_chance(Skill::Engineering, 0), 0.5);
+
+        let mut attributes = Attributes::default();
+        attributes.set(Attribute::Intelligence, 8);
+        let mut skills = Skills::default();
+        skills.set_level(Skill::Engineering, 5);
+        let engineer = average.clone().with_abilities(attributes, skills);
+        assert!(engineer.length_modifier(Skill::Engineering, now()) < 1.0);
+        assert!(
+            engineer.success_chance(Skill::Engineering, 3)
+                > average.success_chance(Skill::Engineering, 3)
+        );
+        // engineering doesn't help to pilot a ship
+        assert_eq!(engineer.length_modifier(Skill::Piloting, now()), 1.0);
+    }
+
+    #[test]
+    fn test_background() {
+        let data = GameData::load();
+        let medic = data.backgrounds.get("colony_medic").unwrap();
+        let mut skills = Skills::default();
+        skills.set_level(Skill::Medicine, 5);
+        skills.set_level(Skill::Engineering, 0);
+        let character = average()
+            .with_abilities(Attributes::default(), skills)
+            .with_background(medic);
+        assert_eq!(character.background.as_deref(), Some("colony_medic"));
+        // background never lowers skills
+        assert_eq!(character.skills.level(Skill::Medicine), 5);
+        assert_eq!(
+            character.skills.level(Skill::Engineering),
+            medic.skills[&Skill::Engineering]
+        );
+
+        let random = Character::random(&mut rand::thread_rng(), &data, now());
+        let background = random.background.unwrap();
+        assert!(data.backgrounds.contains_key(background.as_str()));
+    }
+
+    #[test]
+    fn test_species() {
+        let data = GameData::load();
+        let vesk = data.species.get("vesk").unwrap();
+        let mut human = average();
+        human.birth = years_before(now(), 40);
+        let character = human.clone().with_species(vesk);
+        assert_eq!(character.body.species, "vesk");
+        assert_eq!(character.body.walk_cost, vesk.walk_cost);
+        // 40 years is a lot for vesk
+        assert!(character.learning_rate(now()) < human.learning_rate(now()));
+        assert!(
+            character.length_modifier(Skill::Athletics, now())
+                > human.length_modifier(Skill::Athletics, now())
+        );
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let random = Character::random(&mut rng, &data, now());
+            let species = data.species.get(random.body.species.as_str()).unwrap();
+            assert!(species.skin_tones.contains(&random.skin_tone));
+            assert!(species
+                .genders
+                .contains(&String::from(random.gender.clone())));
+            let age = random.age(now());
+            assert!(age > species.adult_age as u32 && age < species.lifespan as u32);
+        }
+    }
+
+    #[test]
+    fn test_aging() {
+        let mut character = average();
+        assert_eq!(character.age(now()), 30);
+        let year = 366 * 24 * 60 * 60 * 60;
+        assert_eq!(character.age(timestamp(year * 5)), 35);
+
+        // after a long voyage
+        let later = timestamp(year * 40);
+        assert_eq!(character.age(later), 70);
+        assert!(character.learning_rate(later) < character.learning_rate(now()));
+        assert!(
+            character.length_modifier(Skill::Athletics, later)
+                > character.length_modifier(Skill::Athletics, now())
+        );
+        assert!(!character.train(Skill::Piloting, 1, now()));
+        assert_eq!(character.skills.level(Skill::Piloting), 0);
+    }
 }
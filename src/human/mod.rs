@@ -1,4 +1,7 @@
+pub mod attributes;
+pub mod body;
 pub mod character;
 pub mod gender;
 pub mod main_hand;
+pub mod skills;
 pub mod skin_tone;
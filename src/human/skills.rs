@@ -0,0 +1,124 @@
+use crate::human::attributes::Attribute;
+use enum_iterator::{all, Sequence};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const MAX_SKILL: u8 = 10;
+/// Levels a new character spends on the points-buy step
+pub const SKILL_POINTS: u32 = 10;
+/// Skills can't be raised higher than this on the points-buy step
+pub const MAX_START_SKILL: u8 = 5;
+/// Experience for the next level is this multiplied by that level
+const EXP_PER_LEVEL: u32 = 500;
+
+#[derive(Serialize, Deserialize, Sequence, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Skill {
+    Piloting,
+    Engineering,
+    Medicine,
+    Athletics,
+}
+
+impl Skill {
+    pub fn name(&self) -> &str {
+        match self {
+            Skill::Piloting => "Piloting",
+            Skill::Engineering => "Engineering",
+            Skill::Medicine => "Medicine",
+            Skill::Athletics => "Athletics",
+        }
+    }
+
+    /// Attribute helping to use the skill
+    pub fn attribute(&self) -> Attribute {
+        match self {
+            Skill::Piloting => Attribute::Perception,
+            Skill::Engineering => Attribute::Intelligence,
+            Skill::Medicine => Attribute::Dexterity,
+            Skill::Athletics => Attribute::Strength,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, Eq, PartialEq)]
+struct Progress {
+    level: u8,
+    exp: u32,
+}
+
+/// Skills improve with use, every action gives some experience
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct Skills(HashMap<Skill, Progress>);
+
+impl Skills {
+    pub fn level(&self, skill: Skill) -> u8 {
+        self.0.get(&skill).map(|p| p.level).unwrap_or(0)
+    }
+
+    pub fn set_level(&mut self, skill: Skill, level: u8) {
+        self.0.entry(skill).or_default().level = level.min(MAX_SKILL);
+    }
+
+    /// Adds experience, returns true if the skill got a new level
+    pub fn train(&mut self, skill: Skill, exp: u32) -> bool {
+        let progress = self.0.entry(skill).or_default();
+        if progress.level >= MAX_SKILL {
+            return false;
+        }
+        progress.exp += exp;
+        let needed = EXP_PER_LEVEL * (progress.level as u32 + 1);
+        if progress.exp >= needed {
+            progress.exp -= needed;
+            progress.level += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn points_spent(&self) -> u32 {
+        self.0.values().map(|p| p.level as u32).sum()
+    }
+
+    /// Spends `SKILL_POINTS` randomly, like a player would on the points-buy step
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut skills = Self::default();
+        let list: Vec<Skill> = all::<Skill>().collect();
+        while skills.points_spent() < SKILL_POINTS {
+            let skill = list[rng.gen_range(0..list.len())];
+            let level = skills.level(skill);
+            if level < MAX_START_SKILL {
+                skills.set_level(skill, level + 1);
+            }
+        }
+        skills
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Skill, Skills, EXP_PER_LEVEL, MAX_START_SKILL, SKILL_POINTS};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_train() {
+        let mut skills = Skills::default();
+        assert_eq!(skills.level(Skill::Engineering), 0);
+        assert!(!skills.train(Skill::Engineering, EXP_PER_LEVEL - 1));
+        assert!(skills.train(Skill::Engineering, 1));
+        assert_eq!(skills.level(Skill::Engineering), 1);
+        // next level is harder
+        assert!(!skills.train(Skill::Engineering, EXP_PER_LEVEL));
+        assert!(skills.train(Skill::Engineering, EXP_PER_LEVEL));
+        assert_eq!(skills.level(Skill::Engineering), 2);
+        assert_eq!(skills.level(Skill::Piloting), 0);
+    }
+
+    #[test]
+    fn test_random() {
+        let skills = Skills::random(&mut thread_rng());
+        assert_eq!(skills.points_spent(), SKILL_POINTS);
+        assert!(skills.0.values().all(|p| p.level <= MAX_START_SKILL));
+    }
+}
@@ -5,7 +5,7 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use tetra::graphics::Color;
 
-#[derive(Serialize, Deserialize, Sequence, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Sequence, Debug, Copy, Clone, Eq, PartialEq)]
 pub enum SkinTone {
     PaleIvory,
     WarmIvory,
@@ -23,6 +23,10 @@ pub enum SkinTone {
     Golden,
     Espresso,
     Chocolate,
+    // non-human ones
+    Olive,
+    Teal,
+    Slate,
 }
 
 impl SkinTone {
@@ -45,7 +49,10 @@ impl SkinTone {
             | SkinTone::Bronze
             | SkinTone::Golden
             | SkinTone::Espresso
-            | SkinTone::Chocolate => Colors::LIGHT_GRAY,
+            | SkinTone::Chocolate
+            | SkinTone::Olive
+            | SkinTone::Teal
+            | SkinTone::Slate => Colors::LIGHT_GRAY,
             _ => Colors::DARK_GRAY,
         }
     }
@@ -70,6 +77,9 @@ impl From<SkinTone> for &str {
             SkinTone::Golden => "Golden",
             SkinTone::Espresso => "Espresso",
             SkinTone::Chocolate => "Chocolate",
+            SkinTone::Olive => "Olive",
+            SkinTone::Teal => "Teal",
+            SkinTone::Slate => "Slate",
         }
     }
 }
@@ -93,10 +103,14 @@ impl From<SkinTone> for Color {
             SkinTone::Golden => Colors::GOLDEN,
             SkinTone::Espresso => Colors::ESPRESSO,
             SkinTone::Chocolate => Colors::CHOCOLATE,
+            SkinTone::Olive => Colors::OLIVE_DRAB,
+            SkinTone::Teal => Colors::TEAL,
+            SkinTone::Slate => Colors::SLATE_GRAY,
         }
     }
 }
 
+/// Only human tones, species from data have their own lists
 impl Distribution<SkinTone> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SkinTone {
         match rng.gen_range(0..16) {
@@ -6,6 +6,7 @@ mod assets;
 mod astro;
 mod colors;
 mod data;
+mod export;
 mod fov;
 mod game;
 mod human;
@@ -13,6 +14,8 @@ mod input;
 mod savefile;
 mod scenes;
 mod settings;
+#[cfg(test)]
+mod test_dir;
 mod ui;
 mod window;
 
@@ -24,5 +27,9 @@ const VERSION: &str = concat!(
 );
 
 fn main() -> tetra::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some(export::COMMAND) {
+        std::process::exit(export::run(&args[1..]));
+    }
     window::create_context(format!("{} {}", TITLE, VERSION))?.run(app::App::new)
 }
@@ -0,0 +1,296 @@
+use crate::astro::galaxy::Galaxy;
+use crate::game::calendar::{timestamp, years_before};
+use crate::savefile::{LoadError, SAVE_FORMAT};
+use serde_json::{json, Value};
+
+/// Upgrades lines of the save from one format to the next one
+type Migration = fn(&mut Vec<Value>) -> Result<(), LoadError>;
+
+/// `MIGRATIONS[i]` turns format `i` into format `i + 1`
+const MIGRATIONS: [Migration; SAVE_FORMAT as usize] = [birth_dates, compact_galaxy, components];
+
+/// Header, galaxy, avatar and ship, every format has them
+const REQUIRED_LINES: [&str; 4] = ["header", "galaxy", "avatar", "ship"];
+
+/// Runs every migration from `format` to the current one
+pub fn migrate(lines: &mut Vec<Value>, format: u32) -> Result<(), LoadError> {
+    if format > SAVE_FORMAT {
+        return Err(LoadError::NewerFormat(format));
+    }
+    if let Some(&line) = REQUIRED_LINES.get(lines.len()) {
+        return Err(LoadError::MissingLine(line));
+    }
+    for migration in MIGRATIONS.iter().skip(format as usize) {
+        migration(lines)?;
+    }
+    if let Some(header) = lines[0].as_object_mut() {
+        header.insert("format".to_string(), json!(SAVE_FORMAT));
+    }
+    Ok(())
+}
+
+/// Format 0 is everything before versioned saves: stations and crew lines
+/// may be missing, ships have no quadrant and characters have ages instead of birth dates
+fn birth_dates(lines: &mut Vec<Value>) -> Result<(), LoadError> {
+    // saves made before stations and crew were introduced
+    while lines.len() < 6 {
+        lines.push(json!([]));
+    }
+    // ships didn't travel before the galaxy map, they are still in the start quadrant
+    if lines[3].get("quadrant").is_none() {
+        let galaxy: Galaxy =
+            serde_json::from_value(lines[1].clone()).map_err(|e| LoadError::broken("galaxy", e))?;
+        if let Some(ship) = lines[3].as_object_mut() {
+            ship.insert("quadrant".to_string(), json!(galaxy.start_quadrant()));
+        }
+    }
+    let tick = lines[0]["current_tick"].as_u64().unwrap_or(0);
+    let now = timestamp(tick as u128);
+    let fix = |character: Option<&mut Value>| {
+        if let Some(character) = character.and_then(Value::as_object_mut) {
+            if let Some(age) = character.remove("age").and_then(|a| a.as_u64()) {
+                character.insert("birth".to_string(), json!(years_before(now, age as u32)));
+            }
+        }
+    };
+    fix(lines[0].get_mut("character"));
+    fix(lines[2].get_mut("character"));
+    if let Some(crew) = lines[5].as_array_mut() {
+        for member in crew.iter_mut() {
+            fix(member.get_mut("character"));
+        }
+    }
+    Ok(())
+}
+
+/// Format 1 stores every quadrant, now only visited quadrants and generated chunks are saved,
+/// see `GalaxyChanges`. Stars are kept: this galaxy was made by an older generator.
+#[allow(clippy::ptr_arg)] // every migration has the same signature
+fn compact_galaxy(lines: &mut Vec<Value>) -> Result<(), LoadError> {
+    let galaxy = lines[1]
+        .as_object_mut()
+        .ok_or_else(|| LoadError::DeserializeError {
+            line: "galaxy",
+            message: "not an object".to_string(),
+        })?;
+    let quadrants = galaxy.remove("quadrants").unwrap_or_default();
+    let mut visited = Vec::new();
+    let mut chunks = Vec::new();
+    let mut stars = Vec::new();
+    for (i, quadrant) in quadrants.as_array().into_iter().flatten().enumerate() {
+        stars.push(quadrant["s"].as_u64().unwrap_or(0));
+        if quadrant["v"].as_bool().unwrap_or(false) {
+            visited.push(i);
+        }
+        if let Some(c) = quadrant
+            .get("c")
+            .filter(|c| c.as_array().is_some_and(|c| !c.is_empty()))
+        {
+            chunks.push(json!([i, c]));
+        }
+    }
+    galaxy.insert("visited".to_string(), json!(visited));
+    galaxy.insert("chunks".to_string(), json!(chunks));
+    galaxy.insert("stars".to_string(), json!(stars));
+    Ok(())
+}
+
+/// Format 2 has separate engine, thruster and fuel tank parts,
+/// now they are one `Component` part with the kind of its `ShipComponent`
+#[allow(clippy::ptr_arg)] // every migration has the same signature
+fn components(lines: &mut Vec<Value>) -> Result<(), LoadError> {
+    let fix = |ship: &mut Value| {
+        let parts = ship
+            .get_mut("tiles")
+            .and_then(Value::as_array_mut)
+            .into_iter()
+            .flatten()
+            .filter_map(|tile| tile.get_mut("parts").and_then(Value::as_array_mut))
+            .flatten();
+        for part in parts {
+            for (name, kind) in [
+                ("Engine", "engine"),
+                ("Thruster", "thruster"),
+                ("FuelTank", "fuel_tank"),
+            ] {
+                let component = match part.get_mut(name).and_then(Value::as_object_mut) {
+                    Some(component) => component,
+                    None => continue,
+                };
+                let mut component = std::mem::take(component);
+                component.insert("kind".to_string(), json!(kind));
+                for stat in ["thrust", "fuel_capacity"] {
+                    component.entry(stat).or_insert(json!(0));
+                }
+                *part = json!({ "Component": component });
+            }
+        }
+    };
+    fix(&mut lines[3]);
+    if let Some(stations) = lines.get_mut(4).and_then(Value::as_array_mut) {
+        for station in stations.iter_mut() {
+            if let Some(structure) = station.get_mut("structure") {
+                fix(structure);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::migrate;
+    use crate::astro::galaxy::{Galaxy, GalaxyChanges, GalaxyMeta};
+    use crate::astro::galaxy_class::GalaxyClass;
+    use crate::astro::galaxy_size::GalaxySize;
+    use crate::astro::spiral_arms::SpiralArms;
+    use crate::data::game_data::GameData;
+    use crate::game::avatar::Avatar;
+    use crate::game::calendar::timestamp;
+    use crate::game::ship::Ship;
+    use crate::human::character::Character;
+    use crate::savefile::{load, LoadError, SAVE_FORMAT};
+    use crate::test_dir::TestDir;
+    use geometry::Point;
+    use rand::thread_rng;
+    use serde_json::{json, Value};
+    use std::time::SystemTime;
+
+    fn character(age: u8) -> Value {
+        json!({"name": "Old", "gender": "Male", "age": age, "main_hand": "Left", "skin_tone": "Sand"})
+    }
+
+    /// Lines as the first release wrote them: full galaxy, no stations and crew,
+    /// ship without quadrant, fuel and cargo, avatar without deck
+    fn legacy_lines() -> Vec<Value> {
+        let meta = GalaxyMeta::new(
+            "Legacy".to_string(),
+            "42".to_string(),
+            GalaxySize::Small,
+            GalaxyClass::Spiral,
+            SpiralArms::default(),
+        );
+        let mut galaxy = serde_json::to_value(Galaxy::from(meta.clone())).unwrap();
+        galaxy["quadrants"][1]["v"] = json!(true);
+        galaxy["quadrants"][1]["c"] = json!([null, {}]);
+        // older generator gave other stars for the same seed
+        galaxy["quadrants"][0]["s"] = json!(7777);
+        let mut avatar = serde_json::to_value(Avatar::new(
+            Character::random(&mut thread_rng(), &GameData::load(), timestamp(0)),
+            Point::new(1, 1),
+        ))
+        .unwrap();
+        avatar["character"] = character(42);
+        let avatar = avatar.as_object_mut().unwrap();
+        avatar.remove("deck");
+        avatar.remove("aboard");
+        let frame = json!({"Frame": {"hp": 100}});
+        let floor = json!({"Floor": {"hp": 100}});
+        let tile = |parts: Value| json!({ "parts": parts });
+        vec![
+            json!({
+                "version": "v0.1.0",
+                "time": SystemTime::now(),
+                "current_tick": 0,
+                "character": character(42),
+                "galaxy_meta": meta,
+            }),
+            galaxy,
+            json!(avatar),
+            json!({
+                "name": "Old Dugong",
+                "class_name": "Dugong",
+                "tiles": [
+                    tile(json!([frame, floor])),
+                    tile(json!([frame, {"Door": {"hp": 100, "open": false, "locked": false}}])),
+                    tile(json!([frame, floor])),
+                    tile(json!([frame, floor])),
+                    tile(json!([frame, floor, {"Seat": {"hp": 10}}])),
+                    tile(json!([frame, {"Terminal": {"hp": 10}}])),
+                    tile(json!([])),
+                    tile(json!([frame, floor])),
+                    tile(json!([])),
+                ],
+                "bounds": [3, 3],
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_migrate_legacy_lines() {
+        let mut lines = legacy_lines();
+        migrate(&mut lines, 0).unwrap();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0]["format"], json!(SAVE_FORMAT));
+        assert_eq!(lines[4], json!([]));
+        assert_eq!(lines[1]["visited"], json!([1]));
+        assert_eq!(lines[1]["chunks"], json!([[1, [null, {}]]]));
+        assert!(lines[1].get("quadrants").is_none());
+        assert_eq!(lines[1]["stars"][0], json!(7777));
+        let avatar: Character = serde_json::from_value(lines[2]["character"].take()).unwrap();
+        assert_eq!(avatar.age(timestamp(0)), 42);
+        assert!(!serde_json::to_string(&avatar).unwrap().contains("\"age\""));
+    }
+
+    #[test]
+    fn test_migrate_components() {
+        let mut lines = legacy_lines();
+        let engine = json!({"Engine": {"component": "d", "hp": 5, "max_hp": 10, "mass": 3,
+            "thrust": 50}});
+        let tank = json!({"FuelTank": {"component": "t", "hp": 8, "max_hp": 8, "mass": 2,
+            "fuel_capacity": 40}});
+        lines[3]["tiles"][6]["parts"] = json!([engine]);
+        lines[3]["tiles"][8]["parts"] = json!([tank]);
+        migrate(&mut lines, 0).unwrap();
+        let ship: Ship = serde_json::from_value(lines[3].clone()).unwrap();
+        let stats = ship.stats();
+        assert_eq!(stats.thrust, 25.0);
+        assert_eq!(stats.fuel_capacity, 40.0);
+        assert_eq!(
+            lines[3]["tiles"][6]["parts"][0]["Component"]["kind"],
+            json!("engine")
+        );
+    }
+
+    #[test]
+    fn test_legacy_save() {
+        let dir = TestDir::new("save-legacy");
+        let path = dir.path().join("legacy.save");
+        let data: Vec<String> = legacy_lines().iter().map(Value::to_string).collect();
+        std::fs::write(&path, data.join("\n")).unwrap();
+
+        let savefile = load(&path).unwrap();
+        assert_eq!(savefile.format, 0);
+        let mut world = savefile.load_world().unwrap();
+        assert_eq!(world.ship.name, "Old Dugong");
+        assert_eq!(world.ship.quadrant, world.galaxy.start_quadrant());
+        assert!(world.galaxy.quadrants[1].visited);
+        assert_eq!(world.galaxy.quadrants[0].stars_count, 7777);
+        // stars are saved again and stay the same after the next load
+        let changes = GalaxyChanges::from(&world.galaxy);
+        let galaxy = Galaxy::from(serde_json::from_value::<GalaxyChanges>(json!(changes)).unwrap());
+        assert_eq!(galaxy.quadrants[0].stars_count, 7777);
+        assert_eq!(world.avatar.character.age(world.timestamp()), 42);
+        assert!(world.stations.is_empty());
+        assert!(world.crew.is_empty());
+
+        let data = GameData::load();
+        world.refit_legacy_ship(&data);
+        assert!(world.ship.fuel > 0.0);
+        world.set_destination(Some(world.ship.quadrant + Point::new(1, 0)));
+        assert!(world.travel().is_ok());
+    }
+
+    #[test]
+    fn test_incompatible_save() {
+        let mut lines = vec![json!({}), json!({})];
+        assert!(matches!(
+            migrate(&mut lines, 0),
+            Err(LoadError::MissingLine("avatar"))
+        ));
+        assert!(matches!(
+            migrate(&mut lines, SAVE_FORMAT + 1),
+            Err(LoadError::NewerFormat(..))
+        ));
+    }
+}
@@ -0,0 +1,716 @@
+use crate::astro::galaxy::{Galaxy, GalaxyChanges, GalaxyMeta};
+use crate::game::world::World;
+use crate::human::character::Character;
+use crate::settings::Settings;
+use crate::VERSION;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use migrations::migrate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+mod migrations;
+
+// TODO: move it away, like in Necromanzer
+
+/// Version of the save structure, raise it and add a migration on every breaking change
+pub const SAVE_FORMAT: u32 = 3;
+/// Slot of a new galaxy
+const DEFAULT_SLOT: &str = "Slot 1";
+/// Limit for `Settings.game.backups`, older backups are never looked for
+pub const MAX_BACKUPS: u32 = 10;
+/// First bytes of gzip stream, compressed saves are detected by them
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// Every line of the save in order, errors point to them by these names
+const LINES: [&str; 6] = ["header", "galaxy", "avatar", "ship", "stations", "crew"];
+
+/// Galaxy name or slot name made safe for the file system
+fn file_name(name: &str) -> String {
+    name.trim().replace(' ', "_").replace(['/', '\\', '.'], "")
+}
+
+/// Every galaxy has its own directory with a savefile for every slot,
+/// saves made before slots are right in the `save` directory
+pub fn slot_path(galaxy: &str, slot: &str) -> PathBuf {
+    [
+        "save",
+        file_name(galaxy).as_str(),
+        (file_name(slot) + ".save").as_str(),
+    ]
+    .iter()
+    .collect()
+}
+
+/// Names like "..." lose every character in the file name, for galaxies as well as slots
+pub fn is_valid_slot(slot: &str) -> bool {
+    !file_name(slot).is_empty()
+}
+
+pub fn slot_exists(galaxy: &str, slot: &str) -> bool {
+    slot_path(galaxy, slot).is_file()
+}
+
+/// First of "Slot 1", "Slot 2", etc. which isn't taken yet
+fn free_slot(galaxy: &str) -> String {
+    (1..)
+        .map(|i| format!("Slot {}", i))
+        .find(|slot| !slot_exists(galaxy, slot))
+        .unwrap()
+}
+
+fn is_savefile(path: &Path) -> bool {
+    path.is_file() && path.extension().map(|ext| ext == "save").unwrap_or(false)
+}
+
+/// Savefiles in the `save` directory and directories of galaxies
+fn savefile_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let read_dir = |dir: &Path| -> Vec<PathBuf> {
+        dir.read_dir()
+            .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+            .unwrap_or_default()
+    };
+    for path in read_dir(Path::new("save")) {
+        if path.is_dir() {
+            paths.extend(read_dir(&path).into_iter().filter(|p| is_savefile(p)));
+        } else if is_savefile(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+pub fn savefiles_exists() -> bool {
+    !savefile_paths().is_empty()
+}
+
+/// Savefile this version of the game can't open
+#[derive(Debug)]
+pub struct IncompatibleSave {
+    pub path: PathBuf,
+    pub error: LoadError,
+}
+
+/// Savefiles sorted from the newest one and saves which can't be loaded
+pub fn savefiles() -> (Vec<SaveFile>, Vec<IncompatibleSave>) {
+    let mut files = Vec::new();
+    let mut incompatible = Vec::new();
+    for p in savefile_paths() {
+        match load(&p) {
+            Ok(s) => files.push(s),
+            Err(error) => incompatible.push(IncompatibleSave { path: p, error }),
+        }
+    }
+    files.sort();
+    files.reverse();
+    incompatible.sort_by(|a, b| a.path.cmp(&b.path));
+    (files, incompatible)
+}
+
+pub fn delete(path: &Path) {
+    if path.exists() {
+        std::fs::remove_file(path).ok();
+    }
+    for backup in backups(path) {
+        std::fs::remove_file(backup).ok();
+    }
+    // directory of the galaxy is only removed when it's empty
+    if let Some(dir) = path.parent().filter(|dir| *dir != Path::new("save")) {
+        std::fs::remove_dir(dir).ok();
+    }
+}
+
+/// `save/Name.save` has backups `save/Name.1.bak`, `save/Name.2.bak`, etc.
+fn backup_path(path: &Path, n: u32) -> PathBuf {
+    path.with_extension(format!("{}.bak", n))
+}
+
+/// Existing backups of the savefile, from the newest one
+pub fn backups(path: &Path) -> Vec<PathBuf> {
+    (1..=MAX_BACKUPS)
+        .map(|n| backup_path(path, n))
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+/// Moves backups one step older, the oldest one is overwritten
+fn rotate_backups(path: &Path, count: u32) -> Result<(), SaveError> {
+    for n in (1..count).rev() {
+        let from = backup_path(path, n);
+        if from.is_file() {
+            std::fs::rename(from, backup_path(path, n + 1)).map_err(SaveError::from)?;
+        }
+    }
+    std::fs::copy(path, backup_path(path, 1)).map_err(SaveError::from)?;
+    Ok(())
+}
+
+/// Replaces the savefile with its newest backup, the next backups become newer,
+/// so restoring again goes one more save back
+pub fn restore_backup(path: &Path) -> Result<(), LoadError> {
+    let newest = backup_path(path, 1);
+    if !newest.is_file() {
+        return Err(LoadError::NoBackup);
+    }
+    std::fs::rename(newest, path).map_err(LoadError::from)?;
+    for n in 2..=MAX_BACKUPS {
+        let from = backup_path(path, n);
+        if from.is_file() {
+            std::fs::rename(from, backup_path(path, n - 1)).map_err(LoadError::from)?;
+        }
+    }
+    Ok(())
+}
+
+/// Explains the most common file system problems, others are shown as the OS reports them
+fn io_cause(f: &mut Formatter<'_>, kind: &ErrorKind, message: &str) -> std::fmt::Result {
+    match kind {
+        ErrorKind::NotFound => write!(f, "file or directory is not found"),
+        ErrorKind::PermissionDenied => write!(f, "permission denied"),
+        ErrorKind::UnexpectedEof => write!(f, "file is cut off"),
+        _ => write!(f, "{}", message),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SaveError {
+    /// File or directory can't be written
+    SystemError {
+        kind: ErrorKind,
+        message: String,
+    },
+    /// Part of the world can't be turned into JSON, name of the line and the reason
+    SerializeError {
+        line: &'static str,
+        message: String,
+    },
+    FileExists,
+    /// Galaxy name has nothing left after making it safe for the file system
+    InvalidName,
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveError::SystemError { kind, message } => {
+                write!(f, "can't write file: ")?;
+                io_cause(f, kind, message)
+            }
+            SaveError::SerializeError { line, message } => {
+                write!(f, "can't store {}: {}", line, message)
+            }
+            SaveError::FileExists => write!(f, "galaxy with this name already exists"),
+            SaveError::InvalidName => write!(f, "galaxy name can't be only dots and slashes"),
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(e: std::io::Error) -> Self {
+        SaveError::SystemError {
+            kind: e.kind(),
+            message: e.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LoadError {
+    /// File can't be opened or read
+    SystemError { kind: ErrorKind, message: String },
+    /// Line of the save doesn't match the structure of the world, name of the line and the reason
+    DeserializeError { line: &'static str, message: String },
+    /// Save was made by a newer version of the game
+    NewerFormat(u32),
+    /// File is shorter than its format requires, name of the first missing line
+    MissingLine(&'static str),
+    /// Save without a character only has a galaxy, `CreateCharacter` has to fill it first
+    NoCharacter,
+    /// Backup is asked for but the savefile has none
+    NoBackup,
+}
+
+impl LoadError {
+    fn broken(line: &'static str, e: serde_json::Error) -> Self {
+        LoadError::DeserializeError {
+            line,
+            message: e.to_string(),
+        }
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::SystemError { kind, message } => {
+                write!(f, "can't read file: ")?;
+                io_cause(f, kind, message)
+            }
+            LoadError::DeserializeError { line, message } => {
+                write!(f, "broken {}: {}", line, message)
+            }
+            LoadError::NewerFormat(format) => write!(
+                f,
+                "save format {} is newer than supported {}",
+                format, SAVE_FORMAT
+            ),
+            LoadError::MissingLine(line) => write!(f, "{} is missing", line),
+            LoadError::NoCharacter => write!(f, "there is no character"),
+            LoadError::NoBackup => write!(f, "there is no backup"),
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(e: std::io::Error) -> Self {
+        LoadError::SystemError {
+            kind: e.kind(),
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Failure which takes the player out of the world, shown by the `WorldError` scene
+#[derive(Debug, Clone)]
+pub enum WorldError {
+    Load(PathBuf, LoadError),
+    Save(PathBuf, SaveError),
+}
+
+impl WorldError {
+    pub fn path(&self) -> &Path {
+        match self {
+            WorldError::Load(path, _) | WorldError::Save(path, _) => path,
+        }
+    }
+}
+
+fn serialize<T: Serialize>(value: &T, line: &'static str) -> Result<String, SaveError> {
+    serde_json::to_string(value).map_err(|e| SaveError::SerializeError {
+        line,
+        message: e.to_string(),
+    })
+}
+
+fn make_data(savefile: &SaveFile, world: Option<&World>) -> Result<String, SaveError> {
+    let mut data = vec![serialize(savefile, "header")?];
+    if let Some(world) = world {
+        data.push(serialize(&GalaxyChanges::from(&world.galaxy), "galaxy")?);
+        data.push(serialize(&world.avatar, "avatar")?);
+        data.push(serialize(&world.ship, "ship")?);
+        data.push(serialize(&world.stations, "stations")?);
+        data.push(serialize(&world.crew, "crew")?);
+        // TODO: other units, sectors data
+    } else {
+        data.push(serialize(
+            &GalaxyChanges::from(savefile.galaxy_meta.clone()),
+            "galaxy",
+        )?);
+    }
+    let data = data.join("\n");
+    Ok(data)
+}
+
+fn make_dir(path: &Path) -> Result<(), SaveError> {
+    if let Some(dir) = path.parent() {
+        if !dir.exists() {
+            std::fs::create_dir_all(dir).map_err(SaveError::from)?;
+        }
+    }
+    Ok(())
+}
+
+/// Galaxy names are unique, other playthroughs in the same galaxy are added by `new_slot()`
+pub fn create(galaxy_meta: GalaxyMeta) -> Result<(), SaveError> {
+    if !is_valid_slot(&galaxy_meta.name) {
+        return Err(SaveError::InvalidName);
+    }
+    let savefile: SaveFile = galaxy_meta.into();
+    let legacy: PathBuf = [
+        "save",
+        (file_name(savefile.galaxy_name()) + ".save").as_str(),
+    ]
+    .iter()
+    .collect();
+    if savefile.path.parent().is_some_and(Path::exists) || legacy.is_file() {
+        return Err(SaveError::FileExists);
+    }
+    make_dir(&savefile.path)?;
+    let compress = Settings::instance().game.compress_saves;
+    write(&savefile.path, make_data(&savefile, None)?, compress, 0)
+}
+
+/// Empty slot in the galaxy of the savefile, for a new character
+pub fn new_slot(savefile: &SaveFile) -> Result<SaveFile, SaveError> {
+    let savefile: SaveFile = savefile.galaxy_meta.clone().into();
+    let slot = free_slot(savefile.galaxy_name());
+    let savefile = savefile.with_slot(slot);
+    make_dir(&savefile.path)?;
+    let compress = Settings::instance().game.compress_saves;
+    write(&savefile.path, make_data(&savefile, None)?, compress, 0)?;
+    Ok(savefile)
+}
+
+/// Data goes to a temporary file first and replaces the savefile only when it's written,
+/// so a crash never leaves a broken save; the previous version becomes the first backup
+fn write(path: &Path, data: String, compress: bool, backups: u32) -> Result<(), SaveError> {
+    let tmp = path.with_extension("tmp");
+    let file = File::create(&tmp).map_err(SaveError::from)?;
+    let file = if compress {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder
+            .write_all(data.as_bytes())
+            .map_err(SaveError::from)?;
+        encoder.finish().map_err(SaveError::from)?
+    } else {
+        let mut file = file;
+        file.write_all(data.as_bytes()).map_err(SaveError::from)?;
+        file
+    };
+    file.sync_all().map_err(SaveError::from)?;
+    if backups > 0 && path.is_file() {
+        rotate_backups(path, backups)?;
+    }
+    std::fs::rename(tmp, path).map_err(SaveError::from)
+}
+
+/// Lines of the save, compressed and plain files are read the same way
+fn open(path: &Path) -> Result<Box<dyn BufRead>, LoadError> {
+    let mut reader = BufReader::new(File::open(path).map_err(LoadError::from)?);
+    let compressed = reader
+        .fill_buf()
+        .map_err(LoadError::from)?
+        .starts_with(&GZIP_MAGIC);
+    Ok(if compressed {
+        Box::new(BufReader::new(GzDecoder::new(reader)))
+    } else {
+        Box::new(reader)
+    })
+}
+
+/// Reads only the first line, headers of every format can be read without migrations
+pub fn load(path: &Path) -> Result<SaveFile, LoadError> {
+    let mut lines = open(path)?.lines();
+    let meta = lines
+        .next()
+        .ok_or(LoadError::MissingLine("header"))?
+        .map_err(LoadError::from)?;
+    let savefile: SaveFile =
+        serde_json::from_str(meta.as_str()).map_err(|e| LoadError::broken("header", e))?;
+    if savefile.format > SAVE_FORMAT {
+        return Err(LoadError::NewerFormat(savefile.format));
+    }
+    Ok(savefile.with_path(path))
+}
+
+pub fn save(world: &World) -> Result<(), SaveError> {
+    let savefile: SaveFile = world.into();
+    make_dir(&savefile.path)?;
+    let (compress, backups) = {
+        let settings = Settings::instance();
+        (settings.game.compress_saves, settings.game.backups)
+    };
+    write(
+        &savefile.path,
+        make_data(&savefile, Some(world))?,
+        compress,
+        backups,
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SaveFile {
+    #[serde(skip)]
+    pub path: PathBuf,
+    pub version: String,
+    /// `SAVE_FORMAT` of the game which wrote the file, 0 for saves made before formats
+    #[serde(default)]
+    pub format: u32,
+    pub time: SystemTime,
+    pub current_tick: u128,
+    pub character: Option<Character>,
+    /// Crew hired in `CreateCharacter`, only used by `World::create()`
+    #[serde(skip)]
+    pub crew: Vec<Character>,
+    galaxy_meta: GalaxyMeta,
+    /// Name of the save slot, empty for saves made before slots
+    #[serde(default)]
+    pub slot: String,
+    /// Ids of mods the world was created with, in load order
+    #[serde(default)]
+    pub mods: Vec<String>,
+}
+
+impl SaveFile {
+    // there are 3 ways to create SaveFile
+    // 1. GalaxyMeta::into(), uses in create()
+    // 2. load(&Path) uses in savefiles() and LoadWorld scene for loading only first string
+    // 3. World::into(), uses in save()
+
+    fn with_path(mut self, path: &Path) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    fn with_slot(mut self, slot: String) -> Self {
+        self.path = slot_path(self.galaxy_name(), &slot);
+        self.slot = slot;
+        self
+    }
+
+    /// Old saves don't have slot names, they are called by the character
+    pub fn slot_name(&self) -> &str {
+        if self.slot.is_empty() {
+            self.character_name()
+        } else {
+            self.slot.as_str()
+        }
+    }
+
+    pub fn galaxy_name(&self) -> &str {
+        self.galaxy_meta.name.as_str()
+    }
+
+    pub fn character_name(&self) -> &str {
+        if let Some(character) = &self.character {
+            character.name.as_str()
+        } else {
+            "no character"
+        }
+    }
+
+    pub fn has_character(&self) -> bool {
+        self.character.is_some()
+    }
+
+    pub fn set_character(&mut self, character: Character) -> &mut Self {
+        self.character = Some(character);
+        self
+    }
+
+    pub fn set_crew(&mut self, crew: Vec<Character>) -> &mut Self {
+        self.crew = crew;
+        self
+    }
+
+    /// Full galaxies written by old formats are read as changes too, nothing changes
+    /// in galaxies of saves without a character
+    pub fn load_galaxy(&self) -> Result<Galaxy, LoadError> {
+        let mut lines = open(&self.path)?.lines();
+        let line = lines
+            .nth(1)
+            .ok_or(LoadError::MissingLine("galaxy"))?
+            .map_err(LoadError::from)?;
+        serde_json::from_str::<GalaxyChanges>(line.as_str())
+            .map(Galaxy::from)
+            .map_err(|e| LoadError::broken("galaxy", e))
+    }
+
+    pub fn load_world(&self) -> Result<World, LoadError> {
+        let mut lines = open(&self.path)?
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let name = LINES.get(i).copied().unwrap_or("line");
+                serde_json::from_str(line.map_err(LoadError::from)?.as_str())
+                    .map_err(|e| LoadError::broken(name, e))
+            })
+            .collect::<Result<Vec<Value>, LoadError>>()?;
+        migrate(&mut lines, self.format)?;
+        let world = World::new(
+            self.path.clone(),
+            Galaxy::from(take::<GalaxyChanges>(&mut lines, 1, "galaxy")?),
+            take(&mut lines, 2, "avatar")?,
+            take(&mut lines, 3, "ship")?,
+            take(&mut lines, 4, "stations")?,
+            self.current_tick,
+        )
+        .with_crew(take(&mut lines, 5, "crew")?)
+        .with_mods(self.mods.clone())
+        .with_slot(self.slot.clone());
+        Ok(world)
+    }
+}
+
+fn take<T: DeserializeOwned>(
+    lines: &mut [Value],
+    index: usize,
+    name: &'static str,
+) -> Result<T, LoadError> {
+    let line = lines
+        .get_mut(index)
+        .ok_or(LoadError::MissingLine(name))?
+        .take();
+    serde_json::from_value(line).map_err(|e| LoadError::broken(name, e))
+}
+
+impl Eq for SaveFile {}
+
+impl PartialEq<Self> for SaveFile {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl PartialOrd<Self> for SaveFile {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SaveFile {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.time.cmp(&other.time)
+    }
+}
+
+impl From<GalaxyMeta> for SaveFile {
+    fn from(galaxy_meta: GalaxyMeta) -> Self {
+        Self {
+            path: slot_path(&galaxy_meta.name, DEFAULT_SLOT),
+            version: VERSION.to_string(),
+            format: SAVE_FORMAT,
+            time: SystemTime::now(),
+            galaxy_meta,
+            character: None,
+            crew: Vec::new(),
+            current_tick: 0,
+            slot: DEFAULT_SLOT.to_string(),
+            mods: Vec::new(),
+        }
+    }
+}
+
+impl From<&World> for SaveFile {
+    fn from(world: &World) -> Self {
+        SaveFile {
+            path: world.path.clone(),
+            version: VERSION.to_string(),
+            format: SAVE_FORMAT,
+            time: SystemTime::now(),
+            galaxy_meta: world.galaxy.meta.clone(),
+            character: Some(world.avatar.character.clone()),
+            crew: Vec::new(),
+            current_tick: world.current_tick,
+            slot: world.slot.clone(),
+            mods: world.mods.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        backups, create, delete, is_valid_slot, load, open, restore_backup, slot_path, write,
+        LoadError, SaveError,
+    };
+    use crate::astro::galaxy::GalaxyMeta;
+    use crate::astro::galaxy_class::GalaxyClass;
+    use crate::astro::galaxy_size::GalaxySize;
+    use crate::astro::spiral_arms::SpiralArms;
+    use crate::test_dir::TestDir;
+    use std::io::{BufRead, ErrorKind};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_slot_path() {
+        let path: PathBuf = ["save", "Milky_Way", "Slot_2.save"].iter().collect();
+        assert_eq!(slot_path("Milky Way", "Slot 2"), path);
+        let path: PathBuf = ["save", "Andromeda", "etcpasswd.save"].iter().collect();
+        assert_eq!(slot_path("Andromeda", " ../etc/passwd "), path);
+    }
+
+    #[test]
+    fn test_valid_slot() {
+        assert!(is_valid_slot("Slot 2"));
+        assert!(is_valid_slot("../etc"));
+        for slot in ["", "   ", "...", "//", " \\. "] {
+            assert!(!is_valid_slot(slot), "{:?}", slot);
+        }
+    }
+
+    #[test]
+    fn test_invalid_galaxy_name() {
+        let meta = GalaxyMeta::new(
+            " ... ".to_string(),
+            "42".to_string(),
+            GalaxySize::Small,
+            GalaxyClass::Spiral,
+            SpiralArms::default(),
+        );
+        assert!(matches!(create(meta), Err(SaveError::InvalidName)));
+    }
+
+    #[test]
+    fn test_compressed() {
+        let tmp = TestDir::new("save-compressed");
+        let dir = tmp.path();
+        let data = "{\"a\":1}\n{\"b\":2}".to_string();
+        for compress in [false, true] {
+            let path = dir.join(format!("{}.save", compress));
+            write(&path, data.clone(), compress, 0).unwrap();
+            let lines: Vec<String> = open(&path).unwrap().lines().map(|l| l.unwrap()).collect();
+            assert_eq!(lines, ["{\"a\":1}", "{\"b\":2}"]);
+        }
+    }
+
+    fn first_line(path: &std::path::Path) -> String {
+        open(path).unwrap().lines().next().unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_backups() {
+        let tmp = TestDir::new("save-backups");
+        let dir = tmp.path();
+        let path = dir.join("world.save");
+        for i in 0..4 {
+            write(&path, i.to_string(), false, 2).unwrap();
+        }
+        assert_eq!(first_line(&path), "3");
+        let list = backups(&path);
+        assert_eq!(list, [dir.join("world.1.bak"), dir.join("world.2.bak")]);
+        assert_eq!(first_line(&list[0]), "2");
+        assert_eq!(first_line(&list[1]), "1");
+        assert!(!path.with_extension("tmp").exists());
+
+        restore_backup(&path).unwrap();
+        assert_eq!(first_line(&path), "2");
+        assert_eq!(backups(&path), [dir.join("world.1.bak")]);
+        assert_eq!(first_line(&dir.join("world.1.bak")), "1");
+
+        delete(&path);
+        assert!(!path.exists());
+        assert!(backups(&path).is_empty());
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let tmp = TestDir::new("save-errors");
+        let dir = tmp.path();
+        let path = dir.join("broken.save");
+        assert!(matches!(
+            load(&path),
+            Err(LoadError::SystemError {
+                kind: ErrorKind::NotFound,
+                ..
+            })
+        ));
+        assert!(matches!(restore_backup(&path), Err(LoadError::NoBackup)));
+
+        write(&path, "{\"version\": 1}".to_string(), false, 0).unwrap();
+        let error = load(&path).unwrap_err();
+        assert!(matches!(
+            error,
+            LoadError::DeserializeError { line: "header", .. }
+        ));
+        assert!(error.to_string().starts_with("broken header: "));
+    }
+}
@@ -0,0 +1,268 @@
+use crate::assets::Assets;
+use crate::colors::Colors;
+use crate::human::attributes::{
+    Attribute, Attributes, ATTRIBUTE_POINTS, MAX_ATTRIBUTE, MIN_ATTRIBUTE,
+};
+use crate::human::skills::{Skill, Skills, MAX_START_SKILL, SKILL_POINTS};
+use crate::savefile::SaveFile;
+use crate::scenes::{bg, easy_back, Scene, Transition};
+use crate::ui::{Button, Horizontal, Label, Position, UiSprite, Vertical};
+use enum_iterator::all;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tetra::input::{Key, KeyModifier};
+use tetra::{window, Context, Event};
+
+const ROW_HEIGHT: f32 = 40.0;
+
+/// Points-buy step of the character creation
+pub struct CharacterAbilities {
+    savefile: SaveFile,
+    sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+    attributes: Attributes,
+    skills: Skills,
+    /// Levels given by the background, they are free but can't be lowered
+    base_skills: Skills,
+    attributes_title: Rc<RefCell<Label>>,
+    skills_title: Rc<RefCell<Label>>,
+    attribute_labels: Vec<Rc<RefCell<Label>>>,
+    skill_labels: Vec<Rc<RefCell<Label>>>,
+}
+
+fn attributes_title(attributes: &Attributes) -> String {
+    format!(
+        "Attributes, {} points left:",
+        ATTRIBUTE_POINTS - attributes.points_spent()
+    )
+}
+
+fn skills_title(skills: &Skills, base: &Skills) -> String {
+    format!(
+        "Skills, {} points left:",
+        SKILL_POINTS + base.points_spent() - skills.points_spent()
+    )
+}
+
+impl CharacterAbilities {
+    pub fn new(savefile: SaveFile, assets: &Rc<Assets>) -> Self {
+        let attributes = Attributes::default();
+        let base_skills = savefile
+            .character
+            .as_ref()
+            .map(|c| c.skills.clone())
+            .unwrap_or_default();
+        let skills = base_skills.clone();
+        let title = Rc::new(RefCell::new(Label::new(
+            format!("Abilities of {}:", savefile.character_name()),
+            assets.fonts.handel32.clone(),
+            Colors::ORANGE_RED,
+            Position {
+                x: Horizontal::AtWindowCenterByCenter { offset: 0.0 },
+                y: Vertical::AtWindowCenterByBottom { offset: -220.0 },
+            },
+        )));
+        let attributes_title = Rc::new(RefCell::new(Label::new(
+            attributes_title(&attributes),
+            assets.fonts.handel24.clone(),
+            Colors::ORANGE,
+            Position {
+                x: Horizontal::AtWindowCenterByCenter { offset: 0.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -170.0 },
+            },
+        )));
+        let skills_title = Rc::new(RefCell::new(Label::new(
+            skills_title(&skills, &base_skills),
+            assets.fonts.handel24.clone(),
+            Colors::ORANGE,
+            Position {
+                x: Horizontal::AtWindowCenterByCenter { offset: 0.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 45.0 },
+            },
+        )));
+        let mut sprites: Vec<Rc<RefCell<dyn UiSprite>>> = vec![
+            bg(assets),
+            title,
+            attributes_title.clone(),
+            skills_title.clone(),
+        ];
+
+        let mut row = |name: &str, value: u8, event: String, y: f32| {
+            sprites.push(Rc::new(RefCell::new(Label::new(
+                name,
+                assets.fonts.handel24.clone(),
+                Colors::ORANGE,
+                Position {
+                    x: Horizontal::AtWindowCenterByRight { offset: -30.0 },
+                    y: Vertical::AtWindowCenterByCenter { offset: y },
+                },
+            ))));
+            sprites.push(Rc::new(RefCell::new(Button::icon(
+                vec![],
+                '-',
+                assets.clone(),
+                2.0,
+                Position {
+                    x: Horizontal::AtWindowCenterByLeft { offset: 0.0 },
+                    y: Vertical::AtWindowCenterByCenter { offset: y },
+                },
+                Transition::CustomEvent(format!("{}:-", event)),
+            ))));
+            let label = Rc::new(RefCell::new(Label::new(
+                value.to_string(),
+                assets.fonts.handel24.clone(),
+                Colors::ORANGE_RED,
+                Position {
+                    x: Horizontal::AtWindowCenterByCenter { offset: 70.0 },
+                    y: Vertical::AtWindowCenterByCenter { offset: y },
+                },
+            )));
+            sprites.push(label.clone());
+            sprites.push(Rc::new(RefCell::new(Button::icon(
+                vec![],
+                '+',
+                assets.clone(),
+                2.0,
+                Position {
+                    x: Horizontal::AtWindowCenterByRight { offset: 140.0 },
+                    y: Vertical::AtWindowCenterByCenter { offset: y },
+                },
+                Transition::CustomEvent(format!("{}:+", event)),
+            ))));
+            label
+        };
+        let attribute_labels = all::<Attribute>()
+            .enumerate()
+            .map(|(i, a)| {
+                row(
+                    a.name(),
+                    attributes.get(a),
+                    format!("attribute:{}", i),
+                    -120.0 + ROW_HEIGHT * i as f32,
+                )
+            })
+            .collect();
+        let skill_labels = all::<Skill>()
+            .enumerate()
+            .map(|(i, s)| {
+                row(
+                    s.name(),
+                    skills.level(s),
+                    format!("skill:{}", i),
+                    85.0 + ROW_HEIGHT * i as f32,
+                )
+            })
+            .collect();
+
+        sprites.push(Rc::new(RefCell::new(Button::text(
+            vec![(Key::Escape, None)],
+            "[Esc] Back",
+            assets.fonts.consolab18.clone(),
+            Position {
+                x: Horizontal::AtWindowCenterByRight { offset: -10.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 290.0 },
+            },
+            Transition::Pop,
+        ))));
+        sprites.push(Rc::new(RefCell::new(Button::text(
+            vec![(Key::Enter, Some(KeyModifier::Alt))],
+            "[Alt+Enter] Create",
+            assets.fonts.consolab18.clone(),
+            Position {
+                x: Horizontal::AtWindowCenterByLeft { offset: 10.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 290.0 },
+            },
+            Transition::CustomEvent("create".to_string()),
+        ))));
+
+        Self {
+            savefile,
+            sprites,
+            attributes,
+            skills,
+            base_skills,
+            attributes_title,
+            skills_title,
+            attribute_labels,
+            skill_labels,
+        }
+    }
+
+    fn change_attribute(&mut self, index: usize, up: bool) {
+        let attribute = all::<Attribute>().nth(index).unwrap();
+        let value = self.attributes.get(attribute);
+        if up {
+            if value < MAX_ATTRIBUTE && self.attributes.points_spent() < ATTRIBUTE_POINTS {
+                self.attributes.set(attribute, value + 1);
+            }
+        } else if value > MIN_ATTRIBUTE {
+            self.attributes.set(attribute, value - 1);
+        }
+    }
+
+    fn change_skill(&mut self, index: usize, up: bool) {
+        let skill = all::<Skill>().nth(index).unwrap();
+        let level = self.skills.level(skill);
+        let spent = self.skills.points_spent() - self.base_skills.points_spent();
+        if up {
+            if level < MAX_START_SKILL && spent < SKILL_POINTS {
+                self.skills.set_level(skill, level + 1);
+            }
+        } else if level > self.base_skills.level(skill) {
+            self.skills.set_level(skill, level - 1);
+        }
+    }
+
+    fn update_labels(&mut self, ctx: &mut Context) {
+        let window_size = window::get_size(ctx);
+        self.attributes_title.borrow_mut().update(
+            attributes_title(&self.attributes),
+            ctx,
+            window_size,
+        );
+        self.skills_title.borrow_mut().update(
+            skills_title(&self.skills, &self.base_skills),
+            ctx,
+            window_size,
+        );
+        for (attribute, label) in all::<Attribute>().zip(self.attribute_labels.iter()) {
+            label
+                .borrow_mut()
+                .update(self.attributes.get(attribute).to_string(), ctx, window_size);
+        }
+        for (skill, label) in all::<Skill>().zip(self.skill_labels.iter()) {
+            label
+                .borrow_mut()
+                .update(self.skills.level(skill).to_string(), ctx, window_size);
+        }
+    }
+}
+
+impl Scene for CharacterAbilities {
+    fn event(&mut self, _ctx: &mut Context, event: Event, focused: bool) -> Transition {
+        easy_back(event, focused).unwrap_or(Transition::DoNothing)
+    }
+
+    fn sprites(&mut self) -> Option<&Vec<Rc<RefCell<dyn UiSprite>>>> {
+        Some(&self.sprites)
+    }
+
+    fn custom_event(&mut self, ctx: &mut Context, event: &str) -> Option<Transition> {
+        if event == "create" {
+            let mut character = self.savefile.character.clone().unwrap();
+            character.attributes = self.attributes.clone();
+            character.skills = self.skills.clone();
+            self.savefile.set_character(character);
+            return Some(Transition::CreateWorld(self.savefile.clone()));
+        }
+        let mut parts = event.split(':');
+        let (kind, index, sign) = (parts.next()?, parts.next()?, parts.next()?);
+        let index = index.parse::<usize>().ok()?;
+        match kind {
+            "attribute" => self.change_attribute(index, sign == "+"),
+            "skill" => self.change_skill(index, sign == "+"),
+            _ => unreachable!(),
+        }
+        self.update_labels(ctx);
+        None
+    }
+}
@@ -1,12 +1,16 @@
 use crate::assets::Assets;
 use crate::colors::Colors;
+use crate::data::background::Background;
 use crate::data::game_data::GameData;
+use crate::data::species::Species;
+use crate::game::calendar::{timestamp, years_before};
+use crate::human::body::Body;
 use crate::human::character::Character;
 use crate::human::gender::Gender;
 use crate::human::main_hand::MainHand;
 use crate::human::skin_tone::SkinTone;
 use crate::savefile::SaveFile;
-use crate::scenes::{bg, easy_back, Scene, Transition};
+use crate::scenes::{bg, easy_back, GameScene, Scene, Transition};
 use crate::ui::{
     Button, Colorize, Draw, Horizontal, JustMesh, Label, Position, Positionate, Stringify,
     TextInput, UiSprite, Vertical,
@@ -19,19 +23,30 @@ use tetra::graphics::Rectangle;
 use tetra::input::{Key, KeyModifier};
 use tetra::{window, Context, Event};
 
+const MAX_CREW: u32 = 3;
+
 pub struct CreateCharacter {
     savefile: SaveFile,
     data: Rc<GameData>,
     sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
     name_input: Rc<RefCell<TextInput>>,
     name_empty: Rc<RefCell<Label>>,
+    species_label: Rc<RefCell<Label>>,
     gender_input: Rc<RefCell<TextInput>>,
     age_input: Rc<RefCell<TextInput>>,
+    crew_input: Rc<RefCell<TextInput>>,
     hand_label: Rc<RefCell<Label>>,
     skin_mesh: Rc<RefCell<JustMesh>>,
     skin_label: Rc<RefCell<Label>>,
+    background_label: Rc<RefCell<Label>>,
     main_hand: MainHand,
     skin_tone: SkinTone,
+    /// Ids sorted by name
+    species_ids: Vec<&'static str>,
+    species: usize,
+    /// Ids sorted by name
+    backgrounds: Vec<&'static str>,
+    background: usize,
 }
 
 impl CreateCharacter {
@@ -66,7 +81,7 @@ impl CreateCharacter {
             Colors::ORANGE,
             Position {
                 x: Horizontal::AtWindowCenterByRight { offset: -80.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: -125.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -145.0 },
             },
         )));
         let name_input = Rc::new(RefCell::new(TextInput::new(
@@ -75,7 +90,7 @@ impl CreateCharacter {
             assets.fonts.handel24.clone(),
             Position {
                 x: Horizontal::AtWindowCenterByLeft { offset: -70.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: -120.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -140.0 },
             },
         )));
         let name_empty = Rc::new(RefCell::new(Label::hidden(
@@ -86,16 +101,73 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByCenter {
                     offset: right_column_width / 2.0 - 70.0,
                 },
-                y: Vertical::AtWindowCenterByBottom { offset: -151.0 },
+                y: Vertical::AtWindowCenterByBottom { offset: -171.0 },
+            },
+        )));
+        let species_title = Rc::new(RefCell::new(Label::new(
+            "Species:",
+            assets.fonts.handel24.clone(),
+            Colors::ORANGE,
+            Position {
+                x: Horizontal::AtWindowCenterByRight { offset: -80.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -95.0 },
             },
         )));
+        let species_left = Rc::new(RefCell::new(Button::icon(
+            vec![],
+            '<',
+            assets.clone(),
+            2.0,
+            Position {
+                x: Horizontal::AtWindowCenterByLeft { offset: -70.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -90.0 },
+            },
+            Transition::CustomEvent("species:left".to_string()),
+        )));
+        let mut species: Vec<&Species> = data.species.values().collect();
+        species.sort_by(|a, b| a.name.cmp(&b.name));
+        let species_ids: Vec<&'static str> = species
+            .into_iter()
+            .map(|s| *data.species.get_key_value(s.id.as_str()).unwrap().0)
+            .collect();
+        let species_label = Rc::new(RefCell::new(Label::new(
+            species_ids
+                .first()
+                .map(|id| data.species[id].name.as_str())
+                .unwrap_or("Human"),
+            assets.fonts.handel24.clone(),
+            Colors::ORANGE_RED,
+            Position {
+                x: Horizontal::AtWindowCenterByCenter {
+                    offset: right_column_width / 2.0 - 70.0,
+                },
+                y: Vertical::AtWindowCenterByCenter { offset: -95.0 },
+            },
+        )));
+        let species_right = Rc::new(RefCell::new(Button::icon(
+            vec![],
+            '>',
+            assets.clone(),
+            2.0,
+            Position {
+                x: Horizontal::AtWindowCenterByRight {
+                    offset: right_column_width - 70.0,
+                },
+                y: Vertical::AtWindowCenterByCenter { offset: -90.0 },
+            },
+            Transition::CustomEvent("species:right".to_string()),
+        )));
+        let body = species_ids
+            .first()
+            .map(|id| Body::from(&data.species[id]))
+            .unwrap_or_default();
         let gender_label = Rc::new(RefCell::new(Label::new(
             "Gender:",
             assets.fonts.handel24.clone(),
             Colors::ORANGE,
             Position {
                 x: Horizontal::AtWindowCenterByRight { offset: -80.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: -55.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -45.0 },
             },
         )));
         let gender_left = Rc::new(RefCell::new(Button::icon(
@@ -105,7 +177,7 @@ impl CreateCharacter {
             2.0,
             Position {
                 x: Horizontal::AtWindowCenterByLeft { offset: -70.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: -50.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -40.0 },
             },
             Transition::CustomEvent("gender:left".to_string()),
         )));
@@ -117,7 +189,7 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByCenter {
                     offset: right_column_width / 2.0 - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: -50.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -40.0 },
             },
         )));
         let gender_right = Rc::new(RefCell::new(Button::icon(
@@ -129,7 +201,7 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByRight {
                     offset: right_column_width - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: -50.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: -40.0 },
             },
             Transition::CustomEvent("gender:right".to_string()),
         )));
@@ -139,7 +211,7 @@ impl CreateCharacter {
             Colors::ORANGE,
             Position {
                 x: Horizontal::AtWindowCenterByRight { offset: -80.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: 15.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 5.0 },
             },
         )));
         let age_left = Rc::new(RefCell::new(Button::icon(
@@ -149,20 +221,22 @@ impl CreateCharacter {
             2.0,
             Position {
                 x: Horizontal::AtWindowCenterByLeft { offset: -70.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: 20.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 10.0 },
             },
             Transition::CustomEvent("age:left".to_string()),
         )));
         let age_input = Rc::new(RefCell::new(TextInput::int(
-            18,
-            (15, 199),
+            (body.adult_age as u32 + 2)
+                .max(18)
+                .min(body.lifespan as u32),
+            (body.adult_age as u32, body.lifespan as u32),
             right_column_width - 100.0,
             assets.fonts.handel24.clone(),
             Position {
                 x: Horizontal::AtWindowCenterByCenter {
                     offset: right_column_width / 2.0 - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: 20.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 10.0 },
             },
         )));
         let age_right = Rc::new(RefCell::new(Button::icon(
@@ -174,7 +248,7 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByRight {
                     offset: right_column_width - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: 20.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 10.0 },
             },
             Transition::CustomEvent("age:right".to_string()),
         )));
@@ -184,7 +258,7 @@ impl CreateCharacter {
             Colors::ORANGE,
             Position {
                 x: Horizontal::AtWindowCenterByRight { offset: -80.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: 85.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 55.0 },
             },
         )));
         let hand_left = Rc::new(RefCell::new(Button::icon(
@@ -194,7 +268,7 @@ impl CreateCharacter {
             2.0,
             Position {
                 x: Horizontal::AtWindowCenterByLeft { offset: -70.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: 90.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 60.0 },
             },
             Transition::CustomEvent("hand:left".to_string()),
         )));
@@ -207,7 +281,7 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByCenter {
                     offset: right_column_width / 2.0 - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: 85.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 55.0 },
             },
         )));
         let hand_right = Rc::new(RefCell::new(Button::icon(
@@ -219,7 +293,7 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByRight {
                     offset: right_column_width - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: 90.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 60.0 },
             },
             Transition::CustomEvent("hand:right".to_string()),
         )));
@@ -229,7 +303,7 @@ impl CreateCharacter {
             Colors::ORANGE,
             Position {
                 x: Horizontal::AtWindowCenterByRight { offset: -80.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: 155.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 105.0 },
             },
         )));
         let skin_left = Rc::new(RefCell::new(Button::icon(
@@ -239,7 +313,7 @@ impl CreateCharacter {
             2.0,
             Position {
                 x: Horizontal::AtWindowCenterByLeft { offset: -70.0 },
-                y: Vertical::AtWindowCenterByCenter { offset: 160.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 110.0 },
             },
             Transition::CustomEvent("skin:left".to_string()),
         )));
@@ -258,7 +332,7 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByCenter {
                     offset: right_column_width / 2.0 - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: 160.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 110.0 },
             },
         )));
         let skin_label = Rc::new(RefCell::new(Label::new(
@@ -269,7 +343,7 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByCenter {
                     offset: right_column_width / 2.0 - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: 155.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 105.0 },
             },
         )));
         let skin_right = Rc::new(RefCell::new(Button::icon(
@@ -281,10 +355,109 @@ impl CreateCharacter {
                 x: Horizontal::AtWindowCenterByRight {
                     offset: right_column_width - 70.0,
                 },
-                y: Vertical::AtWindowCenterByCenter { offset: 160.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 110.0 },
             },
             Transition::CustomEvent("skin:right".to_string()),
         )));
+        let background_title = Rc::new(RefCell::new(Label::new(
+            "Background:",
+            assets.fonts.handel24.clone(),
+            Colors::ORANGE,
+            Position {
+                x: Horizontal::AtWindowCenterByRight { offset: -80.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 155.0 },
+            },
+        )));
+        let background_left = Rc::new(RefCell::new(Button::icon(
+            vec![],
+            '<',
+            assets.clone(),
+            2.0,
+            Position {
+                x: Horizontal::AtWindowCenterByLeft { offset: -70.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 160.0 },
+            },
+            Transition::CustomEvent("background:left".to_string()),
+        )));
+        let mut backgrounds: Vec<&Background> = data.backgrounds.values().collect();
+        backgrounds.sort_by(|a, b| a.name.cmp(&b.name));
+        let backgrounds: Vec<&'static str> = backgrounds
+            .into_iter()
+            .map(|b| *data.backgrounds.get_key_value(b.id.as_str()).unwrap().0)
+            .collect();
+        let background_label = Rc::new(RefCell::new(Label::new(
+            backgrounds
+                .first()
+                .map(|id| data.backgrounds[id].name.as_str())
+                .unwrap_or("None"),
+            assets.fonts.handel24.clone(),
+            Colors::ORANGE_RED,
+            Position {
+                x: Horizontal::AtWindowCenterByCenter {
+                    offset: right_column_width / 2.0 - 70.0,
+                },
+                y: Vertical::AtWindowCenterByCenter { offset: 155.0 },
+            },
+        )));
+        let background_right = Rc::new(RefCell::new(Button::icon(
+            vec![],
+            '>',
+            assets.clone(),
+            2.0,
+            Position {
+                x: Horizontal::AtWindowCenterByRight {
+                    offset: right_column_width - 70.0,
+                },
+                y: Vertical::AtWindowCenterByCenter { offset: 160.0 },
+            },
+            Transition::CustomEvent("background:right".to_string()),
+        )));
+        let crew_label = Rc::new(RefCell::new(Label::new(
+            "Crew:",
+            assets.fonts.handel24.clone(),
+            Colors::ORANGE,
+            Position {
+                x: Horizontal::AtWindowCenterByRight { offset: -80.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 205.0 },
+            },
+        )));
+        let crew_left = Rc::new(RefCell::new(Button::icon(
+            vec![],
+            '-',
+            assets.clone(),
+            2.0,
+            Position {
+                x: Horizontal::AtWindowCenterByLeft { offset: -70.0 },
+                y: Vertical::AtWindowCenterByCenter { offset: 210.0 },
+            },
+            Transition::CustomEvent("crew:left".to_string()),
+        )));
+        // random characters joining the player's one
+        let crew_input = Rc::new(RefCell::new(TextInput::int(
+            0,
+            (0, MAX_CREW),
+            right_column_width - 100.0,
+            assets.fonts.handel24.clone(),
+            Position {
+                x: Horizontal::AtWindowCenterByCenter {
+                    offset: right_column_width / 2.0 - 70.0,
+                },
+                y: Vertical::AtWindowCenterByCenter { offset: 210.0 },
+            },
+        )));
+        let crew_right = Rc::new(RefCell::new(Button::icon(
+            vec![],
+            '+',
+            assets.clone(),
+            2.0,
+            Position {
+                x: Horizontal::AtWindowCenterByRight {
+                    offset: right_column_width - 70.0,
+                },
+                y: Vertical::AtWindowCenterByCenter { offset: 210.0 },
+            },
+            Transition::CustomEvent("crew:right".to_string()),
+        )));
         let randomize_btn = Rc::new(RefCell::new(Button::text(
             vec![
                 (Key::NumPadMultiply, None),
@@ -294,7 +467,7 @@ impl CreateCharacter {
             assets.fonts.consolab18.clone(),
             Position {
                 x: Horizontal::AtWindowCenterByLeft { offset: -100.0 },
-                y: Vertical::AtWindowCenterByTop { offset: 220.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 260.0 },
             },
             Transition::CustomEvent("randomize".to_string()),
         )));
@@ -305,22 +478,22 @@ impl CreateCharacter {
             assets.fonts.consolab18.clone(),
             Position {
                 x: Horizontal::AtWindowCenterByRight { offset: -110.0 },
-                y: Vertical::AtWindowCenterByTop { offset: 220.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 260.0 },
             },
             Transition::Pop,
         )));
         // TODO: add next stage for selecting scenario and ship name
-        let create_btn = Rc::new(RefCell::new(Button::text(
+        let next_btn = Rc::new(RefCell::new(Button::text(
             vec![(Key::Enter, Some(KeyModifier::Alt))],
-            "[Alt+Enter] Create",
+            "[Alt+Enter] Next",
             assets.fonts.consolab18.clone(),
             Position {
                 x: Horizontal::AtWindowCenterByLeft {
                     offset: randomize_size.x - 90.0,
                 },
-                y: Vertical::AtWindowCenterByTop { offset: 220.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 260.0 },
             },
-            Transition::CustomEvent("create".to_string()),
+            Transition::CustomEvent("next".to_string()),
         )));
 
         Self {
@@ -333,6 +506,10 @@ impl CreateCharacter {
                 name_label,
                 name_input.clone(),
                 name_empty.clone(),
+                species_title,
+                species_left,
+                species_label.clone(),
+                species_right,
                 gender_label,
                 gender_left,
                 gender_input.clone(),
@@ -350,21 +527,85 @@ impl CreateCharacter {
                 skin_mesh.clone(),
                 skin_label.clone(),
                 skin_right,
+                background_title,
+                background_left,
+                background_label.clone(),
+                background_right,
+                crew_label,
+                crew_left,
+                crew_input.clone(),
+                crew_right,
                 back_btn,
                 randomize_btn,
-                create_btn,
+                next_btn,
             ],
             name_input,
             name_empty,
+            species_label,
             gender_input,
             age_input,
+            crew_input,
             hand_label,
             skin_mesh,
             skin_label,
+            background_label,
             main_hand,
             skin_tone,
+            species_ids,
+            species: 0,
+            backgrounds,
+            background: 0,
+        }
+    }
+}
+
+impl CreateCharacter {
+    fn current_species(&self) -> Option<&Species> {
+        self.species_ids
+            .get(self.species)
+            .map(|id| &self.data.species[id])
+    }
+
+    /// Keeps gender, skin tone and age valid for the selected species
+    fn update_species(&mut self, ctx: &mut Context) {
+        let species = match self.current_species() {
+            Some(species) => species,
+            None => return,
+        };
+        let name = species.name.clone();
+        let genders = species.genders.clone();
+        let skin_tones = species.skin_tones.clone();
+        let clamps = (species.adult_age as u32, species.lifespan as u32);
+        self.species_label
+            .borrow_mut()
+            .update(name, ctx, window::get_size(ctx));
+        let mut gender = self.gender_input.borrow_mut();
+        if !genders.contains(&gender.value()) {
+            gender.set_value(genders[0].as_str());
+        }
+        self.age_input.borrow_mut().set_clamps(clamps);
+        if !skin_tones.contains(&self.skin_tone) {
+            self.skin_tone = skin_tones[0];
+            self.update_skin(ctx);
         }
     }
+
+    fn update_skin(&self, ctx: &mut Context) {
+        self.skin_mesh.borrow_mut().set_color(self.skin_tone);
+        let mut label = self.skin_label.borrow_mut();
+        label.set_value(self.skin_tone.name());
+        label.set_color(self.skin_tone.text_color());
+        label.positionate(ctx, window::get_size(ctx));
+    }
+
+    fn update_background(&self, ctx: &mut Context) {
+        let id = self.backgrounds[self.background];
+        self.background_label.borrow_mut().update(
+            self.data.backgrounds[id].name.as_str(),
+            ctx,
+            window::get_size(ctx),
+        );
+    }
 }
 
 impl Scene for CreateCharacter {
@@ -385,7 +626,7 @@ impl Scene for CreateCharacter {
 
     fn custom_event(&mut self, ctx: &mut Context, event: &str) -> Option<Transition> {
         match event {
-            "create" => {
+            "next" => {
                 let name = self.name_input.borrow().value();
                 if name.is_empty() {
                     self.name_input.borrow_mut().set_danger(true);
@@ -394,47 +635,106 @@ impl Scene for CreateCharacter {
                 }
 
                 let gender = self.gender_input.borrow().value();
-                let age = self.age_input.borrow().value().parse::<u8>().unwrap();
-                let character = Character::new(
+                let age = self.age_input.borrow().value().parse::<u32>().unwrap();
+                let now = timestamp(self.savefile.current_tick);
+                let mut character = Character::new(
                     name,
                     Gender::from(gender),
-                    age,
+                    years_before(now, age),
                     self.main_hand,
                     self.skin_tone,
                 );
-                self.savefile.set_character(character);
-                Some(Transition::CreateWorld(self.savefile.clone()))
+                if let Some(species) = self.current_species() {
+                    character = character.with_species(species);
+                }
+                if let Some(id) = self.backgrounds.get(self.background) {
+                    character = character.with_background(&self.data.backgrounds[id]);
+                }
+                let crew_size = self.crew_input.borrow().value().parse::<u8>().unwrap();
+                let mut rng = rand::thread_rng();
+                let crew = (0..crew_size)
+                    .map(|_| Character::random(&mut rng, &self.data, now))
+                    .collect();
+                self.savefile.set_character(character).set_crew(crew);
+                Some(Transition::Push(GameScene::CharacterAbilities(
+                    self.savefile.clone(),
+                )))
             }
             "randomize" => {
                 let mut rng = rand::thread_rng();
                 let window_size = window::get_size(ctx);
-                let character = Character::random(&mut rng, &self.data);
-                self.name_input.borrow_mut().set_value(character.name);
-                self.gender_input.borrow_mut().set_value(character.gender);
+                let now = timestamp(self.savefile.current_tick);
+                let character = Character::random(&mut rng, &self.data, now);
+                if let Some(i) = self
+                    .species_ids
+                    .iter()
+                    .position(|s| *s == character.body.species)
+                {
+                    self.species = i;
+                    self.update_species(ctx);
+                }
                 self.age_input
                     .borrow_mut()
-                    .set_value(character.age.to_string());
+                    .set_value(character.age(now).to_string());
+                self.name_input.borrow_mut().set_value(character.name);
+                self.gender_input.borrow_mut().set_value(character.gender);
                 self.main_hand = character.main_hand;
                 let mut hand = self.hand_label.borrow_mut();
                 hand.set_value(self.main_hand.name());
                 hand.positionate(ctx, window_size);
                 self.skin_tone = character.skin_tone;
-                let mut skin = self.skin_label.borrow_mut();
-                skin.set_value(self.skin_tone.name());
-                skin.set_color(self.skin_tone.text_color());
-                skin.positionate(ctx, window_size);
-                self.skin_mesh.borrow_mut().set_color(self.skin_tone);
+                self.update_skin(ctx);
+                if let Some(i) = character
+                    .background
+                    .and_then(|id| self.backgrounds.iter().position(|b| *b == id))
+                {
+                    self.background = i;
+                    self.update_background(ctx);
+                }
+                None
+            }
+            "background:left" | "background:right" => {
+                if !self.backgrounds.is_empty() {
+                    let count = self.backgrounds.len();
+                    self.background = if event == "background:right" {
+                        (self.background + 1) % count
+                    } else {
+                        (self.background + count - 1) % count
+                    };
+                    self.update_background(ctx);
+                }
+                None
+            }
+            "species:left" | "species:right" => {
+                if !self.species_ids.is_empty() {
+                    let count = self.species_ids.len();
+                    self.species = if event == "species:right" {
+                        (self.species + 1) % count
+                    } else {
+                        (self.species + count - 1) % count
+                    };
+                    self.update_species(ctx);
+                }
                 None
             }
             "gender:left" | "gender:right" => {
+                let genders = self
+                    .current_species()
+                    .map(|s| s.genders.clone())
+                    .unwrap_or_else(|| vec!["Male".to_string(), "Female".to_string()]);
                 let mut input = self.gender_input.borrow_mut();
-                let value = input.value();
-                input.set_value(if value == "Male" { "Female" } else { "Male" });
+                let count = genders.len();
+                let i = match genders.iter().position(|g| *g == input.value()) {
+                    Some(i) if event == "gender:right" => (i + 1) % count,
+                    Some(i) => (i + count - 1) % count,
+                    None => 0,
+                };
+                input.set_value(genders[i].as_str());
                 None
             }
             "age:left" | "age:right" => {
                 let mut input = self.age_input.borrow_mut();
-                if let Ok(mut value) = input.value().parse::<u8>() {
+                if let Ok(mut value) = input.value().parse::<u32>() {
                     if event == "age:right" {
                         value += 1;
                     } else {
@@ -444,6 +744,18 @@ impl Scene for CreateCharacter {
                 }
                 None
             }
+            "crew:left" | "crew:right" => {
+                let mut input = self.crew_input.borrow_mut();
+                if let Ok(mut value) = input.value().parse::<u8>() {
+                    if event == "crew:right" {
+                        value = (value + 1).min(MAX_CREW as u8);
+                    } else {
+                        value = value.saturating_sub(1);
+                    }
+                    input.set_value(value.to_string());
+                }
+                None
+            }
             "hand:left" | "hand:right" => {
                 let mut label = self.hand_label.borrow_mut();
                 self.main_hand = if event == "hand:right" {
@@ -456,16 +768,21 @@ impl Scene for CreateCharacter {
                 None
             }
             "skin:left" | "skin:right" => {
-                self.skin_tone = if event == "skin:right" {
-                    self.skin_tone.next()
-                } else {
-                    self.skin_tone.prev()
+                let tones = self.current_species().map(|s| s.skin_tones.clone());
+                self.skin_tone = match tones {
+                    Some(tones) => {
+                        let count = tones.len();
+                        let i = tones.iter().position(|t| *t == self.skin_tone);
+                        tones[match i {
+                            Some(i) if event == "skin:right" => (i + 1) % count,
+                            Some(i) => (i + count - 1) % count,
+                            None => 0,
+                        }]
+                    }
+                    None if event == "skin:right" => self.skin_tone.next(),
+                    None => self.skin_tone.prev(),
                 };
-                self.skin_mesh.borrow_mut().set_color(self.skin_tone);
-                let mut label = self.skin_label.borrow_mut();
-                label.set_value(self.skin_tone.name());
-                label.set_color(self.skin_tone.text_color());
-                label.positionate(ctx, window::get_size(ctx));
+                self.update_skin(ctx);
                 None
             }
             _ => unreachable!(),
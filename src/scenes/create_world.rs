@@ -3,12 +3,13 @@ use crate::astro::galaxy::GalaxyMeta;
 use crate::astro::galaxy_class::GalaxyClass;
 use crate::astro::galaxy_generator;
 use crate::astro::galaxy_size::GalaxySize;
+use crate::astro::spiral_arms::SpiralArms;
 use crate::colors::Colors;
-use crate::savefile::{create, SaveError};
+use crate::savefile::{create, is_valid_slot, SaveError, SaveFile, WorldError};
 use crate::scenes::{bg, easy_back, Scene, Transition};
 use crate::ui::{
-    Button, Draw, Galaxy, Horizontal, Label, Position, Positionate, Stringify, TextInput, UiSprite,
-    Vertical,
+    Button, Disable, Draw, Galaxy, Horizontal, Label, Position, Positionate, Stringify, TextInput,
+    UiSprite, Vertical,
 };
 use rand::distributions::Standard;
 use rand::{thread_rng, Rng};
@@ -39,6 +40,7 @@ pub struct CreateWorld {
     name_input: Rc<RefCell<TextInput>>,
     name_empty: Rc<RefCell<Label>>,
     name_error: Rc<RefCell<Label>>,
+    name_invalid: Rc<RefCell<Label>>,
     seed_input: Rc<RefCell<TextInput>>,
     seed_error: Rc<RefCell<Label>>,
     size_name: Rc<RefCell<Label>>,
@@ -46,6 +48,20 @@ pub struct CreateWorld {
     preview: Rc<RefCell<Galaxy>>,
     galaxy_size: GalaxySize,
     galaxy_class: GalaxyClass,
+    arms_sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+    arms_buttons: Vec<Rc<RefCell<Button>>>,
+    arms_count: Rc<RefCell<Label>>,
+    arms_windings: Rc<RefCell<Label>>,
+    arms_width: Rc<RefCell<Label>>,
+    spiral_arms: SpiralArms,
+}
+
+fn windings_text(arms: &SpiralArms) -> String {
+    format!("{:.2}", arms.windings)
+}
+
+fn width_text(arms: &SpiralArms) -> String {
+    format!("{:.1}%", arms.width * 100.0)
 }
 
 impl CreateWorld {
@@ -90,6 +106,17 @@ impl CreateWorld {
                 y: Vertical::AtWindowCenterByBottom { offset: -151.0 },
             },
         )));
+        let name_invalid = Rc::new(RefCell::new(Label::hidden(
+            "World name can't be only dots and slashes!",
+            assets.fonts.consolab18.clone(),
+            Colors::RED,
+            Position {
+                x: Horizontal::AtWindowCenterByCenter {
+                    offset: right_column_width / 2.0 - 50.0,
+                },
+                y: Vertical::AtWindowCenterByBottom { offset: -151.0 },
+            },
+        )));
         let name_empty = Rc::new(RefCell::new(Label::hidden(
             "World name shall not be empty!",
             assets.fonts.consolab18.clone(),
@@ -220,6 +247,81 @@ impl CreateWorld {
             },
             Transition::CustomEvent("class:right".to_string()),
         )));
+        let spiral_arms = SpiralArms::default();
+        let arms_control = |name: &str, offset: f32, event: &str, value: String| {
+            let label = Rc::new(RefCell::new(Label::new(
+                name,
+                assets.fonts.consolab18.clone(),
+                Colors::ORANGE,
+                Position {
+                    x: Horizontal::AtWindowCenterByLeft { offset },
+                    y: Vertical::AtWindowCenterByCenter { offset: 150.0 },
+                },
+            )));
+            let left = Rc::new(RefCell::new(Button::icon(
+                vec![],
+                '<',
+                assets.clone(),
+                1.0,
+                Position {
+                    x: Horizontal::AtWindowCenterByLeft {
+                        offset: offset + 95.0,
+                    },
+                    y: Vertical::AtWindowCenterByCenter { offset: 150.0 },
+                },
+                Transition::CustomEvent(format!("{}:left", event)),
+            )));
+            let value = Rc::new(RefCell::new(Label::new(
+                value,
+                assets.fonts.consolab18.clone(),
+                Colors::DARK_ORANGE,
+                Position {
+                    x: Horizontal::AtWindowCenterByCenter {
+                        offset: offset + 150.0,
+                    },
+                    y: Vertical::AtWindowCenterByCenter { offset: 150.0 },
+                },
+            )));
+            let right = Rc::new(RefCell::new(Button::icon(
+                vec![],
+                '>',
+                assets.clone(),
+                1.0,
+                Position {
+                    x: Horizontal::AtWindowCenterByLeft {
+                        offset: offset + 185.0,
+                    },
+                    y: Vertical::AtWindowCenterByCenter { offset: 150.0 },
+                },
+                Transition::CustomEvent(format!("{}:right", event)),
+            )));
+            (label, left, value, right)
+        };
+        let (count_label, count_left, arms_count, count_right) =
+            arms_control("Arms:", -330.0, "arms", spiral_arms.count.to_string());
+        let (windings_label, windings_left, arms_windings, windings_right) =
+            arms_control("Windings:", -80.0, "windings", windings_text(&spiral_arms));
+        let (width_label, width_left, arms_width, width_right) =
+            arms_control("Width:", 170.0, "width", width_text(&spiral_arms));
+        let arms_buttons = vec![
+            count_left,
+            count_right,
+            windings_left,
+            windings_right,
+            width_left,
+            width_right,
+        ];
+        let mut arms_sprites: Vec<Rc<RefCell<dyn UiSprite>>> = vec![
+            count_label,
+            arms_count.clone(),
+            windings_label,
+            arms_windings.clone(),
+            width_label,
+            arms_width.clone(),
+        ];
+        for button in arms_buttons.iter() {
+            arms_sprites.push(button.clone());
+        }
         let randomize_btn = Rc::new(RefCell::new(Button::text(
             vec![
                 (Key::NumPadMultiply, None),
@@ -229,7 +331,7 @@ impl CreateWorld {
             assets.fonts.consolab18.clone(),
             Position {
                 x: Horizontal::AtWindowCenterByLeft { offset: -45.0 },
-                y: Vertical::AtWindowCenterByTop { offset: 150.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 215.0 },
             },
             Transition::CustomEvent("randomize".to_string()),
         )));
@@ -240,7 +342,7 @@ impl CreateWorld {
             assets.fonts.consolab18.clone(),
             Position {
                 x: Horizontal::AtWindowCenterByRight { offset: -55.0 },
-                y: Vertical::AtWindowCenterByTop { offset: 150.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 215.0 },
             },
             Transition::CustomEvent("preview".to_string()),
         )));
@@ -253,7 +355,7 @@ impl CreateWorld {
                 x: Horizontal::AtWindowCenterByRight {
                     offset: -65.0 - preview_size.x,
                 },
-                y: Vertical::AtWindowCenterByTop { offset: 150.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 215.0 },
             },
             Transition::Pop,
         )));
@@ -265,7 +367,7 @@ impl CreateWorld {
                 x: Horizontal::AtWindowCenterByLeft {
                     offset: randomize_size.x - 35.0,
                 },
-                y: Vertical::AtWindowCenterByTop { offset: 150.0 },
+                y: Vertical::AtWindowCenterByTop { offset: 215.0 },
             },
             Transition::CustomEvent("create".to_string()),
         )));
@@ -275,34 +377,38 @@ impl CreateWorld {
             assets.fonts.handel24.clone(),
             Position::center(),
         )));
+        let mut sprites: Vec<Rc<RefCell<dyn UiSprite>>> = vec![
+            bg(&assets),
+            title,
+            name_label,
+            name_input.clone(),
+            seed_label,
+            seed_input.clone(),
+            size_label,
+            size_left,
+            size_name.clone(),
+            size_right,
+            class_label,
+            class_left,
+            class_name.clone(),
+            class_right,
+            back_btn,
+            preview_btn,
+            randomize_btn,
+            create_btn,
+            name_error.clone(),
+            seed_error.clone(),
+            name_empty.clone(),
+            name_invalid.clone(),
+        ];
+        sprites.extend(arms_sprites.iter().cloned());
+        sprites.push(preview.clone());
         CreateWorld {
-            sprites: vec![
-                bg(&assets),
-                title,
-                name_label,
-                name_input.clone(),
-                seed_label,
-                seed_input.clone(),
-                size_label,
-                size_left,
-                size_name.clone(),
-                size_right,
-                class_label,
-                class_left,
-                class_name.clone(),
-                class_right,
-                back_btn,
-                preview_btn,
-                randomize_btn,
-                create_btn,
-                name_error.clone(),
-                seed_error.clone(),
-                name_empty.clone(),
-                preview.clone(),
-            ],
+            sprites,
             name_input,
             name_error,
             name_empty,
+            name_invalid,
             seed_input,
             seed_error,
             size_name,
@@ -310,8 +416,42 @@ impl CreateWorld {
             preview,
             galaxy_size,
             galaxy_class,
+            arms_sprites,
+            arms_buttons,
+            arms_count,
+            arms_windings,
+            arms_width,
+            spiral_arms,
+        }
+    }
+
+    fn update_class(&mut self, ctx: &mut Context) {
+        let window_size = window::get_size(ctx);
+        self.class_name
+            .borrow_mut()
+            .update(self.galaxy_class.name(), ctx, window_size);
+        let visible = self.galaxy_class.has_arms();
+        for sprite in self.arms_sprites.iter() {
+            sprite.borrow_mut().set_visible(visible);
+        }
+        for button in self.arms_buttons.iter() {
+            button.borrow_mut().set_disabled(!visible);
         }
     }
+
+    fn update_arms(&mut self, ctx: &mut Context) {
+        self.spiral_arms.validate();
+        let window_size = window::get_size(ctx);
+        self.arms_count
+            .borrow_mut()
+            .update(self.spiral_arms.count.to_string(), ctx, window_size);
+        self.arms_windings
+            .borrow_mut()
+            .update(windings_text(&self.spiral_arms), ctx, window_size);
+        self.arms_width
+            .borrow_mut()
+            .update(width_text(&self.spiral_arms), ctx, window_size);
+    }
 }
 
 impl Scene for CreateWorld {
@@ -319,6 +459,7 @@ impl Scene for CreateWorld {
         let name = self.name_input.borrow();
         let mut name_empty = self.name_empty.borrow_mut();
         let mut name_error = self.name_error.borrow_mut();
+        let mut name_invalid = self.name_invalid.borrow_mut();
         let seed = self.seed_input.borrow();
         let mut seed_error = self.seed_error.borrow_mut();
         if !name.danger() && name_empty.visible() {
@@ -327,6 +468,9 @@ impl Scene for CreateWorld {
         if !name.danger() && name_error.visible() {
             name_error.set_visible(false);
         }
+        if !name.danger() && name_invalid.visible() {
+            name_invalid.set_visible(false);
+        }
         if !seed.danger() && seed_error.visible() {
             seed_error.set_visible(false);
         }
@@ -359,24 +503,50 @@ impl Scene for CreateWorld {
             }
             "class:left" => {
                 self.galaxy_class = self.galaxy_class.prev();
-                let mut label = self.class_name.borrow_mut();
-                label.set_value(self.galaxy_class.name());
-                label.positionate(ctx, window::get_size(ctx));
+                self.update_class(ctx);
                 None
             }
             "class:right" => {
                 self.galaxy_class = self.galaxy_class.next();
-                let mut label = self.class_name.borrow_mut();
-                label.set_value(self.galaxy_class.name());
-                label.positionate(ctx, window::get_size(ctx));
+                self.update_class(ctx);
+                None
+            }
+            "arms:left" => {
+                self.spiral_arms.count = self.spiral_arms.count.saturating_sub(1);
+                self.update_arms(ctx);
+                None
+            }
+            "arms:right" => {
+                self.spiral_arms.count += 1;
+                self.update_arms(ctx);
+                None
+            }
+            "windings:left" => {
+                self.spiral_arms.windings -= 0.25;
+                self.update_arms(ctx);
+                None
+            }
+            "windings:right" => {
+                self.spiral_arms.windings += 0.25;
+                self.update_arms(ctx);
+                None
+            }
+            "width:left" => {
+                self.spiral_arms.width -= 0.005;
+                self.update_arms(ctx);
+                None
+            }
+            "width:right" => {
+                self.spiral_arms.width += 0.005;
+                self.update_arms(ctx);
                 None
             }
             "randomize" => {
                 let mut rng = thread_rng();
                 self.galaxy_class = rng.sample(Standard);
-                let mut label = self.class_name.borrow_mut();
-                label.set_value(self.galaxy_class.name());
-                label.positionate(ctx, window::get_size(ctx));
+                self.update_class(ctx);
+                self.spiral_arms = SpiralArms::random(&mut rng);
+                self.update_arms(ctx);
                 self.name_input
                     .borrow_mut()
                     .set_value(random_name(&mut rng));
@@ -395,7 +565,12 @@ impl Scene for CreateWorld {
                 self.preview.borrow_mut().redraw(
                     ctx,
                     size,
-                    galaxy_generator::generate_quadrants(seed, size, self.galaxy_class),
+                    galaxy_generator::generate_quadrants(
+                        seed,
+                        size,
+                        self.galaxy_class,
+                        &self.spiral_arms,
+                    ),
                     name.as_str(),
                 );
                 None
@@ -411,24 +586,34 @@ impl Scene for CreateWorld {
                     self.name_input.borrow_mut().set_danger(true);
                     self.name_empty.borrow_mut().set_visible(true);
                     None
+                } else if !is_valid_slot(&name) {
+                    self.name_input.borrow_mut().set_danger(true);
+                    self.name_invalid.borrow_mut().set_visible(true);
+                    None
                 } else {
-                    match create(GalaxyMeta::new(
+                    let meta = GalaxyMeta::new(
                         name,
                         seed,
                         self.galaxy_size,
                         self.galaxy_class,
-                    )) {
+                        self.spiral_arms,
+                    );
+                    match create(meta.clone()) {
                         Ok(_) => Some(Transition::Pop),
-                        Err(err) => {
-                            match err {
-                                SaveError::FileExists => {
-                                    self.name_input.borrow_mut().set_danger(true);
-                                    self.name_error.borrow_mut().set_visible(true);
-                                }
-                                _ => panic!("Can't create savefile: {:?}", err),
-                            }
+                        Err(SaveError::FileExists) => {
+                            self.name_input.borrow_mut().set_danger(true);
+                            self.name_error.borrow_mut().set_visible(true);
+                            None
+                        }
+                        Err(SaveError::InvalidName) => {
+                            self.name_input.borrow_mut().set_danger(true);
+                            self.name_invalid.borrow_mut().set_visible(true);
                             None
                         }
+                        Err(e) => Some(Transition::WorldError(WorldError::Save(
+                            SaveFile::from(meta).path,
+                            e,
+                        ))),
                     }
                 }
             }
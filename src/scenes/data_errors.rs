@@ -0,0 +1,83 @@
+use crate::assets::Assets;
+use crate::colors::Colors;
+use crate::data::data_error::DataError;
+use crate::scenes::{bg, Scene, Transition};
+use crate::ui::{Button, Horizontal, Label, Position, UiSprite, Vertical};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tetra::input::Key;
+
+/// How many errors fit the screen
+const MAX_LINES: usize = 15;
+
+/// Shown instead of main menu if game data is broken,
+/// or instead of the world if its mods can't be loaded
+pub struct DataErrors {
+    sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+}
+
+impl DataErrors {
+    pub fn new(assets: &Assets, errors: &[DataError], on_close: Transition) -> Self {
+        let mut sprites: Vec<Rc<RefCell<dyn UiSprite>>> = Vec::with_capacity(MAX_LINES + 4);
+        sprites.push(bg(assets));
+        sprites.push(Rc::new(RefCell::new(Label::new(
+            "Can't load game data:",
+            assets.fonts.handel32.clone(),
+            Colors::ORANGE_RED,
+            Position {
+                x: Horizontal::AtWindowCenterByCenter { offset: 0.0 },
+                y: Vertical::AtWindowCenterByBottom { offset: -200.0 },
+            },
+        ))));
+        let mut y = -170.0;
+        for error in errors.iter().take(MAX_LINES) {
+            sprites.push(Rc::new(RefCell::new(Label::new(
+                error.to_string(),
+                assets.fonts.consolab12.clone(),
+                if error.is_fatal() {
+                    Colors::RED
+                } else {
+                    Colors::LIGHT_YELLOW
+                },
+                Position {
+                    x: Horizontal::AtWindowCenterByCenter { offset: 0.0 },
+                    y: Vertical::AtWindowCenterByTop { offset: y },
+                },
+            ))));
+            y += 20.0;
+        }
+        if errors.len() > MAX_LINES {
+            sprites.push(Rc::new(RefCell::new(Label::new(
+                format!(
+                    "...and {} more, see console output",
+                    errors.len() - MAX_LINES
+                ),
+                assets.fonts.consolab12.clone(),
+                Colors::LIGHT_YELLOW,
+                Position {
+                    x: Horizontal::AtWindowCenterByCenter { offset: 0.0 },
+                    y: Vertical::AtWindowCenterByTop { offset: y },
+                },
+            ))));
+        }
+        let text = if matches!(on_close, Transition::Quit) {
+            "[x] Exit"
+        } else {
+            "[x] Back"
+        };
+        sprites.push(Rc::new(RefCell::new(Button::text(
+            vec![(Key::X, None), (Key::Escape, None)],
+            text,
+            assets.fonts.consolab18.clone(),
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 180.0 }),
+            on_close,
+        ))));
+        Self { sprites }
+    }
+}
+
+impl Scene for DataErrors {
+    fn sprites(&mut self) -> Option<&Vec<Rc<RefCell<dyn UiSprite>>>> {
+        Some(&self.sprites)
+    }
+}
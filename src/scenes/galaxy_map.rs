@@ -0,0 +1,332 @@
+use crate::assets::Assets;
+use crate::astro::factions::Territory;
+use crate::colors::Colors;
+use crate::game::world::{TravelError, World};
+use crate::input;
+use crate::scenes::{easy_back, Scene, Transition};
+use crate::ui::{Bg, Draw, Horizontal, Label, Position, StarMap, UiSprite, Vertical};
+use geometry::{Point, Vec2};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tetra::input::Key;
+use tetra::{window, Context, Event};
+
+const PAN_SPEED: f32 = 10.0;
+
+pub struct GalaxyMap {
+    world: Rc<RefCell<World>>,
+    sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+    map: Rc<RefCell<StarMap>>,
+    quadrant_info: Rc<RefCell<Label>>,
+    stars_info: Rc<RefCell<Label>>,
+    chunks_info: Rc<RefCell<Label>>,
+    territory_info: Rc<RefCell<Label>>,
+    destination_info: Rc<RefCell<Label>>,
+    hovered: Option<Point>,
+}
+
+fn territory_text(world: &World, point: Point) -> String {
+    let galaxy = &world.galaxy;
+    let name = |id: u8| galaxy.faction(id).map_or("", |f| f.name.as_str());
+    match galaxy.territory(point) {
+        Territory::Unclaimed => "Unclaimed space".to_string(),
+        Territory::Owned(id) => {
+            if galaxy.faction(id).map_or(false, |f| f.home == point) {
+                format!("Home of the {}", name(id))
+            } else {
+                format!("Territory of the {}", name(id))
+            }
+        }
+        Territory::Contested(a, b) => {
+            format!("Contested by the {} and the {}", name(a), name(b))
+        }
+    }
+}
+
+/// Voyages take from hours to years
+fn duration_text(ticks: u128) -> String {
+    let minutes = ticks / 60 / 60;
+    let hours = minutes / 60;
+    let days = hours / 24;
+    if days >= 365 {
+        format!("{}y {}d", days / 365, days % 365)
+    } else if days > 0 {
+        format!("{}d {}h", days, hours % 24)
+    } else {
+        format!("{}h {:02}m", hours, minutes % 60)
+    }
+}
+
+fn destination_text(world: &World) -> String {
+    if let Some(destination) = world.ship.destination {
+        let distance = world.ship.distance(destination);
+        let stats = world.ship.stats();
+        let eta = stats
+            .travel_time(distance)
+            .map_or("never".to_string(), duration_text);
+        format!(
+            "Destination: {}:{}, {:.1} quadrants away, {}, {:.0} of {:.0} fuel",
+            destination.x,
+            destination.y,
+            distance,
+            eta,
+            stats.fuel_needed(distance),
+            world.ship.fuel
+        )
+    } else {
+        format!(
+            "No destination, {:.0} fuel for {:.1} quadrants",
+            world.ship.fuel,
+            world.ship.stats().range(world.ship.fuel)
+        )
+    }
+}
+
+fn travel_error_text(error: TravelError) -> &'static str {
+    match error {
+        TravelError::NoDestination => "Can't travel: no destination",
+        TravelError::Docked => "Can't travel: ship is docked",
+        TravelError::AvatarNotAboard => "Can't travel without the captain",
+        TravelError::NoThrust => "Can't travel: engines are not working",
+        TravelError::NotEnoughFuel => "Can't travel: not enough fuel",
+    }
+}
+
+impl GalaxyMap {
+    pub fn new(world: Rc<RefCell<World>>, assets: &Assets, ctx: &mut Context) -> Self {
+        let bg = Rc::new(RefCell::new(Bg::new(Colors::SPACE_VIOLET)));
+        let map = {
+            let world = world.borrow();
+            Rc::new(RefCell::new(StarMap::new(
+                ctx,
+                &world.galaxy,
+                world.ship.quadrant,
+                world.ship.destination,
+            )))
+        };
+        let title = Rc::new(RefCell::new(Label::new(
+            format!("{} galaxy", world.borrow().galaxy.meta.name),
+            assets.fonts.handel24.clone(),
+            Colors::ORANGE,
+            Position::by_left_top(10.0, 10.0),
+        )));
+        let destination_info = Rc::new(RefCell::new(Label::new(
+            destination_text(&world.borrow()),
+            assets.fonts.handel16.clone(),
+            Colors::ORANGE,
+            Position::by_right_top(-10.0, 10.0),
+        )));
+        let info_label = |offset: f32| {
+            Rc::new(RefCell::new(Label::hidden(
+                "-",
+                assets.fonts.consolab18.clone(),
+                Colors::LIGHT_YELLOW,
+                Position {
+                    x: Horizontal::ByLeft { x: 10.0 },
+                    y: Vertical::AtWindowBottomByBottom { offset },
+                },
+            )))
+        };
+        let quadrant_info = info_label(-105.0);
+        let territory_info = info_label(-80.0);
+        let stars_info = info_label(-55.0);
+        let chunks_info = info_label(-30.0);
+        let help = Rc::new(RefCell::new(Label::new(
+            "[LMB] Set destination  [Enter] Travel  [Drag] Pan  [Wheel] Zoom  [c] Center on ship  [t] Territories  [Del] Clear destination  [Esc] Back",
+            assets.fonts.consolab12.clone(),
+            Colors::LIGHT_GRAY,
+            Position {
+                x: Horizontal::ByLeft { x: 10.0 },
+                y: Vertical::AtWindowBottomByBottom { offset: -10.0 },
+            },
+        )));
+        Self {
+            world,
+            sprites: vec![
+                bg,
+                map.clone(),
+                title,
+                destination_info.clone(),
+                quadrant_info.clone(),
+                territory_info.clone(),
+                stars_info.clone(),
+                chunks_info.clone(),
+                help,
+            ],
+            map,
+            quadrant_info,
+            stars_info,
+            chunks_info,
+            territory_info,
+            destination_info,
+            hovered: None,
+        }
+    }
+
+    fn update_hovered(&mut self, ctx: &mut Context) {
+        let window_size = window::get_size(ctx);
+        let labels = [
+            &self.quadrant_info,
+            &self.territory_info,
+            &self.stars_info,
+            &self.chunks_info,
+        ];
+        if let Some(point) = self.hovered {
+            let world = self.world.borrow();
+            let quadrant = world.galaxy.quadrant(point).unwrap();
+            let mut title = format!("Quadrant {}:{}", point.x, point.y);
+            if point == world.ship.quadrant {
+                title.push_str(" (you are here)");
+            } else if Some(point) == world.ship.destination {
+                title.push_str(" (destination)");
+            }
+            self.quadrant_info
+                .borrow_mut()
+                .update(title, ctx, window_size);
+            self.territory_info.borrow_mut().update(
+                territory_text(&world, point),
+                ctx,
+                window_size,
+            );
+            let mut stars = format!("{} stars", quadrant.stars_count);
+            if let Some(station) = world.station_at(point).map(|i| &world.stations[i]) {
+                stars.push_str(
+                    format!(
+                        ", {} ({})",
+                        station.name,
+                        station.kind.name().to_lowercase()
+                    )
+                    .as_str(),
+                );
+            }
+            self.stars_info.borrow_mut().update(stars, ctx, window_size);
+            self.chunks_info.borrow_mut().update(
+                format!(
+                    "{} chunks generated, {}",
+                    quadrant.generated_chunks(),
+                    if quadrant.visited {
+                        "visited"
+                    } else {
+                        "not visited yet"
+                    }
+                ),
+                ctx,
+                window_size,
+            );
+            for label in labels {
+                label.borrow_mut().set_visible(true);
+            }
+        } else {
+            for label in labels {
+                label.borrow_mut().set_visible(false);
+            }
+        }
+    }
+
+    fn travel(&mut self, ctx: &mut Context) {
+        let mut world = self.world.borrow_mut();
+        let age = world.avatar.character.age(world.timestamp());
+        let text = match world.travel() {
+            Ok(..) => {
+                let mut map = self.map.borrow_mut();
+                map.set_ship(world.ship.quadrant);
+                map.set_destination(None);
+                let now = world.timestamp();
+                let character = &world.avatar.character;
+                // long voyages take years
+                if character.age(now) > age {
+                    format!(
+                        "{}, {} is {} years old now",
+                        destination_text(&world),
+                        character.name,
+                        character.age(now)
+                    )
+                } else {
+                    destination_text(&world)
+                }
+            }
+            Err(e) => travel_error_text(e).to_string(),
+        };
+        self.destination_info
+            .borrow_mut()
+            .update(text, ctx, window::get_size(ctx));
+    }
+
+    fn set_destination(&mut self, ctx: &mut Context, destination: Option<Point>) {
+        let mut world = self.world.borrow_mut();
+        world.set_destination(destination);
+        self.map
+            .borrow_mut()
+            .set_destination(world.ship.destination);
+        self.destination_info.borrow_mut().update(
+            destination_text(&world),
+            ctx,
+            window::get_size(ctx),
+        );
+    }
+}
+
+impl Scene for GalaxyMap {
+    fn update(&mut self, ctx: &mut Context, focused: bool) -> Transition {
+        if focused {
+            return Transition::DoNothing;
+        }
+        {
+            let mut map = self.map.borrow_mut();
+            if let Some(dir) = input::get_direction_keys_down(ctx) {
+                map.pan(Vec2::new(
+                    -dir.dx() as f32 * PAN_SPEED,
+                    -dir.dy() as f32 * PAN_SPEED,
+                ));
+            }
+            if input::is_key_pressed(ctx, Key::Equals)
+                || input::is_key_pressed(ctx, Key::NumPadPlus)
+            {
+                map.zoom_centered(1.25);
+            } else if input::is_key_pressed(ctx, Key::Minus)
+                || input::is_key_pressed(ctx, Key::NumPadMinus)
+            {
+                map.zoom_centered(0.8);
+            }
+            if input::is_key_with_mod_pressed(ctx, Key::C) {
+                map.center_on(self.world.borrow().ship.quadrant);
+            }
+            if input::is_key_with_mod_pressed(ctx, Key::T) {
+                map.toggle_territories();
+            }
+        }
+        if input::is_key_with_mod_pressed(ctx, Key::Delete) {
+            self.set_destination(ctx, None);
+        }
+        if input::is_key_with_mod_pressed(ctx, Key::Enter) {
+            self.travel(ctx);
+            self.update_hovered(ctx);
+        }
+        let hovered = self.map.borrow().hovered();
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            self.update_hovered(ctx);
+        }
+        Transition::DoNothing
+    }
+
+    fn event(&mut self, _ctx: &mut Context, event: Event, focused: bool) -> Transition {
+        easy_back(event, focused).unwrap_or(Transition::DoNothing)
+    }
+
+    fn sprites(&mut self) -> Option<&Vec<Rc<RefCell<dyn UiSprite>>>> {
+        Some(&self.sprites)
+    }
+
+    fn custom_event(&mut self, ctx: &mut Context, event: &str) -> Option<Transition> {
+        match event {
+            "destination" => {
+                let hovered = self.map.borrow().hovered();
+                self.set_destination(ctx, hovered);
+                self.update_hovered(ctx);
+                None
+            }
+            _ => unreachable!(),
+        }
+    }
+}
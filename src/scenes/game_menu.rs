@@ -1,17 +1,40 @@
 use crate::assets::Assets;
+use crate::colors::Colors;
+use crate::game::world::World;
+use crate::savefile::{is_valid_slot, slot_exists};
 use crate::scenes::{bg, easy_back, GameScene, Scene, Transition};
-use crate::ui::{Button, Position, UiSprite, Vertical};
+use crate::ui::{Button, Draw, Label, Position, Stringify, TextInput, UiSprite, Vertical};
 use std::cell::RefCell;
 use std::rc::Rc;
 use tetra::input::Key;
-use tetra::{Context, Event};
+use tetra::{window, Context, Event};
+
+/// Moves the world to another slot, returns a message for the player either way
+pub fn save_as(world: &mut World, slot: &str) -> Result<String, String> {
+    let slot = slot.trim();
+    if slot.is_empty() {
+        Err("Slot name shall not be empty!".to_string())
+    } else if !is_valid_slot(slot) {
+        Err("Slot name can't be only dots and slashes!".to_string())
+    } else if slot != world.slot && slot_exists(&world.galaxy.meta.name, slot) {
+        Err(format!("Slot \"{}\" already exists!", slot))
+    } else {
+        world
+            .save_as(slot)
+            .map(|_| format!("Saved to \"{}\"", slot))
+            .map_err(|e| format!("Can't save: {}", e))
+    }
+}
 
 pub struct GameMenu {
+    world: Rc<RefCell<World>>,
     sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+    slot_input: Rc<RefCell<TextInput>>,
+    message: Rc<RefCell<Label>>,
 }
 
 impl GameMenu {
-    pub fn new(assets: &Assets) -> Self {
+    pub fn new(world: Rc<RefCell<World>>, assets: &Assets) -> Self {
         let back = Rc::new(RefCell::new(Button::text(
             vec![(Key::R, None)],
             "[r] Back to game",
@@ -33,10 +56,47 @@ impl GameMenu {
             Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 50.0 }),
             Transition::UnloadWorld,
         )));
+        let slot_input = Rc::new(RefCell::new(TextInput::new(
+            world.borrow().slot.as_str(),
+            250.0,
+            assets.fonts.handel24.clone(),
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 110.0 }),
+        )));
+        let save_as = Rc::new(RefCell::new(Button::text(
+            vec![(Key::A, None)],
+            "[a] Save as",
+            assets.fonts.consolab18.clone(),
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 165.0 }),
+            Transition::CustomEvent("save_as".to_string()),
+        )));
+        let message = Rc::new(RefCell::new(Label::hidden(
+            "",
+            assets.fonts.consolab18.clone(),
+            Colors::LIGHT_YELLOW,
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 210.0 }),
+        )));
         Self {
-            sprites: vec![bg(assets), back, settings, quit],
+            world,
+            sprites: vec![
+                bg(assets),
+                back,
+                settings,
+                quit,
+                slot_input.clone(),
+                save_as,
+                message.clone(),
+            ],
+            slot_input,
+            message,
         }
     }
+
+    fn show_message(&self, ctx: &mut Context, text: String, danger: bool) {
+        self.slot_input.borrow_mut().set_danger(danger);
+        let mut message = self.message.borrow_mut();
+        message.set_visible(true);
+        message.update(text, ctx, window::get_size(ctx));
+    }
 }
 
 impl Scene for GameMenu {
@@ -47,4 +107,19 @@ impl Scene for GameMenu {
     fn sprites(&mut self) -> Option<&Vec<Rc<RefCell<dyn UiSprite>>>> {
         Some(&self.sprites)
     }
+
+    fn custom_event(&mut self, ctx: &mut Context, event: &str) -> Option<Transition> {
+        match event {
+            "save_as" => {
+                let slot = self.slot_input.borrow().value();
+                let result = save_as(&mut self.world.borrow_mut(), &slot);
+                match result {
+                    Ok(text) => self.show_message(ctx, text, false),
+                    Err(text) => self.show_message(ctx, text, true),
+                }
+                None
+            }
+            _ => unreachable!(),
+        }
+    }
 }
@@ -1,26 +1,58 @@
 use crate::assets::Assets;
 use crate::colors::Colors;
-use crate::savefile::{delete, load, savefiles, savefiles_exists};
+use crate::savefile::{
+    backups, delete, load, new_slot, restore_backup, savefiles, savefiles_exists, LoadError,
+    SaveFile, WorldError,
+};
 use crate::scenes::{bg, easy_back, GameScene, Scene, Transition};
-use crate::ui::{Button, Horizontal, Label, Position, Positionate, UiSprite, Vertical};
+use crate::ui::{Button, Draw, Horizontal, Label, Position, Positionate, UiSprite, Vertical};
 use crate::VERSION;
 use chrono::{DateTime, Local};
 use geometry::Vec2;
 use std::cell::RefCell;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use tetra::input::{Key, KeyModifier};
-use tetra::{Context, Event};
+use tetra::{window, Context, Event};
 
 pub struct LoadWorld {
     sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+    /// Save waiting for the second click on its Restore button
+    restoring: Option<PathBuf>,
+    message: Rc<RefCell<Label>>,
+}
+
+/// Replaces the save with its newest backup, asks to click again first
+fn restore_button(assets: &Assets, path: &Path, y: f32) -> Rc<RefCell<dyn UiSprite>> {
+    Rc::new(RefCell::new(Button::text(
+        vec![],
+        "Restore",
+        assets.fonts.consolab18.clone(),
+        Position {
+            x: Horizontal::AtWindowCenterByLeft { offset: 320.0 },
+            y: Vertical::AtWindowCenterByCenter { offset: y },
+        },
+        Transition::CustomEvent(format!("restore:{}", path.to_str().unwrap())),
+    )))
 }
 
 impl LoadWorld {
     pub fn new(assets: &Assets, ctx: &mut Context) -> Self {
-        let savefiles = savefiles();
-        let mut sprites: Vec<Rc<RefCell<dyn UiSprite>>> =
-            Vec::with_capacity(savefiles.len() * 5 + 2);
+        let (savefiles, incompatible) = savefiles();
+        // slots grouped by galaxies, the galaxy with the newest save goes first
+        let mut galaxies: Vec<(&str, Vec<&SaveFile>)> = Vec::new();
+        for savefile in savefiles.iter() {
+            match galaxies
+                .iter_mut()
+                .find(|(name, _)| *name == savefile.galaxy_name())
+            {
+                Some((_, slots)) => slots.push(savefile),
+                None => galaxies.push((savefile.galaxy_name(), vec![savefile])),
+            }
+        }
+        let mut sprites: Vec<Rc<RefCell<dyn UiSprite>>> = Vec::with_capacity(
+            savefiles.len() * 5 + galaxies.len() * 2 + incompatible.len() * 3 + 2,
+        );
         sprites.push(bg(assets));
         // TODO: make a shortcut for scene title
         sprites.push(Rc::new(RefCell::new(Label::new(
@@ -32,7 +64,8 @@ impl LoadWorld {
                 y: Vertical::AtWindowCenterByBottom { offset: -200.0 },
             },
         ))));
-        let height = savefiles.len() as f32 * 50.0;
+        let height =
+            (savefiles.len() + incompatible.len()) as f32 * 50.0 + galaxies.len() as f32 * 40.0;
         // TODO: Add scroll if there are too many savefiles
         let mut y = f32::max(-height / 2.0, -155.0);
         const KEYS: [Key; 10] = [
@@ -47,76 +80,155 @@ impl LoadWorld {
             Key::Num9,
             Key::Num0,
         ];
-        for (i, savefile) in savefiles.iter().enumerate() {
-            sprites.push(Rc::new(RefCell::new(Button::empty(
-                // TODO: add some hint for this shortkeys
-                if i <= 10 {
-                    vec![(KEYS[i], None)]
-                } else {
-                    vec![]
+        let mut i = 0;
+        for (galaxy, slots) in galaxies.iter() {
+            sprites.push(Rc::new(RefCell::new(Label::new(
+                *galaxy,
+                assets.fonts.handel24.clone(),
+                Colors::ORANGE,
+                Position {
+                    x: Horizontal::AtWindowCenterByLeft { offset: -285.0 },
+                    y: Vertical::AtWindowCenterByCenter { offset: y - 4.0 },
                 },
-                Vec2::new(500.0, 40.0),
+            ))));
+            // another playthrough in the same galaxy
+            sprites.push(Rc::new(RefCell::new(Button::text(
+                vec![],
+                "New character",
+                assets.fonts.consolab18.clone(),
                 Position {
-                    x: Horizontal::AtWindowCenterByCenter { offset: -20.0 },
+                    x: Horizontal::AtWindowCenterByLeft { offset: 240.0 },
                     y: Vertical::AtWindowCenterByCenter { offset: y },
                 },
-                Transition::CustomEvent(format!("load:{}", savefile.path.to_str().unwrap())),
+                Transition::CustomEvent(format!("new:{}", slots[0].path.to_str().unwrap())),
             ))));
+            y += 40.0;
+            for savefile in slots.iter() {
+                sprites.push(Rc::new(RefCell::new(Button::empty(
+                    // TODO: add some hint for this shortkeys
+                    if i < KEYS.len() {
+                        vec![(KEYS[i], None)]
+                    } else {
+                        vec![]
+                    },
+                    Vec2::new(500.0, 40.0),
+                    Position {
+                        x: Horizontal::AtWindowCenterByCenter { offset: -20.0 },
+                        y: Vertical::AtWindowCenterByCenter { offset: y },
+                    },
+                    Transition::CustomEvent(format!("load:{}", savefile.path.to_str().unwrap())),
+                ))));
+                sprites.push(Rc::new(RefCell::new(Button::text(
+                    if i < KEYS.len() {
+                        vec![(KEYS[i], Some(KeyModifier::Alt))]
+                    } else {
+                        vec![]
+                    },
+                    "Delete",
+                    assets.fonts.consolab18.clone(),
+                    Position {
+                        x: Horizontal::AtWindowCenterByLeft { offset: 240.0 },
+                        y: Vertical::AtWindowCenterByCenter { offset: y },
+                    },
+                    Transition::CustomEvent(format!("del:{}", savefile.path.to_str().unwrap())),
+                ))));
+                if !backups(&savefile.path).is_empty() {
+                    sprites.push(restore_button(assets, &savefile.path, y));
+                }
+                let name = Rc::new(RefCell::new(Label::new(
+                    format!("{} ({})", savefile.slot_name(), savefile.character_name()),
+                    assets.fonts.handel24.clone(),
+                    Colors::LIGHT_YELLOW,
+                    Position {
+                        x: Horizontal::AtWindowCenterByLeft { offset: -265.0 },
+                        y: Vertical::AtWindowCenterByCenter { offset: y - 4.0 },
+                    },
+                )));
+                let name_size = name.borrow_mut().calc_size(ctx);
+                sprites.push(name);
+                sprites.push(Rc::new(RefCell::new(Label::new(
+                    &savefile.version,
+                    assets.fonts.consolab12.clone(),
+                    if savefile.version.as_str() == VERSION {
+                        Colors::LIME
+                    } else {
+                        Colors::RED
+                    },
+                    Position {
+                        x: Horizontal::AtWindowCenterByLeft {
+                            offset: -255.0 + name_size.x,
+                        },
+                        y: Vertical::AtWindowCenterByBottom { offset: y - 4.0 },
+                    },
+                ))));
+                let time: DateTime<Local> = savefile.time.into();
+                sprites.push(Rc::new(RefCell::new(Label::new(
+                    time.format("%Y.%m.%d %H:%M:%S").to_string().as_str(),
+                    assets.fonts.consolab12.clone(),
+                    Colors::LIGHT_YELLOW,
+                    Position {
+                        x: Horizontal::AtWindowCenterByLeft {
+                            offset: -255.0 + name_size.x,
+                        },
+                        y: Vertical::AtWindowCenterByTop { offset: y },
+                    },
+                ))));
+                y += 50.0;
+                i += 1;
+            }
+        }
+        // saves from newer versions or broken files, they can only be deleted
+        for save in incompatible.iter() {
             sprites.push(Rc::new(RefCell::new(Button::text(
-                if i <= 10 {
-                    vec![(KEYS[i], Some(KeyModifier::Alt))]
-                } else {
-                    vec![]
-                },
+                vec![],
                 "Delete",
                 assets.fonts.consolab18.clone(),
                 Position {
                     x: Horizontal::AtWindowCenterByLeft { offset: 240.0 },
                     y: Vertical::AtWindowCenterByCenter { offset: y },
                 },
-                Transition::CustomEvent(format!("del:{}", savefile.path.to_str().unwrap())),
+                Transition::CustomEvent(format!("del:{}", save.path.to_str().unwrap())),
             ))));
-            let name = Rc::new(RefCell::new(Label::new(
-                format!("{} ({})", savefile.galaxy_name(), savefile.character_name()),
+            if !backups(&save.path).is_empty() {
+                sprites.push(restore_button(assets, &save.path, y));
+            }
+            let name = save
+                .path
+                .file_stem()
+                .map(|s| s.to_string_lossy().replace('_', " "))
+                .unwrap_or_default();
+            sprites.push(Rc::new(RefCell::new(Label::new(
+                format!("{} (incompatible save)", name),
                 assets.fonts.handel24.clone(),
-                Colors::LIGHT_YELLOW,
+                Colors::GRAY,
                 Position {
                     x: Horizontal::AtWindowCenterByLeft { offset: -265.0 },
                     y: Vertical::AtWindowCenterByCenter { offset: y - 4.0 },
                 },
-            )));
-            let name_size = name.borrow_mut().calc_size(ctx);
-            sprites.push(name);
-            sprites.push(Rc::new(RefCell::new(Label::new(
-                &savefile.version,
-                assets.fonts.consolab12.clone(),
-                if savefile.version.as_str() == VERSION {
-                    Colors::LIME
-                } else {
-                    Colors::RED
-                },
-                Position {
-                    x: Horizontal::AtWindowCenterByLeft {
-                        offset: -255.0 + name_size.x,
-                    },
-                    y: Vertical::AtWindowCenterByBottom { offset: y - 4.0 },
-                },
             ))));
-            let time: DateTime<Local> = savefile.time.into();
             sprites.push(Rc::new(RefCell::new(Label::new(
-                time.format("%Y.%m.%d %H:%M:%S").to_string().as_str(),
+                save.error.to_string(),
                 assets.fonts.consolab12.clone(),
-                Colors::LIGHT_YELLOW,
+                Colors::RED,
                 Position {
-                    x: Horizontal::AtWindowCenterByLeft {
-                        offset: -255.0 + name_size.x,
-                    },
-                    y: Vertical::AtWindowCenterByTop { offset: y },
+                    x: Horizontal::AtWindowCenterByLeft { offset: -265.0 },
+                    y: Vertical::AtWindowCenterByTop { offset: y + 10.0 },
                 },
             ))));
             y += 50.0;
         }
-        Self { sprites }
+        let message = Rc::new(RefCell::new(Label::hidden(
+            "",
+            assets.fonts.consolab18.clone(),
+            Colors::ORANGE,
+            Position::horizontal_center(0.0, Vertical::AtWindowBottomByBottom { offset: -20.0 }),
+        )));
+        sprites.push(message.clone());
+        Self {
+            sprites,
+            restoring: None,
+            message,
+        }
     }
 }
 
@@ -129,15 +241,20 @@ impl Scene for LoadWorld {
         Some(&self.sprites)
     }
 
-    fn custom_event(&mut self, _ctx: &mut Context, event: &str) -> Option<Transition> {
+    fn custom_event(&mut self, ctx: &mut Context, event: &str) -> Option<Transition> {
         let mut parts = event.split(':');
         match (parts.next(), parts.next()) {
-            (Some("load"), Some(path)) => load(path.as_ref()).map(|s| {
-                if s.has_character() {
-                    Transition::LoadWorld(s)
-                } else {
-                    Transition::Replace(GameScene::CreateCharacter(s))
-                }
+            (Some("load"), Some(path)) => Some(match load(path.as_ref()) {
+                Ok(s) if s.has_character() => Transition::LoadWorld(s),
+                Ok(s) => Transition::Replace(GameScene::CreateCharacter(s)),
+                Err(e) => Transition::WorldError(WorldError::Load(path.into(), e)),
+            }),
+            (Some("new"), Some(path)) => Some(match load(path.as_ref()) {
+                Ok(s) => match new_slot(&s) {
+                    Ok(s) => Transition::Replace(GameScene::CreateCharacter(s)),
+                    Err(e) => Transition::WorldError(WorldError::Save(s.path, e)),
+                },
+                Err(e) => Transition::WorldError(WorldError::Load(path.into(), e)),
             }),
             (Some("del"), Some(path)) => {
                 let path = path.parse::<PathBuf>().unwrap();
@@ -148,6 +265,31 @@ impl Scene for LoadWorld {
                     Transition::Pop
                 })
             }
+            (Some("restore"), Some(path)) => {
+                let path = path.parse::<PathBuf>().unwrap();
+                if self.restoring.as_ref() != Some(&path) {
+                    let name = path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().replace('_', " "))
+                        .unwrap_or_default();
+                    let mut message = self.message.borrow_mut();
+                    message.set_visible(true);
+                    message.update(
+                        format!(
+                            "Click Restore again to replace \"{}\" with its backup, progress made after the backup is lost",
+                            name
+                        ),
+                        ctx,
+                        window::get_size(ctx),
+                    );
+                    self.restoring = Some(path);
+                    return None;
+                }
+                Some(match restore_backup(&path) {
+                    Ok(_) | Err(LoadError::NoBackup) => Transition::Replace(GameScene::LoadWorld),
+                    Err(e) => Transition::WorldError(WorldError::Load(path, e)),
+                })
+            }
             (_, _) => unreachable!(),
         }
     }
@@ -1,25 +1,36 @@
+mod character_abilities;
 mod create_character;
 mod create_world;
+pub mod data_errors;
 mod empty;
+mod galaxy_map;
 mod game_menu;
 mod load_world;
 pub mod main_menu;
 mod settings;
 mod ship_walk;
 mod terminal;
+mod trade;
+mod world_error;
 
 use crate::app::App;
 use crate::assets::Assets;
-use crate::savefile::SaveFile;
+use crate::data::data_error::DataError;
+use crate::savefile::{SaveFile, WorldError};
+use crate::scenes::character_abilities::CharacterAbilities;
 use crate::scenes::create_character::CreateCharacter;
 use crate::scenes::create_world::CreateWorld;
+use crate::scenes::data_errors::DataErrors;
 use crate::scenes::empty::Empty;
+use crate::scenes::galaxy_map::GalaxyMap;
 use crate::scenes::game_menu::GameMenu;
 use crate::scenes::load_world::LoadWorld;
 use crate::scenes::main_menu::MainMenu;
 use crate::scenes::settings::SettingsScene;
 use crate::scenes::ship_walk::ShipWalk;
 use crate::scenes::terminal::Terminal;
+use crate::scenes::trade::Trade;
+use crate::scenes::world_error::WorldErrorScene;
 use crate::ui::{Image, Position, UiSprite};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -35,9 +46,14 @@ pub enum GameScene {
     CreateWorld,
     LoadWorld,
     CreateCharacter(SaveFile),
+    CharacterAbilities(SaveFile),
     ShipWalk,
     GameMenu,
     Terminal,
+    GalaxyMap,
+    Trade,
+    DataErrors(Vec<DataError>),
+    WorldError(WorldError),
 }
 
 impl GameScene {
@@ -54,13 +70,36 @@ impl GameScene {
                 game.data.clone(),
                 ctx,
             )),
+            GameScene::CharacterAbilities(s) => Box::new(CharacterAbilities::new(s, &game.assets)),
             GameScene::ShipWalk => Box::new(ShipWalk::new(
                 game.world.as_ref().unwrap().clone(),
                 game.assets.clone(),
+                game.data.clone(),
                 ctx,
             )),
-            GameScene::GameMenu => Box::new(GameMenu::new(&game.assets)),
+            GameScene::GameMenu => Box::new(GameMenu::new(
+                game.world.as_ref().unwrap().clone(),
+                &game.assets,
+            )),
             GameScene::Terminal => Box::new(Terminal::new(ctx, &game.assets)),
+            GameScene::GalaxyMap => Box::new(GalaxyMap::new(
+                game.world.as_ref().unwrap().clone(),
+                &game.assets,
+                ctx,
+            )),
+            GameScene::Trade => Box::new(Trade::new(
+                game.world.as_ref().unwrap().clone(),
+                game.data.clone(),
+                &game.assets,
+            )),
+            GameScene::DataErrors(errors) => {
+                Box::new(DataErrors::new(&game.assets, &errors, Transition::Pop))
+            }
+            GameScene::WorldError(error) => Box::new(WorldErrorScene::new(
+                &game.assets,
+                error,
+                game.world.clone(),
+            )),
         }
     }
 }
@@ -70,8 +109,10 @@ pub enum Transition {
     DoNothing,
     Push(GameScene),
     CreateWorld(SaveFile),
-    LoadWorld(SaveFile), // and replace to GameScene::ShipWalk
-    UnloadWorld,         // and go to main menu
+    LoadWorld(SaveFile),    // and replace to GameScene::ShipWalk
+    UnloadWorld,            // save and go to main menu
+    DropWorld,              // go to main menu without saving
+    WorldError(WorldError), // failed saves keep the world, other errors go to main menu
     Pop,
     Replace(GameScene), // pop and push
     CustomEvent(String),
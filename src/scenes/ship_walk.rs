@@ -1,12 +1,18 @@
 use crate::assets::{Assets, TileSet};
 use crate::colors::Colors;
+use crate::data::game_data::GameData;
 use crate::game::action::{Action, ActionType};
+use crate::game::avatar::Aboard;
+use crate::game::ship::DeckDirection;
 use crate::game::ship_parts::ShipPartAction;
 use crate::game::ship_tile::ShipTile;
-use crate::game::world::World;
+use crate::game::world::{DockError, HireError, World};
+use crate::human::character::Character;
 use crate::input;
 use crate::scenes::{GameScene, Scene, Transition};
-use crate::ui::{Image, Label, Position, Positionate, ShipView, UiSprite};
+use crate::ui::{
+    Draw, Horizontal, Image, Label, Position, Positionate, ShipView, UiSprite, Vertical,
+};
 use geometry::Vec2;
 use geometry::{Direction, DIR9};
 use std::cell::RefCell;
@@ -91,13 +97,65 @@ impl From<Zoom> for f32 {
     }
 }
 
+fn title(world: &World) -> String {
+    let name = match world.avatar.aboard {
+        Aboard::Ship => match world.ship.docked {
+            Some(i) => format!("{}, docked at {}", world.ship.name, world.stations[i].name),
+            None => world.ship.name.clone(),
+        },
+        Aboard::Station(i) => {
+            let station = &world.stations[i];
+            format!(
+                "{} ({} station)",
+                station.name,
+                station.kind.name().to_lowercase()
+            )
+        }
+    };
+    let name = if world.structure().decks > 1 {
+        format!("{}, deck {}", name, world.avatar.deck + 1)
+    } else {
+        name
+    };
+    if world.crew.is_empty() {
+        name
+    } else {
+        format!("{} - {}", world.avatar.character.name, name)
+    }
+}
+
+fn dock_error_text(error: DockError) -> &'static str {
+    match error {
+        DockError::NoStation => "Can't dock: there is no station here",
+        DockError::AlreadyDocked => "Can't dock: ship is already docked",
+        DockError::NotDocked => "Can't undock: ship is not docked",
+        DockError::AvatarNotAboard => "Can't undock without the captain",
+        DockError::CrewNotAboard => "Can't undock: somebody from the crew is on the station",
+        DockError::UnknownStationClass => "Can't dock: station has an unknown class",
+    }
+}
+
+fn hire_error_text(error: HireError) -> &'static str {
+    match error {
+        HireError::NotDocked => "Can't hire: crew is hired on stations only",
+        HireError::NotEnoughCredits => "Can't hire: not enough credits",
+        HireError::NoRoom => "Can't hire: there is no room aboard",
+    }
+}
+
 pub struct ShipWalk {
     #[allow(dead_code)]
     world: Rc<RefCell<World>>,
     assets: Rc<Assets>,
+    data: Rc<GameData>,
     sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+    name: Rc<RefCell<Label>>,
     ship_view: Rc<RefCell<ShipView>>,
     clock: Rc<RefCell<Label>>,
+    /// Shown while autosaves fail
+    autosave_error: Rc<RefCell<Label>>,
+    /// Level-ups and failed skill checks of the last finished actions
+    status: Rc<RefCell<Label>>,
     last_walk: Instant,
     mode: GameMode,
     cursor: Mesh,
@@ -106,12 +164,17 @@ pub struct ShipWalk {
 }
 
 impl ShipWalk {
-    pub fn new(world: Rc<RefCell<World>>, assets: Rc<Assets>, ctx: &mut Context) -> Self {
+    pub fn new(
+        world: Rc<RefCell<World>>,
+        assets: Rc<Assets>,
+        data: Rc<GameData>,
+        ctx: &mut Context,
+    ) -> Self {
         let bg = Rc::new(RefCell::new(Image::repeat(
             assets.images.blue_nebula.clone(),
         )));
         let name = Rc::new(RefCell::new(Label::new(
-            world.borrow().ship.name.clone(),
+            title(&world.borrow()),
             assets.fonts.handel16.clone(),
             Colors::LIGHT_SKY_BLUE,
             Position::by_left_top(10.0, 10.0),
@@ -119,8 +182,9 @@ impl ShipWalk {
         let zoom = Zoom(4); // TODO: load current zoom from savefile
         let ship_view = Rc::new(RefCell::new(ShipView::new(
             ctx,
-            &world.borrow().ship,
+            world.borrow().structure(),
             &world.borrow().avatar,
+            &world.borrow().crew,
             &assets.tileset,
             zoom.as_view(),
         )));
@@ -131,12 +195,38 @@ impl ShipWalk {
             Colors::ORANGE,
             Position::by_right_top(-10.0, 10.0),
         )));
+        let autosave_error = Rc::new(RefCell::new(Label::hidden(
+            "",
+            assets.fonts.consolab12.clone(),
+            Colors::RED,
+            Position::by_right_top(-10.0, 35.0),
+        )));
+        let status = Rc::new(RefCell::new(Label::hidden(
+            "",
+            assets.fonts.consolab12.clone(),
+            Colors::LIGHT_YELLOW,
+            Position {
+                x: Horizontal::ByLeft { x: 10.0 },
+                y: Vertical::AtWindowBottomByBottom { offset: -10.0 },
+            },
+        )));
         Self {
             world,
             assets,
-            sprites: vec![bg, name, ship_view.clone(), clock.clone()],
+            data,
+            sprites: vec![
+                bg,
+                name.clone(),
+                ship_view.clone(),
+                clock.clone(),
+                autosave_error.clone(),
+                status.clone(),
+            ],
+            name,
             ship_view,
             clock,
+            autosave_error,
+            status,
             last_walk: Instant::now(),
             mode: GameMode::Default,
             cursor: Mesh::rectangle(
@@ -162,6 +252,72 @@ impl ShipWalk {
         }
     }
 
+    fn toggle_dock(&mut self, ctx: &mut Context) {
+        let mut world = self.world.borrow_mut();
+        let result = if world.ship.docked.is_some() {
+            world.undock()
+        } else {
+            world.dock(&self.data).map(|_| ())
+        };
+        let window_size = window::get_size(ctx);
+        let mut status = self.status.borrow_mut();
+        match result {
+            Ok(()) => status.set_visible(false),
+            Err(e) => {
+                status.set_visible(true);
+                status.update(dock_error_text(e), ctx, window_size);
+            }
+        }
+        self.name
+            .borrow_mut()
+            .update(title(&world), ctx, window_size);
+    }
+
+    /// Takes control of the next crew member
+    fn switch_avatar(&mut self, ctx: &mut Context) {
+        let mut world = self.world.borrow_mut();
+        if world.crew.is_empty() {
+            return;
+        }
+        world.switch_avatar(0);
+        let window_size = window::get_size(ctx);
+        self.ship_view.borrow_mut().update(
+            ctx,
+            world.structure(),
+            &world.avatar,
+            &world.crew,
+            &self.assets.tileset,
+            window_size,
+        );
+        self.name
+            .borrow_mut()
+            .update(title(&world), ctx, window_size);
+    }
+
+    fn hire(&mut self, ctx: &mut Context) {
+        let mut world = self.world.borrow_mut();
+        let character = Character::random(&mut rand::thread_rng(), &self.data, world.timestamp());
+        let window_size = window::get_size(ctx);
+        let mut status = self.status.borrow_mut();
+        if let Err(e) = world.hire(character) {
+            status.set_visible(true);
+            status.update(hire_error_text(e), ctx, window_size);
+            return;
+        }
+        status.set_visible(false);
+        self.ship_view.borrow_mut().update(
+            ctx,
+            world.structure(),
+            &world.avatar,
+            &world.crew,
+            &self.assets.tileset,
+            window_size,
+        );
+        self.name
+            .borrow_mut()
+            .update(title(&world), ctx, window_size);
+    }
+
     fn draw_cursor(&self, ctx: &mut Context, dir: Direction, color: Color) {
         let rect = self.ship_view.borrow().rect();
         let center =
@@ -205,6 +361,26 @@ impl Scene for ShipWalk {
                     self.mode = GameMode::Activating(Some(ShipPartAction::Close));
                 } else if input::is_key_with_mod_pressed(ctx, Key::A) {
                     self.mode = GameMode::Activating(None);
+                } else if input::is_key_with_mod_pressed(ctx, Key::M) {
+                    return Transition::Push(GameScene::GalaxyMap);
+                } else if input::is_key_with_mod_pressed(ctx, Key::D) {
+                    self.toggle_dock(ctx);
+                } else if input::is_key_with_mod_pressed(ctx, Key::B)
+                    && self.world.borrow().ship.docked.is_some()
+                {
+                    return Transition::Push(GameScene::Trade);
+                } else if input::is_key_with_mod_pressed(ctx, Key::Tab) {
+                    self.switch_avatar(ctx);
+                } else if input::is_key_with_mod_pressed(ctx, Key::H) {
+                    self.hire(ctx);
+                } else if input::is_key_with_mod_pressed(ctx, (Key::Period, KeyModifier::Shift)) {
+                    let mut world = self.world.borrow_mut();
+                    world.avatar.action =
+                        Action::new(ActionType::ChangingDeck(DeckDirection::Up), &world);
+                } else if input::is_key_with_mod_pressed(ctx, (Key::Comma, KeyModifier::Shift)) {
+                    let mut world = self.world.borrow_mut();
+                    world.avatar.action =
+                        Action::new(ActionType::ChangingDeck(DeckDirection::Down), &world);
                 }
                 // TODO: Key::E to examine, Key::T to talk, Key::I to inventory view, Key::Q to drop an item, etc.
 
@@ -237,7 +413,7 @@ impl Scene for ShipWalk {
                         } else {
                             // TODO: select action from list
                             let world = self.world.borrow();
-                            if let Some(tile) = world.ship.get_tile(world.avatar.pos + dir) {
+                            if let Some(tile) = world.tile_at(dir) {
                                 if tile.supports_action(ShipPartAction::UseTerminal) {
                                     return Transition::Push(GameScene::Terminal);
                                 }
@@ -253,18 +429,51 @@ impl Scene for ShipWalk {
 
         if self.world.borrow().avatar.action.is_some() {
             let mut world = self.world.borrow_mut();
-            world.tick();
+            let (aboard, deck) = (world.avatar.aboard, world.avatar.deck);
             let window_size = window::get_size(ctx);
+            match world.tick() {
+                Ok(true) => self.autosave_error.borrow_mut().set_visible(false),
+                Ok(false) => {}
+                Err(e) => {
+                    let mut label = self.autosave_error.borrow_mut();
+                    label.set_visible(true);
+                    label.update(
+                        format!("Autosave failed: {}, will try again later", e),
+                        ctx,
+                        window_size,
+                    );
+                }
+            }
+            let events = world.take_events();
+            if !events.is_empty() {
+                let text = events
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                let mut label = self.status.borrow_mut();
+                label.set_visible(true);
+                label.update(text, ctx, window_size);
+            } else if world.avatar.action.is_none() {
+                // message stays until the next action is done
+                self.status.borrow_mut().set_visible(false);
+            }
             self.clock
                 .borrow_mut()
                 .update(format!("{}", world.time()), ctx, window_size);
             self.ship_view.borrow_mut().update(
                 ctx,
-                &world.ship,
+                world.structure(),
                 &world.avatar,
+                &world.crew,
                 &self.assets.tileset,
                 window_size,
             );
+            if aboard != world.avatar.aboard || deck != world.avatar.deck {
+                self.name
+                    .borrow_mut()
+                    .update(title(&world), ctx, window_size);
+            }
         }
 
         Transition::DoNothing
@@ -281,8 +490,7 @@ impl Scene for ShipWalk {
                 {
                     continue;
                 }
-                let pos = self.world.borrow().avatar.pos + dir;
-                if let Some(tile) = self.world.borrow().ship.get_tile(pos) {
+                if let Some(tile) = self.world.borrow().tile_at(dir) {
                     if self.mode.cursor_here(tile) {
                         self.draw_cursor(ctx, dir, Colors::ORANGE.with_alpha(0.7));
                     }
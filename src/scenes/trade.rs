@@ -0,0 +1,208 @@
+use crate::assets::Assets;
+use crate::colors::Colors;
+use crate::data::game_data::GameData;
+use crate::game::market::TradeError;
+use crate::game::world::World;
+use crate::input;
+use crate::scenes::{bg, easy_back, GameScene, Scene, Transition};
+use crate::ui::{Button, Draw, Horizontal, Label, Position, UiSprite, Vertical};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tetra::input::{Key, KeyModifier};
+use tetra::{window, Context, Event};
+
+/// Units traded by one click with Shift down
+const BULK: u32 = 10;
+
+fn trade_error_text(error: TradeError) -> &'static str {
+    match error {
+        TradeError::NotTraded => "This commodity is not traded here",
+        TradeError::NotEnoughStock => "Station doesn't have that much in stock",
+        TradeError::NotEnoughCredits => "Not enough credits",
+        TradeError::NotEnoughCargo => "Not enough cargo in the hold",
+        TradeError::NoCargoSpace => "No space left in the hold",
+        TradeError::TooExpensive => "Deal is too big, try to trade less",
+    }
+}
+
+fn credits_text(world: &World) -> String {
+    format!(
+        "{} credits, {}/{} t of cargo, {:.0}/{:.0} fuel in tanks",
+        world.ship.credits,
+        world.ship.cargo_total(),
+        world.ship.cargo_capacity,
+        world.ship.fuel,
+        world.ship.stats().fuel_capacity
+    )
+}
+
+/// Market of the station ship is docked to
+pub struct Trade {
+    world: Rc<RefCell<World>>,
+    data: Rc<GameData>,
+    sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+    message: Rc<RefCell<Label>>,
+}
+
+impl Trade {
+    pub fn new(world: Rc<RefCell<World>>, data: Rc<GameData>, assets: &Assets) -> Self {
+        let mut sprites: Vec<Rc<RefCell<dyn UiSprite>>> = vec![bg(assets)];
+        {
+            let mut world = world.borrow_mut();
+            let tick = world.current_tick;
+            let station = world.ship.docked.unwrap();
+            world.stations[station].market.update(tick);
+            let world = &*world;
+            let station = &world.stations[station];
+            sprites.push(Rc::new(RefCell::new(Label::new(
+                format!("Market of {}", station.name),
+                assets.fonts.handel32.clone(),
+                Colors::ORANGE_RED,
+                Position {
+                    x: Horizontal::AtWindowCenterByCenter { offset: 0.0 },
+                    y: Vertical::AtWindowCenterByBottom { offset: -200.0 },
+                },
+            ))));
+            sprites.push(Rc::new(RefCell::new(Label::new(
+                credits_text(world),
+                assets.fonts.consolab18.clone(),
+                Colors::LIGHT_YELLOW,
+                Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: -180.0 }),
+            ))));
+            let cell = |text: String, column: f32, row: f32, color| {
+                Rc::new(RefCell::new(Label::new(
+                    text,
+                    assets.fonts.consolab18.clone(),
+                    color,
+                    Position {
+                        x: Horizontal::AtWindowCenterByLeft { offset: column },
+                        y: Vertical::AtWindowCenterByCenter { offset: row },
+                    },
+                )))
+            };
+            for (text, column) in [
+                ("Commodity", -360.0),
+                ("Stock", -160.0),
+                ("Buy", -60.0),
+                ("Sell", 40.0),
+                ("Hold", 140.0),
+            ] {
+                sprites.push(cell(text.to_string(), column, -130.0, Colors::GRAY));
+            }
+            for (i, goods) in station.market.goods.iter().enumerate() {
+                let commodity = match data.commodities.get(goods.commodity.as_str()) {
+                    Some(commodity) => commodity,
+                    None => continue,
+                };
+                let row = -100.0 + i as f32 * 30.0;
+                let price = |price: Option<u32>| price.map_or("-".to_string(), |p| p.to_string());
+                let in_hold = world.ship.cargo.get(&commodity.id).copied().unwrap_or(0);
+                sprites.push(cell(
+                    commodity.name.clone(),
+                    -360.0,
+                    row,
+                    Colors::LIGHT_YELLOW,
+                ));
+                sprites.push(cell(
+                    goods.stock.to_string(),
+                    -160.0,
+                    row,
+                    Colors::LIGHT_YELLOW,
+                ));
+                sprites.push(cell(
+                    price(station.market.buy_price(commodity, tick)),
+                    -60.0,
+                    row,
+                    Colors::LIGHT_YELLOW,
+                ));
+                sprites.push(cell(
+                    price(station.market.sell_price(commodity, tick)),
+                    40.0,
+                    row,
+                    Colors::LIGHT_YELLOW,
+                ));
+                sprites.push(cell(in_hold.to_string(), 140.0, row, Colors::LIGHT_YELLOW));
+                for (text, event, column) in [("Buy", "buy", 220.0), ("Sell", "sell", 290.0)] {
+                    sprites.push(Rc::new(RefCell::new(Button::text(
+                        vec![],
+                        text,
+                        assets.fonts.consolab18.clone(),
+                        Position {
+                            x: Horizontal::AtWindowCenterByLeft { offset: column },
+                            y: Vertical::AtWindowCenterByCenter { offset: row },
+                        },
+                        Transition::CustomEvent(format!("{}:{}", event, commodity.id)),
+                    ))));
+                }
+            }
+        }
+        let message = Rc::new(RefCell::new(Label::hidden(
+            "",
+            assets.fonts.consolab18.clone(),
+            Colors::RED,
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 220.0 }),
+        )));
+        sprites.push(message.clone());
+        sprites.push(Rc::new(RefCell::new(Label::new(
+            format!("[Shift] Trade {} units  [Esc] Back", BULK),
+            assets.fonts.consolab12.clone(),
+            Colors::LIGHT_GRAY,
+            Position {
+                x: Horizontal::ByLeft { x: 10.0 },
+                y: Vertical::AtWindowBottomByBottom { offset: -10.0 },
+            },
+        ))));
+        sprites.push(Rc::new(RefCell::new(Button::text(
+            vec![(Key::R, None)],
+            "[r] Back to ship",
+            assets.fonts.consolab18.clone(),
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 260.0 }),
+            Transition::Pop,
+        ))));
+        Self {
+            world,
+            data,
+            sprites,
+            message,
+        }
+    }
+}
+
+impl Scene for Trade {
+    fn event(&mut self, _ctx: &mut Context, event: Event, focused: bool) -> Transition {
+        easy_back(event, focused).unwrap_or(Transition::DoNothing)
+    }
+
+    fn sprites(&mut self) -> Option<&Vec<Rc<RefCell<dyn UiSprite>>>> {
+        Some(&self.sprites)
+    }
+
+    fn custom_event(&mut self, ctx: &mut Context, event: &str) -> Option<Transition> {
+        let (action, id) = event.split_once(':').unwrap();
+        let commodity = self.data.commodities.get(id).unwrap();
+        let amount = if input::is_key_modifier_down(ctx, KeyModifier::Shift) {
+            BULK
+        } else {
+            1
+        };
+        let result = {
+            let mut world = self.world.borrow_mut();
+            let station = world.ship.docked.unwrap();
+            match action {
+                "buy" => world.buy(station, commodity, amount),
+                "sell" => world.sell(station, commodity, amount),
+                _ => unreachable!(),
+            }
+        };
+        match result {
+            // screen is rebuilt with new stocks and prices
+            Ok(_) => Some(Transition::Replace(GameScene::Trade)),
+            Err(error) => {
+                let mut message = self.message.borrow_mut();
+                message.set_visible(true);
+                message.update(trade_error_text(error), ctx, window::get_size(ctx));
+                None
+            }
+        }
+    }
+}
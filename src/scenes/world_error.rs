@@ -0,0 +1,185 @@
+use crate::assets::Assets;
+use crate::colors::Colors;
+use crate::game::world::World;
+use crate::savefile::{backups, load, restore_backup, LoadError, WorldError};
+use crate::scenes::game_menu::save_as;
+use crate::scenes::{bg, GameScene, Scene, Transition};
+use crate::ui::{Button, Horizontal, Label, Position, Stringify, TextInput, UiSprite, Vertical};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tetra::input::Key;
+use tetra::{window, Context};
+
+/// Shown over the main menu when the world can't be loaded or saved;
+/// world which can't be saved stays loaded and the scene is shown over the game
+pub struct WorldErrorScene {
+    sprites: Vec<Rc<RefCell<dyn UiSprite>>>,
+    error: WorldError,
+    /// Unsaved world, `None` if the error took the player out of it
+    world: Option<Rc<RefCell<World>>>,
+    cause: Rc<RefCell<Label>>,
+    slot_input: Option<Rc<RefCell<TextInput>>>,
+}
+
+impl WorldErrorScene {
+    pub fn new(assets: &Assets, error: WorldError, world: Option<Rc<RefCell<World>>>) -> Self {
+        let has_backup = !backups(error.path()).is_empty();
+        let world = world.filter(|_| matches!(error, WorldError::Save(..)));
+        let (title, cause, hint) = match &error {
+            WorldError::Load(_, e) => (
+                "Can't load the world:",
+                e.to_string(),
+                if has_backup {
+                    "The newest backup can replace this save, progress made after it is lost"
+                } else {
+                    "This save has no backups"
+                },
+            ),
+            WorldError::Save(_, e) => (
+                "Can't save the world:",
+                e.to_string(),
+                if world.is_some() {
+                    "The world is still loaded, try again or save it to another slot"
+                } else {
+                    "Progress since the last save is lost, the savefile itself is not damaged"
+                },
+            ),
+        };
+        let mut sprites: Vec<Rc<RefCell<dyn UiSprite>>> = Vec::with_capacity(7);
+        sprites.push(bg(assets));
+        sprites.push(Rc::new(RefCell::new(Label::new(
+            title,
+            assets.fonts.handel32.clone(),
+            Colors::ORANGE_RED,
+            Position {
+                x: Horizontal::AtWindowCenterByCenter { offset: 0.0 },
+                y: Vertical::AtWindowCenterByBottom { offset: -200.0 },
+            },
+        ))));
+        sprites.push(Rc::new(RefCell::new(Label::new(
+            error.path().to_string_lossy(),
+            assets.fonts.consolab18.clone(),
+            Colors::LIGHT_YELLOW,
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: -150.0 }),
+        ))));
+        let cause = Rc::new(RefCell::new(Label::new(
+            cause,
+            assets.fonts.consolab18.clone(),
+            Colors::RED,
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: -110.0 }),
+        )));
+        sprites.push(cause.clone());
+        sprites.push(Rc::new(RefCell::new(Label::new(
+            hint,
+            assets.fonts.consolab12.clone(),
+            Colors::LIGHT_YELLOW,
+            Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: -70.0 }),
+        ))));
+        if has_backup && matches!(error, WorldError::Load(..)) {
+            sprites.push(Rc::new(RefCell::new(Button::text(
+                vec![(Key::B, None)],
+                "[b] Open backup",
+                assets.fonts.consolab18.clone(),
+                Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 130.0 }),
+                Transition::CustomEvent("backup".to_string()),
+            ))));
+        }
+        let mut slot_input = None;
+        if let Some(world) = &world {
+            sprites.push(Rc::new(RefCell::new(Button::text(
+                vec![(Key::R, None)],
+                "[r] Retry",
+                assets.fonts.consolab18.clone(),
+                Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: -20.0 }),
+                Transition::CustomEvent("retry".to_string()),
+            ))));
+            let input = Rc::new(RefCell::new(TextInput::new(
+                world.borrow().slot.as_str(),
+                250.0,
+                assets.fonts.handel24.clone(),
+                Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 30.0 }),
+            )));
+            sprites.push(input.clone());
+            slot_input = Some(input);
+            sprites.push(Rc::new(RefCell::new(Button::text(
+                vec![(Key::A, None)],
+                "[a] Save as",
+                assets.fonts.consolab18.clone(),
+                Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 85.0 }),
+                Transition::CustomEvent("save_as".to_string()),
+            ))));
+            sprites.push(Rc::new(RefCell::new(Button::text(
+                vec![(Key::X, None)],
+                "[x] Quit without saving",
+                assets.fonts.consolab18.clone(),
+                Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 180.0 }),
+                Transition::DropWorld,
+            ))));
+            sprites.push(Rc::new(RefCell::new(Button::text(
+                vec![(Key::Escape, None)],
+                "[Esc] Back to game",
+                assets.fonts.consolab18.clone(),
+                Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 230.0 }),
+                Transition::Pop,
+            ))));
+        } else {
+            // main menu is always under this scene
+            sprites.push(Rc::new(RefCell::new(Button::text(
+                vec![(Key::X, None), (Key::Escape, None)],
+                "[x] Main menu",
+                assets.fonts.consolab18.clone(),
+                Position::horizontal_center(0.0, Vertical::AtWindowCenterByTop { offset: 180.0 }),
+                Transition::Pop,
+            ))));
+        }
+        Self {
+            sprites,
+            error,
+            world,
+            cause,
+            slot_input,
+        }
+    }
+
+    /// Main menu after a successful save, the reason is shown otherwise
+    fn saved(&self, ctx: &mut Context, result: Result<(), String>) -> Option<Transition> {
+        match result {
+            Ok(_) => Some(Transition::DropWorld),
+            Err(cause) => {
+                self.cause.borrow_mut().update(cause, ctx, window::get_size(ctx));
+                None
+            }
+        }
+    }
+}
+
+impl Scene for WorldErrorScene {
+    fn sprites(&mut self) -> Option<&Vec<Rc<RefCell<dyn UiSprite>>>> {
+        Some(&self.sprites)
+    }
+
+    fn custom_event(&mut self, ctx: &mut Context, event: &str) -> Option<Transition> {
+        match event {
+            "retry" => {
+                let result = self.world.as_ref()?.borrow().save();
+                self.saved(ctx, result.map_err(|e| e.to_string()))
+            }
+            "save_as" => {
+                let slot = self.slot_input.as_ref()?.borrow().value();
+                let result = save_as(&mut self.world.as_ref()?.borrow_mut(), &slot);
+                self.saved(ctx, result.map(|_| ()))
+            }
+            "backup" => {
+                let path = self.error.path().to_path_buf();
+                // if the backup is broken too the next one is offered
+                Some(match restore_backup(&path).and_then(|_| load(&path)) {
+                    Ok(savefile) if savefile.has_character() => Transition::LoadWorld(savefile),
+                    Ok(savefile) => Transition::Replace(GameScene::CreateCharacter(savefile)),
+                    Err(LoadError::NoBackup) => Transition::Pop,
+                    Err(e) => Transition::WorldError(WorldError::Load(path, e)),
+                })
+            }
+            _ => unreachable!(),
+        }
+    }
+}
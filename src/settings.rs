@@ -1,3 +1,4 @@
+use crate::savefile::MAX_BACKUPS;
 use once_cell::sync::OnceCell;
 use std::fs::File;
 use std::io::BufReader;
@@ -30,15 +31,26 @@ pub struct Debug {
     // TODO: debug log, backtrace, god-mode, etc.
 }
 
+/// Settings added later are taken from `Default` when they're missing in the file
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
+#[serde(default)]
 pub struct Game {
     pub repeat_interval: u32,
+    /// Gzip savefiles, they are much smaller but can't be read by hand
+    pub compress_saves: bool,
+    /// Ticks between autosaves, 0 turns them off
+    pub autosave_interval: u32,
+    /// Previous versions of the savefile kept on every save
+    pub backups: u32,
 }
 
 impl Default for Game {
     fn default() -> Self {
         Self {
             repeat_interval: 125,
+            compress_saves: false,
+            autosave_interval: 36_000,
+            backups: 3,
         }
     }
 }
@@ -67,6 +79,7 @@ impl Settings {
         self.window.width = self.window.width.clamp(800, 1920);
         self.window.height = self.window.height.clamp(600, 1280);
         self.game.repeat_interval = self.game.repeat_interval.clamp(1, 1000);
+        self.game.backups = self.game.backups.min(MAX_BACKUPS);
     }
 }
 
@@ -100,6 +113,7 @@ fn save(settings: &Settings, path: &'static str) {
 #[cfg(test)]
 mod tests {
     use super::{load, save};
+    use crate::savefile::MAX_BACKUPS;
 
     const TEST_PATH: &str = "./settings-test.json";
 
@@ -122,10 +136,12 @@ mod tests {
         settings.window.width = 123;
         settings.window.height = 456;
         settings.game.repeat_interval = 0;
+        settings.game.backups = 100;
         settings.validate();
 
         assert_eq!(800, settings.window.width);
         assert_eq!(600, settings.window.height);
         assert_eq!(1, settings.game.repeat_interval);
+        assert_eq!(MAX_BACKUPS, settings.game.backups);
     }
 }
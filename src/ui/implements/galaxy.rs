@@ -1,4 +1,5 @@
 use crate::assets::PreparedFont;
+use crate::astro::galaxy_stats::GalaxyStats;
 use crate::colors::Colors;
 use crate::scenes::Transition;
 use crate::ui::{Draw, Focus, Position, Positionate, UiSprite, Update};
@@ -9,6 +10,35 @@ use tetra::graphics::{Canvas, DrawParams, Rectangle, Texture, TextureFormat};
 use tetra::input::{Key, MouseButton};
 use tetra::{graphics, input, window, Context};
 
+/// RGBA pixels of the density map, one pixel per quadrant, rows go by y
+pub(crate) fn galaxy_pixels(size: usize, quadrants: &[u32]) -> Vec<u8> {
+    // TODO: use some textures for beauty
+    let mut data = Vec::with_capacity(size * size * 4);
+    let max = 262_144.0f32;
+    for y in 0..size {
+        for x in 0..size {
+            let i = x * size + y;
+            let d = quadrants[i] as f32 / max;
+            data.push(255);
+            data.push(255);
+            data.push(224);
+            data.push((255.0 * d).round() as u8);
+        }
+    }
+    data
+}
+
+pub(crate) fn galaxy_texture(ctx: &mut Context, size: usize, quadrants: &[u32]) -> Texture {
+    Texture::from_data(
+        ctx,
+        size as i32,
+        size as i32,
+        TextureFormat::Rgba8,
+        galaxy_pixels(size, quadrants).as_slice(),
+    )
+    .unwrap()
+}
+
 fn draw_galaxy(
     ctx: &mut Context,
     size: usize,
@@ -32,27 +62,7 @@ fn draw_galaxy(
     .unwrap();
     mesh.draw(ctx, DrawParams::new().color(Colors::SPACE_VIOLET));
 
-    // TODO: use some textures for beauty
-    let mut data = Vec::with_capacity(size * size * 4);
-    let max = 262_144.0f32;
-    for y in 0..size {
-        for x in 0..size {
-            let i = x * size + y;
-            let d = quadrants[i] as f32 / max;
-            data.push(255);
-            data.push(255);
-            data.push(224);
-            data.push((255.0 * d).round() as u8);
-        }
-    }
-    let texture = Texture::from_data(
-        ctx,
-        size as i32,
-        size as i32,
-        TextureFormat::Rgba8,
-        data.as_slice(),
-    )
-    .unwrap();
+    let texture = galaxy_texture(ctx, size, quadrants);
     texture.draw(ctx, DrawParams::new().scale(Vec2::new(zoom, zoom)));
     let mesh = Mesh::rounded_rectangle(
         ctx,
@@ -74,21 +84,30 @@ fn draw_galaxy(
             ))
             .color(Colors::ORANGE),
     );
-    let sum: u64 = quadrants.iter().copied().map(u64::from).sum();
-    let mut text = Text::new(
-        format!("{} stars in {}x{} quadrants", sum, size, size),
-        font_bottom.font,
-    );
-    let bounds = text.get_bounds(ctx).unwrap();
-    text.draw(
-        ctx,
-        DrawParams::new()
-            .position(Vec2::new(
-                (pixel_size as f32) / 2.0 - bounds.width / 2.0,
-                pixel_size as f32 - bounds.height - 10.0,
-            ))
-            .color(Colors::LIGHT_GRAY),
-    );
+    let stats = GalaxyStats::new(size, quadrants);
+    let lines = [
+        format!("{} stars in {}x{} quadrants", stats.total_stars, size, size),
+        format!(
+            "Radius: {}, core radius: {}, arms: {}",
+            stats.radius, stats.core_radius, stats.arms
+        ),
+    ];
+    let mut bottom = pixel_size as f32 - 10.0;
+    for line in lines.iter().rev() {
+        let mut text = Text::new(line, font_bottom.font.clone());
+        let bounds = text.get_bounds(ctx).unwrap();
+        bottom -= bounds.height;
+        text.draw(
+            ctx,
+            DrawParams::new()
+                .position(Vec2::new(
+                    (pixel_size as f32) / 2.0 - bounds.width / 2.0,
+                    bottom,
+                ))
+                .color(Colors::LIGHT_GRAY),
+        );
+        bottom -= 5.0;
+    }
 
     graphics::reset_canvas(ctx);
     canvas
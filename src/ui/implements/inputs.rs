@@ -83,6 +83,15 @@ impl TextInput {
         s
     }
 
+    /// Changes limits of the int input, current value is clamped to them
+    pub fn set_clamps(&mut self, clamps: (u32, u32)) {
+        if let ValueType::Unsigned { min, max } = &mut self.value_type {
+            *min = clamps.0;
+            *max = clamps.1;
+        }
+        self.validate_value();
+    }
+
     fn border_color(&self) -> Color {
         if self.is_danger {
             Colors::RED
@@ -6,3 +6,4 @@ pub(crate) mod inputs;
 pub(crate) mod label;
 pub(crate) mod meshy;
 pub(crate) mod ship;
+pub(crate) mod star_map;
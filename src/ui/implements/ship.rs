@@ -10,17 +10,25 @@ use tetra::graphics::mesh::{GeometryBuilder, Mesh, ShapeStyle};
 use tetra::graphics::{Canvas, DrawParams, Rectangle};
 use tetra::{graphics, window, Context};
 
-fn draw_ship(ctx: &mut Context, ship: &Ship, avatar: &Avatar, tileset: &TileSet) -> Canvas {
+/// Draws only the deck the avatar is on, with crew members the avatar can see
+fn draw_ship(
+    ctx: &mut Context,
+    ship: &Ship,
+    avatar: &Avatar,
+    crew: &[Avatar],
+    tileset: &TileSet,
+) -> Canvas {
     let canvas_size = (
         TileSet::TILE_SIZE.0 * ship.bounds.0,
         TileSet::TILE_SIZE.1 * ship.bounds.1,
     );
+    let deck = ship.deck(avatar.deck).or_else(|| ship.deck(0)).unwrap();
     let canvas = Canvas::new(ctx, canvas_size.0, canvas_size.1).unwrap();
     graphics::set_canvas(ctx, &canvas);
 
     let mut bg_builder = GeometryBuilder::new();
     let bg_color = Colors::SPACE_VIOLET;
-    for (i, tile) in ship.tiles.iter().enumerate() {
+    for (i, tile) in deck.tiles.iter().enumerate() {
         if tile.is_void() {
             continue;
         }
@@ -57,9 +65,9 @@ fn draw_ship(ctx: &mut Context, ship: &Ship, avatar: &Avatar, tileset: &TileSet)
     )
     .unwrap();
 
-    let fov = field_of_view_set(avatar.pos, ship.bounds.0.max(ship.bounds.1) as u32, ship);
+    let fov = field_of_view_set(avatar.pos, ship.bounds.0.max(ship.bounds.1) as u32, &deck);
     let mut fov_builder = GeometryBuilder::new();
-    for (i, tile) in ship.tiles.iter().enumerate() {
+    for (i, tile) in deck.tiles.iter().enumerate() {
         if tile.is_void() {
             continue;
         }
@@ -84,13 +92,23 @@ fn draw_ship(ctx: &mut Context, ship: &Ship, avatar: &Avatar, tileset: &TileSet)
                 mesh.draw(ctx, DrawParams::new().position(pos).color(color));
             }
         }
-        if avatar.pos == point {
+        let member = if avatar.pos == point {
+            Some(avatar)
+        } else {
+            crew.iter().find(|m| {
+                m.aboard == avatar.aboard
+                    && m.deck == avatar.deck
+                    && m.pos == point
+                    && fov.contains(&point)
+            })
+        };
+        if let Some(member) = member {
             tileset.draw(
                 ctx,
                 '@',
                 DrawParams::new()
                     .position(pos)
-                    .color(avatar.character.skin_tone.into()),
+                    .color(member.character.skin_tone.into()),
             );
         } else {
             tileset.draw(ctx, tile.ch, DrawParams::new().position(pos).color(tile.fg));
@@ -130,11 +148,12 @@ impl ShipView {
         ctx: &mut Context,
         ship: &Ship,
         avatar: &Avatar,
+        crew: &[Avatar],
         tileset: &TileSet,
         zoom: f32,
     ) -> Self {
         Self {
-            canvas: draw_ship(ctx, ship, avatar, tileset),
+            canvas: draw_ship(ctx, ship, avatar, crew, tileset),
             position: position(avatar.pos, zoom),
             rect: None,
             zoom,
@@ -153,10 +172,11 @@ impl ShipView {
         ctx: &mut Context,
         ship: &Ship,
         avatar: &Avatar,
+        crew: &[Avatar],
         tileset: &TileSet,
         window_size: (i32, i32),
     ) {
-        self.canvas = draw_ship(ctx, ship, avatar, tileset);
+        self.canvas = draw_ship(ctx, ship, avatar, crew, tileset);
         self.avatar_pos = avatar.pos;
         self.repositionate(ctx, window_size);
     }
@@ -0,0 +1,333 @@
+use crate::astro::factions::Territory;
+use crate::astro::galaxy::Galaxy;
+use crate::colors::Colors;
+use crate::input;
+use crate::scenes::Transition;
+use crate::ui::implements::galaxy::galaxy_texture;
+use crate::ui::{Draw, Focus, Position, Positionate, UiSprite, Update};
+use geometry::{Point, Rect, Vec2};
+use tetra::graphics::mesh::{Mesh, ShapeStyle};
+use tetra::graphics::{Color, DrawParams, FilterMode, Rectangle, Texture, TextureFormat};
+use tetra::input::MouseButton;
+use tetra::{window, Context};
+
+const MAX_ZOOM: f32 = 64.0;
+const MIN_FRAME_SIZE: f32 = 8.0;
+const FACTION_COLORS: [Color; 12] = [
+    Colors::RED,
+    Colors::DODGER_BLUE,
+    Colors::LIME,
+    Colors::GOLD,
+    Colors::MAGENTA,
+    Colors::CYAN,
+    Colors::DARK_ORANGE,
+    Colors::MEDIUM_PURPLE,
+    Colors::SPRING_GREEN,
+    Colors::HOT_PINK,
+    Colors::ROYAL_BLUE,
+    Colors::YELLOW_GREEN,
+];
+
+fn faction_color(id: u8) -> Color {
+    FACTION_COLORS[id as usize % FACTION_COLORS.len()]
+}
+
+/// RGBA pixels of the factions overlay, borders are brighter,
+/// contested quadrants are checkered with colors of both factions
+fn territory_pixels(galaxy: &Galaxy) -> Vec<u8> {
+    let size = galaxy.size();
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let point = Point::new(x as i32, y as i32);
+            let (color, alpha) = match galaxy.territory(point) {
+                Territory::Unclaimed => (Colors::TRANSPARENT, 0.0),
+                Territory::Contested(a, b) => {
+                    (faction_color(if (x + y) % 2 == 0 { a } else { b }), 0.45)
+                }
+                Territory::Owned(id) => (
+                    faction_color(id),
+                    if galaxy.is_border(point) { 0.6 } else { 0.25 },
+                ),
+            };
+            data.push((color.r * 255.0).round() as u8);
+            data.push((color.g * 255.0).round() as u8);
+            data.push((color.b * 255.0).round() as u8);
+            data.push((alpha * 255.0f32).round() as u8);
+        }
+    }
+    data
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Drag {
+    start: Vec2,
+    last: Vec2,
+    moved: bool,
+}
+
+/// Zoomable and draggable density map of the whole galaxy
+pub struct StarMap {
+    texture: Texture,
+    territories: Texture,
+    show_territories: bool,
+    size: usize,
+    zoom: f32,
+    center: Vec2, // in quadrants
+    ship: Point,
+    destination: Option<Point>,
+    hovered: Option<Point>,
+    drag: Option<Drag>,
+    position: Position,
+    rect: Option<Rect>,
+    visible: bool,
+}
+
+impl StarMap {
+    pub fn new(
+        ctx: &mut Context,
+        galaxy: &Galaxy,
+        ship: Point,
+        destination: Option<Point>,
+    ) -> Self {
+        let size = galaxy.size();
+        let stars: Vec<u32> = galaxy.quadrants.iter().map(|q| q.stars_count).collect();
+        let mut texture = galaxy_texture(ctx, size, &stars);
+        texture.set_filter_mode(ctx, FilterMode::Nearest);
+        let mut territories = Texture::from_data(
+            ctx,
+            size as i32,
+            size as i32,
+            TextureFormat::Rgba8,
+            territory_pixels(galaxy).as_slice(),
+        )
+        .unwrap();
+        territories.set_filter_mode(ctx, FilterMode::Nearest);
+        Self {
+            texture,
+            territories,
+            show_territories: false,
+            size,
+            zoom: 0.0,
+            center: Vec2::new(size as f32 / 2.0, size as f32 / 2.0),
+            ship,
+            destination,
+            hovered: None,
+            drag: None,
+            position: Position::by_left_top(0.0, 0.0),
+            rect: None,
+            visible: true,
+        }
+    }
+
+    pub fn hovered(&self) -> Option<Point> {
+        self.hovered
+    }
+
+    pub fn toggle_territories(&mut self) {
+        self.show_territories = !self.show_territories;
+    }
+
+    pub fn set_ship(&mut self, ship: Point) {
+        self.ship = ship;
+    }
+
+    pub fn set_destination(&mut self, destination: Option<Point>) {
+        self.destination = destination;
+    }
+
+    pub fn center_on(&mut self, point: Point) {
+        self.center = Vec2::new(point.x as f32 + 0.5, point.y as f32 + 0.5);
+    }
+
+    /// Moves the map by `delta` pixels
+    pub fn pan(&mut self, delta: Vec2) {
+        self.center -= delta / self.zoom;
+    }
+
+    /// Changes zoom keeping the quadrant under `anchor` in place
+    pub fn zoom_at(&mut self, factor: f32, anchor: Vec2) {
+        let offset = anchor - self.window_center();
+        let under = self.center + offset / self.zoom;
+        self.zoom = (self.zoom * factor).clamp(self.fit_zoom() / 2.0, MAX_ZOOM);
+        self.center = under - offset / self.zoom;
+    }
+
+    pub fn zoom_centered(&mut self, factor: f32) {
+        self.zoom_at(factor, self.window_center());
+    }
+
+    fn fit_zoom(&self) -> f32 {
+        let rect = self.rect.unwrap();
+        f32::min(rect.w, rect.h) / self.size as f32
+    }
+
+    fn window_center(&self) -> Vec2 {
+        let rect = self.rect.unwrap();
+        Vec2::new(rect.x + rect.w / 2.0, rect.y + rect.h / 2.0)
+    }
+
+    /// Left top corner of the quadrant on the screen
+    fn screen_pos(&self, point: Point) -> Vec2 {
+        self.window_center() + (Vec2::new(point.x as f32, point.y as f32) - self.center) * self.zoom
+    }
+
+    fn quadrant_at(&self, pos: Vec2) -> Option<Point> {
+        let pos = self.center + (pos - self.window_center()) / self.zoom;
+        let (x, y) = (pos.x.floor() as i32, pos.y.floor() as i32);
+        let size = self.size as i32;
+        if x < 0 || y < 0 || x >= size || y >= size {
+            None
+        } else {
+            Some(Point::new(x, y))
+        }
+    }
+
+    fn draw_frame(&self, ctx: &mut Context, point: Point, color: Color) {
+        let frame_size = self.zoom.max(MIN_FRAME_SIZE);
+        let shift = (self.zoom - frame_size) / 2.0;
+        let mesh = Mesh::rectangle(
+            ctx,
+            ShapeStyle::Stroke(2.0),
+            Rectangle::new(0.0, 0.0, frame_size, frame_size),
+        )
+        .unwrap();
+        mesh.draw(
+            ctx,
+            DrawParams::new()
+                .position(self.screen_pos(point) + Vec2::new(shift, shift))
+                .color(color),
+        );
+    }
+}
+
+impl Draw for StarMap {
+    fn draw(&mut self, ctx: &mut Context) {
+        self.texture.draw(
+            ctx,
+            DrawParams::new()
+                .position(self.screen_pos(Point::new(0, 0)))
+                .scale(Vec2::new(self.zoom, self.zoom)),
+        );
+        if self.show_territories {
+            self.territories.draw(
+                ctx,
+                DrawParams::new()
+                    .position(self.screen_pos(Point::new(0, 0)))
+                    .scale(Vec2::new(self.zoom, self.zoom)),
+            );
+        }
+        let half = Vec2::new(self.zoom / 2.0, self.zoom / 2.0);
+        if let Some(destination) = self.destination {
+            if let Ok(line) = Mesh::polyline(
+                ctx,
+                1.0,
+                &[
+                    self.screen_pos(self.ship) + half,
+                    self.screen_pos(destination) + half,
+                ],
+            ) {
+                line.draw(ctx, DrawParams::new().color(Colors::ORANGE.with_alpha(0.5)));
+            }
+            self.draw_frame(ctx, destination, Colors::ORANGE);
+        }
+        self.draw_frame(ctx, self.ship, Colors::LIME);
+        if let Some(hovered) = self.hovered {
+            self.draw_frame(ctx, hovered, Colors::LIGHT_YELLOW.with_alpha(0.7));
+        }
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+}
+
+impl Positionate for StarMap {
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn calc_size(&mut self, ctx: &mut Context) -> Vec2 {
+        let (width, height) = window::get_size(ctx);
+        Vec2::new(width as f32, height as f32)
+    }
+
+    fn rect(&self) -> Rect {
+        self.rect.unwrap()
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        let first_time = self.rect.is_none();
+        self.rect = Some(rect);
+        if first_time {
+            self.zoom = self.fit_zoom();
+            self.center_on(self.ship);
+        }
+    }
+}
+
+impl Update for StarMap {
+    fn update(
+        &mut self,
+        ctx: &mut Context,
+        _focused: bool,
+        blocked: &[Rect],
+    ) -> Option<Transition> {
+        let mouse = input::get_mouse_position(ctx);
+        let blocked = blocked.iter().any(|r| r.contains_point(mouse));
+        self.hovered = if blocked {
+            None
+        } else {
+            self.quadrant_at(mouse)
+        };
+        if !blocked {
+            if input::is_mouse_scrolled_up(ctx) {
+                self.zoom_at(1.25, mouse);
+            } else if input::is_mouse_scrolled_down(ctx) {
+                self.zoom_at(0.8, mouse);
+            }
+            if input::is_mouse_button_pressed(ctx, MouseButton::Left) {
+                self.drag = Some(Drag {
+                    start: mouse,
+                    last: mouse,
+                    moved: false,
+                });
+            }
+        }
+        if let Some(mut drag) = self.drag {
+            if input::is_mouse_button_down(ctx, MouseButton::Left) {
+                let distance = mouse - drag.start;
+                if distance.x.abs() + distance.y.abs() > 4.0 {
+                    drag.moved = true;
+                }
+                if drag.moved {
+                    self.pan(mouse - drag.last);
+                }
+                drag.last = mouse;
+                self.drag = Some(drag);
+            } else {
+                self.drag = None;
+                if !drag.moved && self.hovered.is_some() {
+                    return Some(Transition::CustomEvent("destination".to_string()));
+                }
+            }
+        }
+        None
+    }
+
+    fn block_mouse(&self) -> bool {
+        false
+    }
+}
+
+impl Focus for StarMap {}
+
+impl UiSprite for StarMap {}
@@ -1,3 +1,4 @@
+pub(crate) use self::implements::galaxy::galaxy_pixels;
 pub use self::implements::{
     bg::Bg,
     button::Button,
@@ -7,6 +8,7 @@ pub use self::implements::{
     label::Label,
     meshy::{HoverableMesh, JustMesh},
     ship::ShipView,
+    star_map::StarMap,
 };
 pub use self::position::{Horizontal, Position, Vertical};
 pub use self::traits::{